@@ -0,0 +1,20 @@
+use std::task::{Context, Poll};
+
+use rocpp_client::v16::{ChargePointCommand, CommandResult, Commands};
+
+pub struct CommandService {}
+
+impl CommandService {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Commands for CommandService {
+    async fn command_result(&mut self, command: ChargePointCommand, result: CommandResult) {
+        log::info!("command: {:?}, result: {:?}", command, result);
+    }
+    fn poll_command(&mut self, _cx: &mut Context<'_>) -> Poll<ChargePointCommand> {
+        Poll::Pending
+    }
+}
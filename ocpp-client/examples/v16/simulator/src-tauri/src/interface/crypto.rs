@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use rcgen::{CertificateParams, DnType, KeyPair};
+use rocpp_client::v16::{ClientCertificate, Crypto};
+use rocpp_core::v16::types::{CertificateHashData, HashAlgorithm};
+use sha2::{Digest, Sha256};
+use x509_parser::pem::parse_x509_pem;
+
+// stands in for a secure element, the charge point only ever sees the key handles
+#[derive(Clone)]
+pub struct KeyStore {
+    dir: PathBuf,
+}
+
+impl KeyStore {
+    pub fn new(dir: PathBuf) -> Self {
+        std::fs::create_dir_all(&dir).unwrap();
+        Self { dir }
+    }
+    fn store(&self, key_pair: &KeyPair) -> Option<String> {
+        let handle = sha256_hex(&key_pair.public_key_der())[..16].to_string();
+        std::fs::write(self.dir.join(format!("{}.pem", handle)), key_pair.serialize_pem()).ok()?;
+        Some(handle)
+    }
+    pub fn private_key(&self, handle: &str) -> Option<String> {
+        std::fs::read_to_string(self.dir.join(format!("{}.pem", handle))).ok()
+    }
+}
+
+pub struct CryptoService {
+    key_store: KeyStore,
+    key_pair: Option<KeyPair>,
+}
+
+impl CryptoService {
+    pub fn new(key_store: KeyStore) -> Self {
+        Self { key_store, key_pair: None }
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl Crypto for CryptoService {
+    async fn generate_csr(&mut self, organization: String, common_name: String) -> Option<String> {
+        let key_pair = KeyPair::generate().ok()?;
+        let mut params = CertificateParams::new(Vec::new()).ok()?;
+        params.distinguished_name.push(DnType::OrganizationName, organization);
+        params.distinguished_name.push(DnType::CommonName, common_name);
+        let csr = params.serialize_request(&key_pair).ok()?.pem().ok()?;
+        self.key_pair = Some(key_pair);
+        Some(csr)
+    }
+    async fn certificate_signed(&mut self, certificate_chain: String) -> Option<ClientCertificate> {
+        let key_pair = self.key_pair.as_ref()?;
+        let (_, pem) = parse_x509_pem(certificate_chain.as_bytes()).ok()?;
+        let leaf = pem.parse_x509().ok()?;
+        if leaf.public_key().raw != key_pair.public_key_der().as_slice() {
+            log::error!("signed certificate does not match the generated key");
+            return None;
+        }
+        let key_handle = self.key_store.store(key_pair)?;
+        Some(ClientCertificate {
+            certificate: certificate_chain,
+            key_handle,
+        })
+    }
+    async fn certificate_hash_data(&mut self, certificate: String) -> Option<CertificateHashData> {
+        let (_, pem) = parse_x509_pem(certificate.as_bytes()).ok()?;
+        let cert = pem.parse_x509().ok()?;
+        // installed certificates are roots, so the issuer key is the subject key
+        Some(CertificateHashData {
+            hash_algorithm: HashAlgorithm::Sha256,
+            issuer_name_hash: sha256_hex(cert.issuer().as_raw()),
+            issuer_key_hash: sha256_hex(&cert.public_key().subject_public_key.data),
+            serial_number: cert.raw_serial_as_string().replace(':', ""),
+        })
+    }
+    async fn verify_certificate(&mut self, certificate: String, trust_anchors: Vec<String>) -> bool {
+        let Ok((_, pem)) = parse_x509_pem(certificate.as_bytes()) else {
+            return false;
+        };
+        let Ok(cert) = pem.parse_x509() else {
+            return false;
+        };
+        trust_anchors.iter().any(|anchor| {
+            let Ok((_, anchor_pem)) = parse_x509_pem(anchor.as_bytes()) else {
+                return false;
+            };
+            let Ok(anchor) = anchor_pem.parse_x509() else {
+                return false;
+            };
+            cert.issuer() == anchor.subject()
+                && cert.validity().is_valid()
+                && cert.verify_signature(Some(anchor.public_key())).is_ok()
+        })
+    }
+}
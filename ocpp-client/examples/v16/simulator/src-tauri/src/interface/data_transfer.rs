@@ -0,0 +1,28 @@
+use std::task::{Context, Poll};
+
+use rocpp_client::v16::DataTransfer;
+use rocpp_core::v16::{
+    messages::data_transfer::{DataTransferRequest, DataTransferResponse},
+    types::DataTransferStatus,
+};
+
+pub struct DataTransferService {}
+
+impl DataTransferService {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl DataTransfer for DataTransferService {
+    async fn data_transfer(&mut self, req: DataTransferRequest) -> DataTransferResponse {
+        log::info!("data transfer request: {:?}", req);
+        DataTransferResponse { status: DataTransferStatus::UnknownVendorId, data: None }
+    }
+    async fn data_transfer_response(&mut self, res: Option<DataTransferResponse>) {
+        log::info!("data transfer response: {:?}", res);
+    }
+    fn poll_data_transfer(&mut self, _cx: &mut Context<'_>) -> Poll<DataTransferRequest> {
+        Poll::Pending
+    }
+}
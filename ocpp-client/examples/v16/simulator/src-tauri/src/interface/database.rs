@@ -1,4 +1,4 @@
-use rocpp_client::v16::KeyValueStore;
+use rocpp_client::v16::{DataLoss, DbError, KeyValueStore};
 use sled::Db;
 
 #[derive(Clone)]
@@ -15,10 +15,12 @@ impl DatabaseService {
     }
     pub async fn get_firmware_version(&mut self) -> String {
         self.db_get("firmware_version", "version").await
+            .ok()
+            .flatten()
             .unwrap_or("0.0.0").to_string()
     }
     pub async fn set_firmware_version(&mut self, version: String) {
-        self.db_transaction(
+        let _ = self.db_transaction(
             "firmware_version",
             vec![("version", Some(version.as_str()))],
         ).await;
@@ -26,59 +28,97 @@ impl DatabaseService {
 }
 
 impl KeyValueStore for DatabaseService {
-    async fn db_init(&mut self) {
+    async fn db_init(&mut self) -> Result<(), DbError> {
         // nothing needed here for sled
+        Ok(())
     }
 
-    async fn db_transaction(&mut self, table: &str, ops: Vec<(&str, Option<&str>)>) {
-        let tree = self.root.open_tree(table).unwrap();
+    async fn db_transaction(
+        &mut self,
+        table: &str,
+        ops: Vec<(&str, Option<&str>)>,
+    ) -> Result<(), DbError> {
+        let tree = self.root.open_tree(table).map_err(|_| DbError::Io)?;
+        let mut batch = sled::Batch::default();
         for (key, value) in ops {
             if let Some(value) = value {
-                let _ = tree.insert(key.as_bytes(), value.as_bytes());
+                batch.insert(key.as_bytes(), value.as_bytes());
             } else {
-                let _ = tree.remove(key.as_bytes());
+                batch.remove(key.as_bytes());
             }
         }
+        tree.apply_batch(batch).map_err(|_| DbError::Io)
     }
 
-    async fn db_get(&mut self, table: &str, key: &str) -> Option<&str> {
-        let tree = self.root.open_tree(table).ok()?;
-        let bytes = tree.get(key.as_bytes()).ok()??;
-        let s = String::from_utf8(bytes.to_vec()).ok()?;
+    async fn db_get(&mut self, table: &str, key: &str) -> Result<Option<&str>, DbError> {
+        let tree = self.root.open_tree(table).map_err(|_| DbError::Io)?;
+        let Some(bytes) = tree.get(key.as_bytes()).map_err(|_| DbError::Io)? else {
+            return Ok(None);
+        };
+        let s = String::from_utf8(bytes.to_vec()).map_err(|_| DbError::Corrupted)?;
         self.scratch = Some(s);
-        Some(self.scratch.as_ref().unwrap().as_str())
+        Ok(self.scratch.as_deref())
     }
 
-    async fn db_get_all(&mut self, table: &str) -> Vec<(&str, &str)> {
-        let tree = match self.root.open_tree(table) {
-            Ok(tree) => tree,
-            Err(_) => return vec![],
+    async fn db_get_all(&mut self, table: &str) -> Result<Vec<(&str, &str)>, DbError> {
+        let tree = self.root.open_tree(table).map_err(|_| DbError::Io)?;
+        self.scratch_all.clear();
+        for item in tree.iter() {
+            let (k_ivec, v_ivec) = item.map_err(|_| DbError::Io)?;
+            let key = String::from_utf8(k_ivec.to_vec()).map_err(|_| DbError::Corrupted)?;
+            let val = String::from_utf8(v_ivec.to_vec()).map_err(|_| DbError::Corrupted)?;
+            self.scratch_all.push((key, val));
+        }
+        Ok(self.scratch_all
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect())
+    }
+
+    async fn db_scan(
+        &mut self,
+        table: &str,
+        prefix: &str,
+        start_after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<(&str, &str)>, DbError> {
+        let tree = self.root.open_tree(table).map_err(|_| DbError::Io)?;
+        let iter = match start_after.filter(|start| *start >= prefix) {
+            Some(start) => tree.range::<&[u8], _>((
+                std::ops::Bound::Excluded(start.as_bytes()),
+                std::ops::Bound::Unbounded,
+            )),
+            None => tree.range::<&[u8], _>(prefix.as_bytes()..),
         };
         self.scratch_all.clear();
-        for item in tree.iter().filter_map(|res| res.ok()) {
-            let (k_ivec, v_ivec) = item;
-            if let (Ok(key), Ok(val)) = (
-                String::from_utf8(k_ivec.to_vec()),
-                String::from_utf8(v_ivec.to_vec()),
-            ) {
-                self.scratch_all.push((key, val));
+        for item in iter {
+            let (k_ivec, v_ivec) = item.map_err(|_| DbError::Io)?;
+            if !k_ivec.starts_with(prefix.as_bytes()) || self.scratch_all.len() == limit {
+                break;
             }
+            let key = String::from_utf8(k_ivec.to_vec()).map_err(|_| DbError::Corrupted)?;
+            let val = String::from_utf8(v_ivec.to_vec()).map_err(|_| DbError::Corrupted)?;
+            self.scratch_all.push((key, val));
         }
-        self.scratch_all
+        Ok(self.scratch_all
             .iter()
             .map(|(k, v)| (k.as_str(), v.as_str()))
-            .collect()
+            .collect())
+    }
+
+    async fn db_count_keys(&mut self, table: &str) -> Result<usize, DbError> {
+        self.root.open_tree(table).map(|t| t.len()).map_err(|_| DbError::Io)
     }
 
-    async fn db_count_keys(&mut self, table: &str) -> usize {
-        self.root.open_tree(table).map(|t| t.len()).unwrap_or(0)
+    async fn db_delete_table(&mut self, table: &str) -> Result<(), DbError> {
+        self.root.drop_tree(table).map(|_| ()).map_err(|_| DbError::Io)
     }
 
-    async fn db_delete_table(&mut self, table: &str) {
-        let _ = self.root.drop_tree(table);
+    async fn db_delete_all(&mut self) -> Result<(), DbError> {
+        self.root.clear().map_err(|_| DbError::Io)
     }
 
-    async fn db_delete_all(&mut self) {
-        let _ = self.root.clear();
+    async fn db_data_lost(&mut self, loss: DataLoss) {
+        log::error!("storage data lost: {:?}", loss);
     }
 }
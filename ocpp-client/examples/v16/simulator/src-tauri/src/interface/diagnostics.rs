@@ -35,6 +35,12 @@ impl Diagnostics for DiagnosticsService {
         self.upload_fut = Some(Box::pin(timed));
         
     }
+    async fn log_upload(&mut self, location: String, file_name: String, content: String, timeout: u64) {
+        let ftp = FtpService::new(location);
+        let inner_fut: Pin<Box<dyn Future<Output = Result<(), Error>> + Send>> = Box::pin(async move { ftp.put(file_name, content).await });
+        let timed = tokio::time::timeout(Duration::from_secs(timeout), inner_fut);
+        self.upload_fut = Some(Box::pin(timed));
+    }
     fn poll_diagnostics_upload(&mut self, cx: &mut Context<'_>) -> Poll<DiagnosticsResponse> {
         let timed_fut = match self.upload_fut.as_mut() {
             Some(fut) => fut,
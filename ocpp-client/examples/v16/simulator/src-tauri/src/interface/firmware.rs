@@ -1,8 +1,11 @@
 use std::{future::Future, pin::Pin, task::{Context, Poll}};
 
 use anyhow::Error;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use rocpp_client::v16::Firmware;
 use regex::Regex;
+use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_ASN1, RSA_PKCS1_2048_8192_SHA256};
+use x509_parser::pem::parse_x509_pem;
 
 use crate::interface::ftp::FtpService;
 
@@ -12,15 +15,31 @@ pub struct FirmwareService {
     db: DatabaseService,
     downloaded_firmware: Option<Vec<u8>>,
     download_fut: Option<Pin<Box<dyn Future<Output = Result<Vec<u8>, Error>> + Send>>>,
-    install_res: Option<bool>
+    install_res: Option<bool>,
+    verify_res: Option<bool>
 }
 
 impl FirmwareService {
     pub fn new(db: DatabaseService) -> Self {
-        Self { db, downloaded_firmware: None, download_fut: None, install_res: None }
+        Self { db, downloaded_firmware: None, download_fut: None, install_res: None, verify_res: None }
     }
 }
 
+// the signature is a base64 rsa or ecdsa sha256 signature over the downloaded image
+fn verify_signature(firmware: &[u8], signing_certificate: &str, signature: &str) -> bool {
+    let Ok(signature) = STANDARD.decode(signature.trim()) else {
+        return false;
+    };
+    let Ok((_, pem)) = parse_x509_pem(signing_certificate.as_bytes()) else {
+        return false;
+    };
+    let Ok(cert) = pem.parse_x509() else {
+        return false;
+    };
+    let public_key = &cert.public_key().subject_public_key.data;
+    UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, public_key).verify(firmware, &signature).is_ok()
+        || UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, public_key).verify(firmware, &signature).is_ok()
+}
 
 impl Firmware for FirmwareService { 
     async fn firmware_download(&mut self, location: String) {
@@ -44,6 +63,13 @@ impl Firmware for FirmwareService {
         };
         self.install_res = Some(res);
     }
+    async fn firmware_verify(&mut self, signing_certificate: String, signature: String) {
+        let res = match self.downloaded_firmware.as_ref() {
+            Some(firmware) => verify_signature(firmware, &signing_certificate, &signature),
+            None => false
+        };
+        self.verify_res = Some(res);
+    }
     fn poll_firmware_download(&mut self, cx: &mut Context<'_>) -> Poll<bool> {
         let fut = match self.download_fut.as_mut() {
             Some(t) => t,
@@ -73,4 +99,11 @@ impl Firmware for FirmwareService {
             Poll::Pending
         }
     }
+    fn poll_firmware_verify(&mut self, _cx: &mut Context<'_>) -> Poll<bool> {
+        if let Some(res) = self.verify_res.take() {
+            Poll::Ready(res)
+        } else {
+            Poll::Pending
+        }
+    }
 }
@@ -18,22 +18,21 @@ impl FtpService {
         file_name: String,
     ) -> anyhow::Result<()> {
         tokio::time::sleep(Duration::from_secs(2)).await;
-        match self.get_ftp_stream().await {
-            Ok((mut ftp_stream, path)) => {
-                if let Some(path) = path {
-                    ftp_stream.cwd(&path).await?;
-                }
-                let mut res = format!("LOGS START -----------\n");
-                // add custom logic
-                res += "LOGS END -----------";
-                let mut cursor = std::io::Cursor::new(res.as_bytes());
-                ftp_stream
-                    .put(&file_name, &mut cursor)
-                    .await
-                    .map_err(|e| e.into())
-            }
-            Err(e) => Err(e),
+        let mut res = format!("LOGS START -----------\n");
+        // add custom logic
+        res += "LOGS END -----------";
+        self.put(file_name, res).await
+    }
+    pub async fn put(&self, file_name: String, content: String) -> anyhow::Result<()> {
+        let (mut ftp_stream, path) = self.get_ftp_stream().await?;
+        if let Some(path) = path {
+            ftp_stream.cwd(&path).await?;
         }
+        let mut cursor = std::io::Cursor::new(content.as_bytes());
+        ftp_stream
+            .put(&file_name, &mut cursor)
+            .await
+            .map_err(|e| e.into())
     }
     pub async fn download(&self) -> anyhow::Result<Vec<u8>> {
         let (mut ftp_stream, path) = self.get_ftp_stream().await?;
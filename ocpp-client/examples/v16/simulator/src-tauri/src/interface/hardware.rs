@@ -1,8 +1,9 @@
 use std::{future::Future, pin::Pin, task::{Context, Poll}, time::Duration};
 
+use chrono::{DateTime, Utc};
 use flume::{r#async::RecvFut, Receiver};
 use futures_util::FutureExt;
-use rocpp_client::v16::{Hardware, HardwareEvent, MeterData, MeterDataType};
+use rocpp_client::v16::{ChargingLimit, Hardware, HardwareEvent, MeterData, MeterDataType};
 use rocpp_core::v16::types::{ChargePointStatus, Measurand};
 use tokio_util::sync::CancellationToken;
 
@@ -25,6 +26,10 @@ impl Hardware for HardwareService {
     async fn get_boot_time(&self) -> u64 {
         uptime_lib::get().unwrap().as_micros() as u64
     }
+    async fn get_rtc_time(&self) -> Option<DateTime<Utc>> {
+        // the host clock plays the rtc
+        Some(Utc::now())
+    }
     async fn hard_reset(&mut self) {
         let token = self.stop_token.clone();
         tokio::task::spawn_local(async move {
@@ -45,6 +50,16 @@ impl Hardware for HardwareService {
             _ => None,
         }
     }
+    async fn set_charging_limit(&mut self, connector_id: usize, limit: ChargingLimit) {
+        log::info!("charging limit for connector: {}, limit: {:?}", connector_id + 1, limit);
+    }
+    async fn has_cable_lock(&self, _connector_id: usize) -> bool {
+        true
+    }
+    async fn unlock_connector(&mut self, connector_id: usize) -> bool {
+        log::info!("unlock connector: {}", connector_id + 1);
+        true
+    }
     fn poll_hardware_events(&mut self, cx: &mut Context<'_>) -> Poll<HardwareEvent> {
         match self.ev_rx_fut.poll_unpin(cx) {
             Poll::Ready(t) => Poll::Ready(t.unwrap()),
@@ -1,6 +1,10 @@
+pub mod commands;
+pub mod crypto;
+pub mod data_transfer;
 pub mod database;
 pub mod firmware;
 pub mod log;
+pub mod observer;
 pub mod hardware;
 pub mod ui;
 pub mod diagnostics;
@@ -0,0 +1,15 @@
+use rocpp_client::v16::{ChargePointSnapshot, Observer, StateChange};
+
+pub struct ObserverService {}
+
+impl ObserverService {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Observer for ObserverService {
+    async fn state_changed(&mut self, change: StateChange, snapshot: ChargePointSnapshot) {
+        log::debug!("state change: {:?}, snapshot: {:?}", change, snapshot);
+    }
+}
@@ -1,22 +1,32 @@
-use core::{task::{Context, Poll}, time::Duration};
+use core::task::{Context, Poll};
 
 use flume::{r#async::RecvFut, unbounded, Receiver, Sender};
 use futures::{FutureExt, SinkExt, StreamExt};
-use rocpp_client::v16::{Websocket, WsEvent};
-use tokio_tungstenite::{connect_async, tungstenite::{client::IntoClientRequest, http::HeaderValue, Message}};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rocpp_client::v16::{Websocket, WsConnectOptions, WsEvent};
+use tokio_tungstenite::{connect_async_tls_with_config, tungstenite::{client::IntoClientRequest, http::{header::AUTHORIZATION, HeaderValue}, Message}, Connector};
 use tokio_util::sync::CancellationToken;
 
+use super::crypto::KeyStore;
+
+enum Outgoing {
+    Text(String),
+    Ping,
+    Close,
+}
 
 pub struct WsClient {
     ev_tx: Sender<WsEvent>,
     ev_rx: Receiver<WsEvent>,
     ev_rx_fut: RecvFut<'static, WsEvent>,
-    msg_tx: Sender<Option<String>>,
-    msg_rx: Receiver<Option<String>>
+    msg_tx: Sender<Outgoing>,
+    msg_rx: Receiver<Outgoing>,
+    conn_token: CancellationToken,
+    key_store: KeyStore,
 }
 
 impl WsClient {
-    pub fn new() -> Self {
+    pub fn new(key_store: KeyStore) -> Self {
         let (ev_tx, ev_rx) = unbounded();
         let (msg_tx, msg_rx) = unbounded();
         Self {
@@ -24,24 +34,34 @@ impl WsClient {
             ev_rx: ev_rx.clone(),
             ev_rx_fut: ev_rx.into_recv_async(),
             msg_tx,
-            msg_rx
+            msg_rx,
+            conn_token: CancellationToken::new(),
+            key_store,
         }
     }
 }
 
 impl Websocket for WsClient {
-    async fn ws_connect(&mut self, url: String) {
+    async fn ws_connect(&mut self, options: WsConnectOptions) {
+        // a pending attempt with older credentials must not connect behind our back
+        self.conn_token.cancel();
+        self.conn_token = CancellationToken::new();
         let _ = self.ev_rx.drain();
         let _ = self.msg_rx.drain();
         let ev_tx = self.ev_tx.clone();
         let msg_rx = self.msg_rx.clone();
-        tokio::spawn(ws_task(url, ev_tx, msg_rx));
+        let connector = tls_connector(&options, &self.key_store);
+        tokio::spawn(ws_task(options, connector, ev_tx, msg_rx, self.conn_token.clone()));
     }
     async fn ws_send(&mut self, msg: String) {
-        self.msg_tx.send_async(Some(msg)).await.unwrap();
+        self.msg_tx.send_async(Outgoing::Text(msg)).await.unwrap();
     }
     async fn ws_close(&mut self) {
-        self.msg_tx.send_async(None).await.unwrap();
+        self.conn_token.cancel();
+        self.msg_tx.send_async(Outgoing::Close).await.unwrap();
+    }
+    async fn ws_ping(&mut self) {
+        self.msg_tx.send_async(Outgoing::Ping).await.unwrap();
     }
     fn poll_ws_recv(&mut self, cx: &mut Context<'_>) -> Poll<WsEvent> {
         match self.ev_rx_fut.poll_unpin(cx) {
@@ -51,28 +71,73 @@ impl Websocket for WsClient {
     }
 }
 
-async fn ws_task(url: String, ev_tx: Sender<WsEvent>, msg_rx: Receiver<Option<String>>) {
-    let mut req = url.into_client_request().unwrap();
+fn tls_connector(options: &WsConnectOptions, key_store: &KeyStore) -> Option<Connector> {
+    if options.security_profile < 2 {
+        return None;
+    }
+    let mut builder = native_tls::TlsConnector::builder();
+    if let Some(ca) = &options.ca_certificate {
+        match native_tls::Certificate::from_pem(ca.as_bytes()) {
+            Ok(t) => {
+                builder.add_root_certificate(t);
+            }
+            Err(e) => log::error!("invalid ca certificate:{:?}", e),
+        }
+    }
+    if let Some(client) = &options.client_certificate {
+        match key_store.private_key(&client.key_handle) {
+            Some(key) => match native_tls::Identity::from_pkcs8(client.certificate.as_bytes(), key.as_bytes()) {
+                Ok(t) => {
+                    builder.identity(t);
+                }
+                Err(e) => log::error!("invalid client certificate:{:?}", e),
+            },
+            None => log::error!("unknown key handle:{}", client.key_handle),
+        }
+    }
+    match builder.build() {
+        Ok(t) => Some(Connector::NativeTls(t)),
+        Err(e) => {
+            log::error!("tls error:{:?}", e);
+            None
+        }
+    }
+}
+
+async fn ws_task(options: WsConnectOptions, connector: Option<Connector>, ev_tx: Sender<WsEvent>, msg_rx: Receiver<Outgoing>, conn_token: CancellationToken) {
+    let mut req = options.url.as_str().into_client_request().unwrap();
     let headers = req.headers_mut();
     headers.insert(
         tokio_tungstenite::tungstenite::http::header::SEC_WEBSOCKET_PROTOCOL,
         HeaderValue::from_str("ocpp1.6").unwrap(),
     );
-    let stream = loop {
-        match connect_async(req.clone()).await {
-            Ok(t) => {
-                break t.0;
-            }
-            Err(e) => {
-                log::error!("ws error:{:?}", e);
-                tokio::time::sleep(Duration::from_secs(2)).await;
-            }
+    if let Some(auth) = &options.basic_auth {
+        let credentials = STANDARD.encode(format!("{}:{}", auth.username, auth.password));
+        headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Basic {}", credentials)).unwrap());
+    }
+    // one attempt only, the charge point schedules the retries with its backoff
+    let connect = connect_async_tls_with_config(req, None, false, connector);
+    let res = tokio::select! {
+        _ = conn_token.cancelled() => {
+            return;
+        }
+        res = connect => res
+    };
+    let stream = match res {
+        Ok(t) => t.0,
+        Err(e) => {
+            log::error!("ws error:{:?}", e);
+            ev_tx.send_async(WsEvent::ConnectFailed).await.unwrap();
+            return;
         }
     };
     ev_tx.send_async(WsEvent::Connected).await.unwrap();
     let (mut ws_tx, mut ws_rx) = stream.split();
     let token = CancellationToken::new();
     let stop_token = token.clone();
+    // a half open connection never ends the read side, closing must not wait for it
+    let closed = CancellationToken::new();
+    let close_token = closed.clone();
     tokio::spawn(async move {
         loop {
             tokio::select! {
@@ -82,8 +147,12 @@ async fn ws_task(url: String, ev_tx: Sender<WsEvent>, msg_rx: Receiver<Option<St
                 msg = msg_rx.recv_async() => {
                     let msg = msg.unwrap();
                     let msg = match msg {
-                        Some(t) => Message::Text(t.into()),
-                        None => Message::Close(None)
+                        Outgoing::Text(t) => Message::Text(t.into()),
+                        Outgoing::Ping => Message::Ping(Default::default()),
+                        Outgoing::Close => {
+                            close_token.cancel();
+                            Message::Close(None)
+                        }
                     };
                     match ws_tx.send(msg).await {
                         Ok(_) => {},
@@ -96,7 +165,12 @@ async fn ws_task(url: String, ev_tx: Sender<WsEvent>, msg_rx: Receiver<Option<St
         }
     });
     loop {
-        let req = ws_rx.next().await;
+        let req = tokio::select! {
+            _ = closed.cancelled() => {
+                break;
+            }
+            req = ws_rx.next() => req
+        };
         if let Some(req) = req {
             match req {
                 Ok(t) => {
@@ -106,6 +180,9 @@ async fn ws_task(url: String, ev_tx: Sender<WsEvent>, msg_rx: Receiver<Option<St
                         }
                         Message::Ping(_) => {
 
+                        }
+                        Message::Pong(_) => {
+                            ev_tx.send_async(WsEvent::Pong).await.unwrap();
                         }
                         _ => {
                             break;
@@ -15,7 +15,10 @@ use log::LevelFilter;
 use rocpp_client::v16::{ChargePoint, ChargePointConfig, ChargePointInterfaceFacade, HardwareEvent};
 use tokio_util::sync::CancellationToken;
 
-use crate::interface::{diagnostics::DiagnosticsService, timers::TokioTimerServie, ws::WsClient};
+use crate::interface::{
+    commands::CommandService, crypto::{CryptoService, KeyStore}, data_transfer::DataTransferService, diagnostics::DiagnosticsService,
+    observer::ObserverService, timers::TokioTimerServie, ws::WsClient,
+};
 
 mod interface;
 
@@ -23,6 +26,7 @@ mod interface;
 async fn main() {
     let log_level = LevelFilter::Debug;
     let db_path = std::env::temp_dir().join("config.json");
+    let key_store = KeyStore::new(std::env::temp_dir().join("keys"));
 
     let mut configs: ChargePointConfig = {
         let raw = std::fs::read_to_string("config.json").expect("missing config.json");
@@ -73,7 +77,11 @@ async fn main() {
                             hardware_rx.clone(),
                         );
                         let timer = TokioTimerServie::new();
-                        let ws = WsClient::new();
+                        let ws = WsClient::new(key_store.clone());
+                        let data_transfer = DataTransferService::new();
+                        let commands = CommandService::new();
+                        let observer = ObserverService::new();
+                        let crypto = CryptoService::new(key_store.clone());
                         let _ = hardware_rx.drain();
 
                         let interface = ChargePointInterfaceFacade::new(
@@ -83,6 +91,10 @@ async fn main() {
                             timer,
                             hardware,
                             ws,
+                            data_transfer,
+                            commands,
+                            observer,
+                            crypto,
                         );
                         log::info!("ChargePoint Started");
                         ui.update_charger_state(true);
@@ -13,29 +13,33 @@ use rocpp_core::{
     v16::{
         messages::{
             boot_notification::BootNotificationRequest,
+            set_charging_profile::SetChargingProfileRequest,
             status_notification::StatusNotificationRequest,
         },
         protocol_error::ProtocolError,
-        types::{RegistrationStatus, ResetType},
+        types::{ChargingProfilePurposeType, RegistrationStatus, ResetType},
     },
 };
 
 use crate::v16::state_machine::{
     boot::BootState,
-    call::{CallAction, OutgoingCallState},
-    clock::Instant,
+    call::{CallAction, OutgoingCallState, RttEstimate},
+    certificate::InstalledCertificate,
+    clock::{ClockAnchor, Instant, MAX_CLOCK_ANCHORS},
     config::OcppConfigs,
     connector::{ConnectorState, StatusNotificationState},
     diagnostics::DiagnosticsState,
     firmware::FirmwareState,
     heartbeat::HeartbeatState,
     meter::MeterState,
+    ping::PingState,
     transaction::TransactionEventState,
 };
 
 use super::{
     interfaces::{
-        ChargePointBackend, ChargePointEvent, ChargePointInterface, HardwareEvent, WsEvent,
+        ChargePointBackend, ChargePointEvent, ChargePointInterface, ChargingLimit,
+        ClientCertificate, HardwareEvent, TimeSource, WsEvent,
     },
     state_machine::transaction::TransactionEvent,
 };
@@ -48,6 +52,34 @@ pub struct ChargePointConfig {
     pub default_ocpp_configs: Vec<(String, String)>,
     pub clear_db: bool,
     pub seed: u64,
+    #[serde(default)]
+    pub ca_certificate: Option<String>,
+    #[serde(default)]
+    pub client_certificate: Option<ClientCertificate>,
+    #[serde(default)]
+    pub smart_charging: SmartChargingConfig,
+}
+
+// parameters of the composite schedule engine, they are local to the charge point
+// and not part of the configuration the central system sees
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct SmartChargingConfig {
+    // the limits applied where no charging profile limits a connector
+    pub default_limit_amps: f32,
+    pub default_limit_watts: f32,
+    pub default_number_phases: i32,
+    pub supply_voltage: f32,
+}
+
+impl Default for SmartChargingConfig {
+    fn default() -> Self {
+        Self {
+            default_limit_amps: 32.0,
+            default_limit_watts: 22080.0,
+            default_number_phases: 3,
+            supply_voltage: 230.0,
+        }
+    }
 }
 
 pub(crate) type OcppError = rocpp_core::format::error::OcppError<ProtocolError>;
@@ -56,15 +88,33 @@ pub struct ChargePoint<I: ChargePointInterface> {
     pub(crate) interface: ChargePointBackend<I>,
     pub(crate) rng: SmallRng,
     pub(crate) cms_url: String,
+    pub(crate) ca_certificate: Option<String>,
+    pub(crate) client_certificate: Option<ClientCertificate>,
+    pub(crate) security_profile_fallback: Option<u8>,
+    pub(crate) installed_certificates: Vec<InstalledCertificate>,
     pub(crate) boot_info: BootNotificationRequest,
     pub(crate) ws_connected: bool,
+    pub(crate) reconnect_attempts: u32,
     pub(crate) call_timeout: u64,
+    pub(crate) call_rtt: Option<RttEstimate>,
+    pub(crate) smart_charging: SmartChargingConfig,
     pub(crate) outgoing_call_state: OutgoingCallState,
-    pub(crate) pending_calls: VecDeque<(Call, CallAction)>,
+    // each call with the number of times it went out on the socket
+    pub(crate) pending_calls: VecDeque<(Call, CallAction, u32)>,
+    // calls their delivery policy holds across a disconnect
+    pub(crate) undelivered_calls: VecDeque<(Call, CallAction, u32)>,
+    // UntilDelivered calls by unique id with their index in the db
+    pub(crate) held_calls: BTreeMap<String, u64>,
+    pub(crate) held_call_head: u64,
     pub(crate) boot_state: BootState,
     pub(crate) registration_status: RegistrationStatus,
     pub(crate) heartbeat_state: HeartbeatState,
+    pub(crate) ping_state: PingState,
+    pub(crate) last_pong: Option<Instant>,
     pub(crate) base_time: Option<(DateTime<Utc>, Instant)>,
+    pub(crate) time_source: Option<TimeSource>,
+    pub(crate) boot_id: u64,
+    pub(crate) clock_anchors: BTreeMap<u64, ClockAnchor>,
     pub(crate) pending_auth_requests: VecDeque<(usize, String)>,
     pub(crate) local_list_entries_count: usize,
     pub(crate) connector_state: Vec<ConnectorState>,
@@ -75,18 +125,31 @@ pub struct ChargePoint<I: ChargePointInterface> {
     pub(crate) aligned_meter_state: MeterState,
     pub(crate) local_transaction_id: u32,
     pub(crate) active_local_transactions: Vec<Option<(u32, Option<i32>)>>,
+    pub(crate) active_transaction_start: Vec<Option<Instant>>,
     pub(crate) transaction_head: u64,
     pub(crate) transaction_tail: u64,
+    pub(crate) transaction_queue_len: u64,
+    // events merged into the current one, they are popped together with it
+    pub(crate) transaction_batch: Vec<u64>,
     pub(crate) transaction_map: BTreeMap<u32, i32>,
     pub(crate) transaction_connector_map: BTreeMap<u32, usize>,
     pub(crate) transaction_stop_meter_val_count: BTreeMap<u32, usize>,
     pub(crate) transaction_event_state: TransactionEventState,
     pub(crate) transaction_event_retries: u64,
     pub(crate) transacion_current_event: Option<TransactionEvent>,
+    pub(crate) security_log_start: u64,
+    pub(crate) security_event_tail: u64,
+    pub(crate) security_event_head: u64,
+    pub(crate) security_event_in_flight: Option<u64>,
     pub(crate) diagnostics_state: DiagnosticsState,
     pub(crate) firmware_state: FirmwareState,
+    pub(crate) firmware_request_id: Option<i32>,
+    pub(crate) charging_profiles: Vec<SetChargingProfileRequest>,
+    pub(crate) applied_charging_limits: Vec<Option<ChargingLimit>>,
     pub(crate) pending_reset: Option<ResetType>,
     pub(crate) soft_reset_now: bool,
+    pub(crate) pending_shutdown: bool,
+    pub(crate) shutdown_now: bool,
     pub(crate) configs: OcppConfigs,
 }
 
@@ -97,7 +160,11 @@ impl<I: ChargePointInterface> ChargePoint<I> {
     ) -> Self {
         let db_configs = interface.db_get_all_configs().await;
 
-        let ocpp_configs = OcppConfigs::build(db_configs);
+        let (ocpp_configs, invalid_configs) =
+            OcppConfigs::build(&configs.default_ocpp_configs, db_configs);
+        interface
+            .db_reset_configs(invalid_configs, &configs.default_ocpp_configs)
+            .await;
 
         let num_connectors = ocpp_configs.number_of_connectors.value;
 
@@ -108,26 +175,52 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             local_transaction_id,
             transaction_tail,
             transaction_head,
+            transaction_queue_len,
             transaction_map,
             transaction_connector_map,
             transaction_stop_meter_val_count,
             unfinished_transactions,
         ) = interface.db_get_transaction_data().await;
 
+        let (security_log_start, security_event_tail, security_event_head) =
+            interface.db_get_security_event_data().await;
+        let (boot_id, clock_anchors) = interface.db_next_boot(MAX_CLOCK_ANCHORS).await;
         let local_list_entries_count = interface.db_get_local_list_entries_count().await;
+        let charging_profiles = interface.db_get_charging_profiles().await;
+        let installed_certificates = interface.db_get_installed_certificates().await;
+        let client_certificate = interface
+            .db_get_client_certificate()
+            .await
+            .or(configs.client_certificate);
+        let security_profile_fallback = interface.db_get_security_profile_fallback().await;
         let mut res = Self {
             interface,
             rng: SmallRng::seed_from_u64(configs.seed),
             cms_url: configs.cms_url,
+            ca_certificate: configs.ca_certificate,
+            client_certificate,
+            security_profile_fallback,
+            installed_certificates,
             boot_info: configs.boot_info,
             ws_connected: false,
+            reconnect_attempts: 0,
             call_timeout: configs.call_timeout,
+            call_rtt: None,
+            smart_charging: configs.smart_charging,
             outgoing_call_state: OutgoingCallState::Idle,
             pending_calls: VecDeque::new(),
+            undelivered_calls: VecDeque::new(),
+            held_calls: BTreeMap::new(),
+            held_call_head: 0,
             boot_state: BootState::Idle,
             registration_status: RegistrationStatus::Rejected,
             heartbeat_state: HeartbeatState::Idle,
+            ping_state: PingState::Idle,
+            last_pong: None,
             base_time: None,
+            time_source: None,
+            boot_id,
+            clock_anchors,
             local_list_entries_count,
             pending_auth_requests: VecDeque::new(),
             connector_state,
@@ -141,22 +234,40 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             aligned_meter_state: MeterState::Idle,
             local_transaction_id,
             active_local_transactions: vec![None; num_connectors],
+            active_transaction_start: vec![None; num_connectors],
             transaction_head,
             transaction_tail,
+            transaction_queue_len,
+            transaction_batch: Vec::new(),
             transaction_map,
             transaction_connector_map,
             transaction_stop_meter_val_count,
             transaction_event_state: TransactionEventState::Idle,
             transaction_event_retries: 0,
             transacion_current_event: None,
+            security_log_start,
+            security_event_tail,
+            security_event_head,
+            security_event_in_flight: None,
             diagnostics_state: DiagnosticsState::Idle,
             firmware_state: FirmwareState::Idle,
+            firmware_request_id: None,
+            charging_profiles,
+            applied_charging_limits: vec![None; num_connectors],
             pending_reset: None,
             soft_reset_now: false,
+            pending_shutdown: false,
+            shutdown_now: false,
             configs: ocpp_configs,
         };
+        res.load_transaction_event().await;
+        res.load_held_calls().await;
         res.handle_unfinished_transactions(unfinished_transactions)
             .await;
+        res.clear_charging_profiles(|f| {
+            f.cs_charging_profiles.charging_profile_purpose == ChargingProfilePurposeType::TxProfile
+        })
+        .await;
         res
     }
 
@@ -169,11 +280,13 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         let mut soft_reset = false;
         loop {
             let event = poll_fn(|cx| {
-                if let Poll::Ready(_) = cp.interface.interface.poll_reset(cx) {
+                if cp.interface.interface.poll_reset(cx).is_ready() {
                     return Poll::Ready(ChargePointEvent::Reset);
                 }
-                if let Poll::Ready(t) = cp.interface.interface.poll_hardware_events(cx) {
-                    return Poll::Ready(ChargePointEvent::Hardware(t));
+                if !cp.pending_shutdown {
+                    if let Poll::Ready(t) = cp.interface.interface.poll_hardware_events(cx) {
+                        return Poll::Ready(ChargePointEvent::Hardware(t));
+                    }
                 }
                 if let Poll::Ready(t) = cp.interface.interface.poll_ws_recv(cx) {
                     return Poll::Ready(ChargePointEvent::Ws(t));
@@ -193,17 +306,26 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                             return Poll::Ready(ChargePointEvent::FirmwareInstall(res));
                         }
                     }
-                    _ => {}
-                }
-                match &cp.diagnostics_state {
-                    DiagnosticsState::Uploading(_) => {
-                        if let Poll::Ready(res) = cp.interface.interface.poll_diagnostics_upload(cx)
-                        {
-                            return Poll::Ready(ChargePointEvent::Diagnostics(res));
+                    FirmwareState::Verifying(_) => {
+                        if let Poll::Ready(res) = cp.interface.interface.poll_firmware_verify(cx) {
+                            return Poll::Ready(ChargePointEvent::FirmwareVerify(res));
                         }
                     }
                     _ => {}
                 }
+                if let DiagnosticsState::Uploading(_) = &cp.diagnostics_state {
+                    if let Poll::Ready(res) = cp.interface.interface.poll_diagnostics_upload(cx) {
+                        return Poll::Ready(ChargePointEvent::Diagnostics(res));
+                    }
+                }
+                if !cp.pending_shutdown {
+                    if let Poll::Ready(req) = cp.interface.interface.poll_data_transfer(cx) {
+                        return Poll::Ready(ChargePointEvent::DataTransfer(req));
+                    }
+                    if let Poll::Ready(command) = cp.interface.interface.poll_command(cx) {
+                        return Poll::Ready(ChargePointEvent::Command(command));
+                    }
+                }
                 Poll::Pending
             })
             .await;
@@ -225,21 +347,42 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     }
                 }
                 ChargePointEvent::Ws(ev) => match ev {
+                    // events breaking the order documented on WsEvent are dropped
+                    WsEvent::Connected if cp.ws_connected => {
+                        log::warn!("ignoring ws connected while already connected");
+                    }
                     WsEvent::Connected => {
                         log::info!("ws connected");
-                        assert!(!cp.ws_connected);
                         cp.ws_connected().await;
                     }
+                    WsEvent::Disconnected if !cp.ws_connected => {
+                        log::warn!("ignoring ws disconnected while not connected");
+                    }
                     WsEvent::Disconnected => {
                         log::info!("ws disconnected");
-                        assert!(cp.ws_connected);
                         cp.ws_disconnected().await;
                     }
+                    WsEvent::ConnectFailed if cp.ws_connected => {
+                        log::warn!("ignoring ws connect failure while connected");
+                    }
+                    WsEvent::ConnectFailed => {
+                        log::info!("ws connect failed");
+                        cp.ws_connect_failed().await;
+                    }
+                    WsEvent::Msg(msg) if !cp.ws_connected => {
+                        log::warn!("dropping ws message while not connected: {}", msg);
+                    }
                     WsEvent::Msg(msg) => {
                         log::info!("[MSG_IN] {}", msg);
-                        assert!(cp.ws_connected);
                         cp.got_ws_msg(msg).await;
                     }
+                    WsEvent::Pong if !cp.ws_connected => {
+                        log::warn!("ignoring ws pong while not connected");
+                    }
+                    WsEvent::Pong => {
+                        log::trace!("ws pong");
+                        cp.got_pong().await;
+                    }
                 },
                 ChargePointEvent::Timeout(id) => {
                     log::trace!("id timedout: {:?}", id);
@@ -253,20 +396,41 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     log::debug!("firmware install res: {}", res);
                     cp.firmware_install_response(res).await;
                 }
+                ChargePointEvent::FirmwareVerify(res) => {
+                    log::debug!("firmware verify res: {}", res);
+                    cp.firmware_verify_response(res).await;
+                }
                 ChargePointEvent::Diagnostics(res) => {
                     log::debug!("diagnostics upload res: {:?}", res);
                     cp.handle_diagnostics_response(res).await;
                 }
+                ChargePointEvent::DataTransfer(req) => {
+                    log::debug!("data transfer request: {:?}", req);
+                    cp.send_data_transfer(req).await;
+                }
+                ChargePointEvent::Command(command) => {
+                    log::info!("received command: {:?}", command);
+                    cp.handle_command(command).await;
+                }
             }
-            if cp.soft_reset_now {
+            if cp.soft_reset_now || cp.shutdown_now {
                 cp.interface.interface.ws_close().await;
                 cp.interface.interface.remove_all_timeouts().await;
-                loop {
+                // Disconnected only follows Connected, offline there is nothing to wait for
+                while cp.ws_connected {
                     let res = poll_fn(|cx| cp.interface.interface.poll_ws_recv(cx)).await;
                     if res == WsEvent::Disconnected {
                         break;
                     }
                 }
+                if cp.shutdown_now {
+                    // fail whatever is still queued so callers waiting on a response get one
+                    cp.ws_connected = false;
+                    cp.on_outgoing_offline().await;
+                    // held calls stay in the db and go out again after the next boot
+                    cp.fail_undelivered_calls().await;
+                    cp.interface.interface.remove_all_timeouts().await;
+                }
                 match &cp.firmware_state {
                     FirmwareState::Downloading(_) => {
                         let res =
@@ -280,15 +444,12 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     }
                     _ => {}
                 }
-                match &cp.diagnostics_state {
-                    DiagnosticsState::Uploading(_) => {
-                        let res =
-                            poll_fn(|cx| cp.interface.interface.poll_diagnostics_upload(cx)).await;
-                        cp.handle_diagnostics_response(res).await;
-                    }
-                    _ => {}
+                if let DiagnosticsState::Uploading(_) = &cp.diagnostics_state {
+                    let res =
+                        poll_fn(|cx| cp.interface.interface.poll_diagnostics_upload(cx)).await;
+                    cp.handle_diagnostics_response(res).await;
                 }
-                soft_reset = true;
+                soft_reset = !cp.shutdown_now;
                 break;
             }
         }
@@ -296,18 +457,19 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         (interface, soft_reset)
     }
 
-    pub async fn run(interface: I, mut configs: ChargePointConfig) {
+    pub async fn run(interface: I, mut configs: ChargePointConfig) -> I {
         let mut interface = ChargePointBackend::new(interface);
         interface
             .init(configs.default_ocpp_configs.clone(), configs.clear_db)
             .await;
         loop {
             let (i, soft_reset) = Self::run_once(interface, configs.clone()).await;
+            interface = i;
             if !soft_reset {
                 break;
             }
-            interface = i;
             configs.seed = configs.seed.saturating_add(1);
         }
+        interface.interface
     }
 }
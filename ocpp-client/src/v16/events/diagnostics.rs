@@ -12,7 +12,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             core::mem::replace(&mut self.diagnostics_state, DiagnosticsState::Idle)
         {
             if matches!(upload_status, DiagnosticsResponse::Success) {
-                self.send_diagnostics_status_notification(DiagnosticsStatus::Uploaded)
+                self.send_upload_status(t.log.as_ref(), DiagnosticsStatus::Uploaded)
                     .await;
             } else {
                 t.retry_left -= 1;
@@ -20,7 +20,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 self.try_diagnostrics_upload().await;
             }
         } else {
-            unreachable!();
+            log::warn!("ignoring diagnostics upload result while no upload is running");
         }
     }
 }
@@ -1,9 +1,12 @@
-use rocpp_core::v16::types::{FirmwareStatus, Reason, ResetType};
+use rocpp_core::v16::types::{Reason, ResetType, SignedFirmwareStatus};
 
 use crate::v16::{
     cp::ChargePoint,
     interfaces::{ChargePointInterface, TimerId},
-    state_machine::firmware::{FirmwareInstallStatus, FirmwareState},
+    state_machine::{
+        firmware::{FirmwareInstallStatus, FirmwareState},
+        security_event::SecurityEventType,
+    },
 };
 
 impl<I: ChargePointInterface> ChargePoint<I> {
@@ -11,19 +14,13 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         match core::mem::replace(&mut self.firmware_state, FirmwareState::Idle) {
             FirmwareState::Downloading(mut t) => match res {
                 true => {
-                    self.send_firmware_status_notification(FirmwareStatus::Downloaded)
+                    self.send_firmware_status_notification(SignedFirmwareStatus::Downloaded)
                         .await;
-                    if self.active_local_transactions.iter().all(|f| f.is_none()) {
-                        self.try_firmware_install().await;
-                    } else {
-                        self.firmware_state = FirmwareState::WaitingForTransactionToFinish;
-                        for connector_id in 0..self.configs.number_of_connectors.value {
-                            self.sync_connector_states(connector_id, None, None).await;
-                        }
-                    }
+                    self.firmware_downloaded(t).await;
                 }
                 false => {
                     t.retry_left -= 1;
+                    self.update_firmware_job(Some(&t)).await;
                     if t.retry_left > 0 && t.retry_interval > 0 {
                         self.add_timeout(TimerId::Firmware, t.retry_interval).await;
                         self.firmware_state = FirmwareState::DownloadSleep(t);
@@ -32,8 +29,31 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     }
                 }
             },
-            _ => {
-                unreachable!();
+            state => {
+                self.firmware_state = state;
+                log::warn!("ignoring firmware download result while not downloading");
+            }
+        }
+    }
+    pub(crate) async fn firmware_verify_response(&mut self, res: bool) {
+        match core::mem::replace(&mut self.firmware_state, FirmwareState::Idle) {
+            FirmwareState::Verifying(t) => match res {
+                true => {
+                    self.send_firmware_status_notification(SignedFirmwareStatus::SignatureVerified)
+                        .await;
+                    self.schedule_firmware_install(t.install_date).await;
+                }
+                false => {
+                    self.update_firmware_job(None).await;
+                    self.send_firmware_status_notification(SignedFirmwareStatus::InvalidSignature)
+                        .await;
+                    self.raise_security_event(SecurityEventType::InvalidFirmwareSignature, None)
+                        .await;
+                }
+            },
+            state => {
+                self.firmware_state = state;
+                log::warn!("ignoring firmware verification result while not verifying");
             }
         }
     }
@@ -43,7 +63,17 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             false => FirmwareInstallStatus::InstallationFailed,
         };
         self.firmware_state = FirmwareState::Idle;
-        self.interface.db_change_firmware_state(state).await;
+        self.interface
+            .db_change_firmware_state(state, self.firmware_request_id)
+            .await;
+        if res {
+            self.raise_security_event(SecurityEventType::FirmwareUpdated, None)
+                .await;
+            if self.firmware_request_id.is_some() {
+                self.send_firmware_status_notification(SignedFirmwareStatus::InstallRebooting)
+                    .await;
+            }
+        }
         self.reset(ResetType::Soft, Some(Reason::Reboot)).await;
     }
 }
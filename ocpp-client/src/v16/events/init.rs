@@ -1,7 +1,13 @@
-use crate::v16::{interfaces::ChargePointInterface, ChargePoint};
+use crate::v16::{
+    interfaces::ChargePointInterface, state_machine::security_event::SecurityEventType, ChargePoint,
+};
 
 impl<I: ChargePointInterface> ChargePoint<I> {
     pub(crate) async fn init(&mut self) {
-        self.connect(self.cms_url.clone()).await;
+        self.raise_security_event(SecurityEventType::StartupOfTheDevice, None)
+            .await;
+        self.restore_time().await;
+        self.restore_security_profile_fallback().await;
+        self.connect().await;
     }
 }
@@ -0,0 +1,37 @@
+use alloc::string::String;
+use rocpp_core::{
+    format::{frame::CallResult, message::EncodeDecode},
+    v16::{
+        messages::certificate_signed::{CertificateSignedRequest, CertificateSignedResponse},
+        types::CertificateSignedStatus,
+    },
+};
+
+use crate::v16::{cp::ChargePoint, interfaces::ChargePointInterface};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) async fn certificate_signed_ocpp(
+        &mut self,
+        unique_id: String,
+        req: CertificateSignedRequest,
+    ) {
+        let certificate = if self.certificate_chain_too_long(&req.certificate_chain) {
+            None
+        } else {
+            self.interface
+                .interface
+                .certificate_signed(req.certificate_chain)
+                .await
+        };
+        let status = match certificate {
+            Some(certificate) => {
+                self.update_client_certificate(certificate).await;
+                CertificateSignedStatus::Accepted
+            }
+            None => CertificateSignedStatus::Rejected,
+        };
+        let payload = CertificateSignedResponse { status };
+        let res = CallResult::new(unique_id, payload);
+        self.send_ws_msg(res.encode()).await;
+    }
+}
@@ -1,4 +1,4 @@
-use alloc::string::String;
+use alloc::{format, string::String};
 use rocpp_core::{
     format::{frame::CallResult, message::EncodeDecode},
     v16::{
@@ -10,7 +10,7 @@ use rocpp_core::{
 use crate::v16::{
     cp::ChargePoint,
     interfaces::{ChargePointBackend, ChargePointInterface},
-    state_machine::config::OcppConfig,
+    state_machine::{config::OcppConfig, security_event::SecurityEventType},
 };
 
 macro_rules! gen_update_ocpp_match {
@@ -32,7 +32,17 @@ impl<I: ChargePointInterface> ChargePoint<I> {
     ) {
         let key = req.key.as_str();
         let value = req.value;
+        if key == "SecurityProfile" {
+            return self.change_security_profile(unique_id, value).await;
+        }
         let res = config_key_map!(gen_update_ocpp_match, self, key, value);
+        if res.is_ok() && key == "AuthorizationKey" {
+            self.raise_security_event(
+                SecurityEventType::ReconfigurationOfSecurityParameters,
+                Some(req.key.clone()),
+            )
+            .await;
+        }
         let status = match res {
             Ok(t) => match t {
                 true => ConfigurationStatus::RebootRequired,
@@ -45,6 +55,27 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         let res = CallResult::new(unique_id, payload);
         self.send_ws_msg(res.encode()).await;
     }
+    async fn change_security_profile(&mut self, unique_id: String, value: String) {
+        let accepted = match value.parse() {
+            Ok(security_profile) => self.upgrade_security_profile(security_profile).await,
+            Err(_) => false,
+        };
+        let status = match accepted {
+            true => ConfigurationStatus::Accepted,
+            false => ConfigurationStatus::Rejected,
+        };
+        let payload = ChangeConfigurationResponse { status };
+        let res = CallResult::new(unique_id, payload);
+        self.send_ws_msg(res.encode()).await;
+        if accepted {
+            self.raise_security_event(
+                SecurityEventType::ReconfigurationOfSecurityParameters,
+                Some(format!("SecurityProfile {}", value)),
+            )
+            .await;
+            self.apply_security_profile().await;
+        }
+    }
     async fn config_update_helper<T>(
         &mut self,
         accessor: fn(&mut Self) -> (&mut OcppConfig<T>, &mut ChargePointBackend<I>),
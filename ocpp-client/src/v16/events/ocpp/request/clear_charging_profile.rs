@@ -1,6 +1,12 @@
 use alloc::string::String;
-use rocpp_core::v16::{
-    messages::clear_charging_profile::ClearChargingProfileRequest, protocol_error::ProtocolError,
+use rocpp_core::{
+    format::{frame::CallResult, message::EncodeDecode},
+    v16::{
+        messages::clear_charging_profile::{
+            ClearChargingProfileRequest, ClearChargingProfileResponse,
+        },
+        types::ClearChargingProfileStatus,
+    },
 };
 
 use crate::v16::{cp::ChargePoint, interfaces::ChargePointInterface};
@@ -9,9 +15,31 @@ impl<I: ChargePointInterface> ChargePoint<I> {
     pub(crate) async fn clear_charging_profile_ocpp(
         &mut self,
         unique_id: String,
-        _req: ClearChargingProfileRequest,
+        req: ClearChargingProfileRequest,
     ) {
-        self.send_error(unique_id, ProtocolError::NotImplemented)
+        let cleared = self
+            .clear_charging_profiles(|f| {
+                let profile = &f.cs_charging_profiles;
+                if let Some(id) = req.id {
+                    return profile.charging_profile_id == id;
+                }
+                req.connector_id.is_none_or(|t| t == f.connector_id)
+                    && req
+                        .charging_profile_purpose
+                        .as_ref()
+                        .is_none_or(|t| *t == profile.charging_profile_purpose)
+                    && req
+                        .stack_level
+                        .is_none_or(|t| t == profile.stack_level as i32)
+            })
             .await;
+        let status = if cleared {
+            ClearChargingProfileStatus::Accepted
+        } else {
+            ClearChargingProfileStatus::Unknown
+        };
+        let payload = ClearChargingProfileResponse { status };
+        let res = CallResult::new(unique_id, payload);
+        self.send_ws_msg(res.encode()).await;
     }
 }
@@ -1,24 +1,14 @@
 use alloc::string::String;
 use rocpp_core::{
     format::{frame::CallResult, message::EncodeDecode},
-    v16::{
-        messages::data_transfer::{DataTransferRequest, DataTransferResponse},
-        types::DataTransferStatus,
-    },
+    v16::messages::data_transfer::DataTransferRequest,
 };
 
 use crate::v16::{cp::ChargePoint, interfaces::ChargePointInterface};
 
 impl<I: ChargePointInterface> ChargePoint<I> {
-    pub(crate) async fn data_transfer_ocpp(
-        &mut self,
-        unique_id: String,
-        _req: DataTransferRequest,
-    ) {
-        let payload = DataTransferResponse {
-            status: DataTransferStatus::Rejected,
-            data: None,
-        };
+    pub(crate) async fn data_transfer_ocpp(&mut self, unique_id: String, req: DataTransferRequest) {
+        let payload = self.interface.interface.data_transfer(req).await;
         let res = CallResult::new(unique_id, payload);
         self.send_ws_msg(res.encode()).await;
     }
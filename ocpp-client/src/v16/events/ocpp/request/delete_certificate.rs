@@ -0,0 +1,20 @@
+use alloc::string::String;
+use rocpp_core::{
+    format::{frame::CallResult, message::EncodeDecode},
+    v16::messages::delete_certificate::{DeleteCertificateRequest, DeleteCertificateResponse},
+};
+
+use crate::v16::{cp::ChargePoint, interfaces::ChargePointInterface};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) async fn delete_certificate_ocpp(
+        &mut self,
+        unique_id: String,
+        req: DeleteCertificateRequest,
+    ) {
+        let status = self.delete_certificate(&req.certificate_hash_data).await;
+        let payload = DeleteCertificateResponse { status };
+        let res = CallResult::new(unique_id, payload);
+        self.send_ws_msg(res.encode()).await;
+    }
+}
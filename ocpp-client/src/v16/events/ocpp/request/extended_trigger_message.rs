@@ -0,0 +1,83 @@
+use alloc::string::String;
+use rocpp_core::{
+    format::{frame::CallResult, message::EncodeDecode},
+    v16::{
+        messages::extended_trigger_message::{
+            ExtendedTriggerMessageRequest, ExtendedTriggerMessageResponse,
+        },
+        types::{ExtendedMessageTrigger, RegistrationStatus, TriggerMessageStatus},
+    },
+};
+
+use crate::v16::{cp::ChargePoint, interfaces::ChargePointInterface};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) async fn extended_trigger_message_ocpp(
+        &mut self,
+        unique_id: String,
+        req: ExtendedTriggerMessageRequest,
+    ) {
+        let valid_connector_id = req
+            .connector_id
+            .map(|f| f <= self.configs.number_of_connectors.value)
+            .unwrap_or_else(|| {
+                !(matches!(
+                    req.requested_message,
+                    ExtendedMessageTrigger::MeterValues
+                        | ExtendedMessageTrigger::StatusNotification
+                ))
+            });
+        let valid_message = !(self.registration_status == RegistrationStatus::Pending
+            && req.requested_message == ExtendedMessageTrigger::MeterValues);
+
+        let mut csr = None;
+        let status = if !(valid_connector_id && valid_message) {
+            TriggerMessageStatus::Rejected
+        } else {
+            match req.requested_message {
+                ExtendedMessageTrigger::SignChargePointCertificate => {
+                    csr = self.generate_csr().await;
+                    match csr {
+                        Some(_) => TriggerMessageStatus::Accepted,
+                        None => TriggerMessageStatus::Rejected,
+                    }
+                }
+                _ => TriggerMessageStatus::Accepted,
+            }
+        };
+        let payload = ExtendedTriggerMessageResponse {
+            status: status.clone(),
+        };
+        let res = CallResult::new(unique_id, payload);
+        self.send_ws_msg(res.encode()).await;
+
+        if status != TriggerMessageStatus::Accepted {
+            return;
+        }
+        match req.requested_message {
+            ExtendedMessageTrigger::BootNotification => {
+                self.trigger_boot().await;
+            }
+            ExtendedMessageTrigger::FirmwareStatusNotification => {
+                self.trigger_signed_firmware_status_notification().await;
+            }
+            ExtendedMessageTrigger::Heartbeat => {
+                self.trigger_heartbeat().await;
+            }
+            ExtendedMessageTrigger::MeterValues => {
+                let connector_id = req.connector_id.unwrap();
+                self.trigger_meter_values(connector_id).await;
+            }
+            ExtendedMessageTrigger::StatusNotification => {
+                let connector_id = req.connector_id.unwrap();
+                self.trigger_status_notification(connector_id).await;
+            }
+            ExtendedMessageTrigger::SignChargePointCertificate => {
+                self.send_sign_certificate(csr.unwrap()).await;
+            }
+            ExtendedMessageTrigger::LogStatusNotification => {
+                self.trigger_log_status_notification().await;
+            }
+        }
+    }
+}
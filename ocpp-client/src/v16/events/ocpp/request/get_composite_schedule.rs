@@ -1,6 +1,12 @@
 use alloc::string::String;
-use rocpp_core::v16::{
-    messages::get_composite_schedule::GetCompositeScheduleRequest, protocol_error::ProtocolError,
+use rocpp_core::{
+    format::{frame::CallResult, message::EncodeDecode},
+    v16::{
+        messages::get_composite_schedule::{
+            GetCompositeScheduleRequest, GetCompositeScheduleResponse,
+        },
+        types::GetCompositeScheduleStatus,
+    },
 };
 
 use crate::v16::{cp::ChargePoint, interfaces::ChargePointInterface};
@@ -9,9 +15,35 @@ impl<I: ChargePointInterface> ChargePoint<I> {
     pub(crate) async fn get_composite_schedule_ocpp(
         &mut self,
         unique_id: String,
-        _req: GetCompositeScheduleRequest,
+        req: GetCompositeScheduleRequest,
     ) {
-        self.send_error(unique_id, ProtocolError::NotImplemented)
-            .await;
+        let charging_rate_unit = req
+            .charging_rate_unit
+            .unwrap_or_else(|| self.default_charging_rate_unit());
+        let schedule = if req.connector_id >= 0
+            && req.connector_id as usize <= self.configs.number_of_connectors.value
+            && req.duration > 0
+        {
+            self.get_composite_schedule(req.connector_id as usize, req.duration, charging_rate_unit)
+                .await
+        } else {
+            None
+        };
+        let payload = match schedule {
+            Some(schedule) => GetCompositeScheduleResponse {
+                status: GetCompositeScheduleStatus::Accepted,
+                connector_id: Some(req.connector_id),
+                schedule_start: schedule.start_schedule,
+                charging_schedule: Some(schedule),
+            },
+            None => GetCompositeScheduleResponse {
+                status: GetCompositeScheduleStatus::Rejected,
+                connector_id: None,
+                schedule_start: None,
+                charging_schedule: None,
+            },
+        };
+        let res = CallResult::new(unique_id, payload);
+        self.send_ws_msg(res.encode()).await;
     }
 }
@@ -34,6 +34,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                             retry_left,
                             retry_interval,
                             location: req.location,
+                            log: None,
                         };
                         (Some(t), Some(new_upload))
                     }
@@ -0,0 +1,35 @@
+use alloc::string::String;
+use rocpp_core::{
+    format::{frame::CallResult, message::EncodeDecode},
+    v16::{
+        messages::get_installed_certificate_ids::{
+            GetInstalledCertificateIdsRequest, GetInstalledCertificateIdsResponse,
+        },
+        types::GetInstalledCertificateStatus,
+    },
+};
+
+use crate::v16::{cp::ChargePoint, interfaces::ChargePointInterface};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) async fn get_installed_certificate_ids_ocpp(
+        &mut self,
+        unique_id: String,
+        req: GetInstalledCertificateIdsRequest,
+    ) {
+        let hashes = self.installed_certificate_hashes(&req.certificate_type);
+        let payload = if hashes.is_empty() {
+            GetInstalledCertificateIdsResponse {
+                certificate_hash_data: None,
+                status: GetInstalledCertificateStatus::NotFound,
+            }
+        } else {
+            GetInstalledCertificateIdsResponse {
+                certificate_hash_data: Some(hashes),
+                status: GetInstalledCertificateStatus::Accepted,
+            }
+        };
+        let res = CallResult::new(unique_id, payload);
+        self.send_ws_msg(res.encode()).await;
+    }
+}
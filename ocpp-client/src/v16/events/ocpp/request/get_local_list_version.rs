@@ -14,7 +14,10 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             if self.local_list_entries_count == 0 {
                 0
             } else {
-                self.interface.db_get_local_list_version().await.unwrap()
+                self.interface
+                    .db_get_local_list_version()
+                    .await
+                    .unwrap_or(0)
             }
         } else {
             -1
@@ -0,0 +1,69 @@
+use alloc::{format, string::String};
+use rocpp_core::{
+    format::{frame::CallResult, message::EncodeDecode},
+    v16::{
+        messages::get_log::{GetLogRequest, GetLogResponse},
+        types::{DiagnosticsStatus, LogStatus, LogType},
+    },
+};
+
+use crate::v16::{
+    cp::ChargePoint,
+    interfaces::ChargePointInterface,
+    state_machine::diagnostics::{DiagnosticsState, DiagnosticsUploadInfo, LogUploadInfo},
+};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) async fn get_log_ocpp(&mut self, unique_id: String, req: GetLogRequest) {
+        // uploads can not be cancelled, so a running one rejects the request
+        let log = match &self.diagnostics_state {
+            DiagnosticsState::Idle => self.prepare_log(&req).await,
+            DiagnosticsState::Uploading(_) => None,
+        };
+        let payload = GetLogResponse {
+            status: match log {
+                Some(_) => LogStatus::Accepted,
+                None => LogStatus::Rejected,
+            },
+            filename: log.as_ref().map(|t| t.file_name.clone()),
+        };
+        let res = CallResult::new(unique_id, payload);
+        self.send_ws_msg(res.encode()).await;
+
+        if let Some(log) = log {
+            let upload = DiagnosticsUploadInfo {
+                retry_left: req.retries.map(|t| t + 1).unwrap_or(1),
+                retry_interval: req.retry_interval.unwrap_or(0),
+                location: req.log.remote_location,
+                log: Some(log),
+            };
+            self.send_upload_status(upload.log.as_ref(), DiagnosticsStatus::Uploading)
+                .await;
+            self.diagnostics_state = DiagnosticsState::Uploading(upload);
+            self.try_diagnostrics_upload().await;
+        }
+    }
+    async fn prepare_log(&mut self, req: &GetLogRequest) -> Option<LogUploadInfo> {
+        let oldest = req.log.oldest_timestamp;
+        let latest = req.log.latest_timestamp;
+        match req.log_type {
+            LogType::SecurityLog => Some(LogUploadInfo {
+                request_id: req.request_id,
+                file_name: format!("security_log_{}.log", req.request_id),
+                content: Some(self.security_log(oldest, latest).await),
+            }),
+            LogType::DiagnosticsLog => {
+                let file_name = self
+                    .interface
+                    .interface
+                    .get_file_name(oldest, latest)
+                    .await?;
+                Some(LogUploadInfo {
+                    request_id: req.request_id,
+                    file_name,
+                    content: None,
+                })
+            }
+        }
+    }
+}
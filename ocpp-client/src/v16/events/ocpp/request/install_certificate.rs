@@ -0,0 +1,71 @@
+use alloc::string::String;
+use rocpp_core::{
+    format::{frame::CallResult, message::EncodeDecode},
+    v16::{
+        messages::install_certificate::{InstallCertificateRequest, InstallCertificateResponse},
+        types::CertificateStatus,
+    },
+};
+
+use crate::v16::{
+    cp::ChargePoint, interfaces::ChargePointInterface,
+    state_machine::certificate::InstalledCertificate,
+};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) async fn install_certificate_ocpp(
+        &mut self,
+        unique_id: String,
+        req: InstallCertificateRequest,
+    ) {
+        let hash_data = self
+            .interface
+            .interface
+            .certificate_hash_data(req.certificate.clone())
+            .await;
+        let Some(hash_data) = hash_data else {
+            self.send_install_certificate_response(unique_id, CertificateStatus::Rejected)
+                .await;
+            return;
+        };
+        // a root is self-signed, so it is checked against itself and the roots of its kind
+        let mut trust_anchors = self.installed_certificates_of(&req.certificate_type);
+        trust_anchors.push(req.certificate.clone());
+        let valid = self
+            .interface
+            .interface
+            .verify_certificate(req.certificate.clone(), trust_anchors)
+            .await;
+        // replacing an installed certificate does not take another slot
+        let replaces = self
+            .installed_certificates
+            .iter()
+            .any(|t| t.hash_data == hash_data);
+        let status = if !valid {
+            CertificateStatus::Failed
+        } else if !replaces
+            && self.installed_certificates.len() >= self.configs.certificate_store_max_length.value
+        {
+            CertificateStatus::Rejected
+        } else {
+            self.install_certificate(InstalledCertificate {
+                certificate_type: req.certificate_type,
+                certificate: req.certificate,
+                hash_data,
+            })
+            .await;
+            CertificateStatus::Accepted
+        };
+        self.send_install_certificate_response(unique_id, status)
+            .await;
+    }
+    async fn send_install_certificate_response(
+        &mut self,
+        unique_id: String,
+        status: CertificateStatus,
+    ) {
+        let payload = InstallCertificateResponse { status };
+        let res = CallResult::new(unique_id, payload);
+        self.send_ws_msg(res.encode()).await;
+    }
+}
@@ -1,19 +1,26 @@
 pub(crate) mod cancel_reservation;
+pub(crate) mod certificate_signed;
 pub(crate) mod change_availability;
 pub(crate) mod change_configuration;
 pub(crate) mod clear_cache;
 pub(crate) mod clear_charging_profile;
 pub(crate) mod data_transfer;
+pub(crate) mod delete_certificate;
+pub(crate) mod extended_trigger_message;
 pub(crate) mod get_composite_schedule;
 pub(crate) mod get_configuration;
 pub(crate) mod get_diagnostics;
+pub(crate) mod get_installed_certificate_ids;
 pub(crate) mod get_local_list_version;
+pub(crate) mod get_log;
+pub(crate) mod install_certificate;
 pub(crate) mod remote_start_transaction;
 pub(crate) mod remote_stop_transaction;
 pub(crate) mod reserve_now;
 pub(crate) mod reset;
 pub(crate) mod send_local_list;
 pub(crate) mod set_charging_profile;
+pub(crate) mod signed_update_firmware;
 pub(crate) mod trigger_message;
 pub(crate) mod unlock_connector;
 pub(crate) mod update_firmware;
@@ -19,24 +19,25 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         let connector_id = req.connector_id;
         let already_reserved = self.get_connector_with_reservation(req.reservation_id);
         let mut status = ReservationStatus::Rejected;
-        let current = self.get_time().await.unwrap();
-        let diff = req.expiry_date - current;
+        // without a clock the expiry can't be checked, so the reservation is rejected
+        let expires_in = self
+            .get_time()
+            .await
+            .map(|now| (req.expiry_date - now).num_seconds())
+            .unwrap_or(0);
         if connector_id >= 1
             && connector_id <= self.configs.number_of_connectors.value
             && already_reserved
                 .map(|f| f == connector_id - 1)
                 .unwrap_or(true)
-            && diff.num_seconds() > 0
+            && expires_in > 0
         {
             let connector_id = req.connector_id - 1;
             match &mut self.connector_state[connector_id] {
                 ConnectorState::Idle => {
                     self.interface.db_add_reservation(req.clone()).await;
-                    self.add_timeout(
-                        TimerId::Reservation(connector_id),
-                        diff.num_seconds() as u64,
-                    )
-                    .await;
+                    self.add_timeout(TimerId::Reservation(connector_id), expires_in as u64)
+                        .await;
                     send_status = Some((
                         connector_id,
                         ConnectorState::reserved(
@@ -71,11 +72,8 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                         *reservation_id_tag = req.id_tag;
                         *reservation_parent_id_tag = req.parent_id_tag;
                         status = ReservationStatus::Accepted;
-                        self.add_timeout(
-                            TimerId::Reservation(connector_id),
-                            diff.num_seconds() as u64,
-                        )
-                        .await;
+                        self.add_timeout(TimerId::Reservation(connector_id), expires_in as u64)
+                            .await;
                     } else {
                         status = ReservationStatus::Occupied;
                     }
@@ -50,11 +50,11 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 {
                     match id_tag_info {
                         Some(info) => {
-                            if !self
+                            if self
                                 .interface
                                 .db_get_from_local_list(&id_tag)
                                 .await
-                                .is_some()
+                                .is_none()
                             {
                                 net_delta += 1;
                             }
@@ -64,11 +64,11 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                             });
                         }
                         None => {
-                            if !self
+                            if self
                                 .interface
                                 .db_get_from_local_list(&id_tag)
                                 .await
-                                .is_some()
+                                .is_none()
                             {
                                 net_delta -= 1;
                                 changes.push(LocalListChange::Delete {
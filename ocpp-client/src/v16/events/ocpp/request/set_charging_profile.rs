@@ -1,6 +1,10 @@
 use alloc::string::String;
-use rocpp_core::v16::{
-    messages::set_charging_profile::SetChargingProfileRequest, protocol_error::ProtocolError,
+use rocpp_core::{
+    format::{frame::CallResult, message::EncodeDecode},
+    v16::{
+        messages::set_charging_profile::{SetChargingProfileRequest, SetChargingProfileResponse},
+        types::ChargingProfileStatus,
+    },
 };
 
 use crate::v16::{cp::ChargePoint, interfaces::ChargePointInterface};
@@ -9,9 +13,17 @@ impl<I: ChargePointInterface> ChargePoint<I> {
     pub(crate) async fn set_charging_profile_ocpp(
         &mut self,
         unique_id: String,
-        _req: SetChargingProfileRequest,
+        req: SetChargingProfileRequest,
     ) {
-        self.send_error(unique_id, ProtocolError::NotImplemented)
-            .await;
+        let status = if self.validate_charging_profile(req.connector_id, &req.cs_charging_profiles)
+            && self.add_charging_profile(req).await
+        {
+            ChargingProfileStatus::Accepted
+        } else {
+            ChargingProfileStatus::Rejected
+        };
+        let payload = SetChargingProfileResponse { status };
+        let res = CallResult::new(unique_id, payload);
+        self.send_ws_msg(res.encode()).await;
     }
 }
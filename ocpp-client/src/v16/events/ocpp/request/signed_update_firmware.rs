@@ -0,0 +1,70 @@
+use alloc::string::String;
+use rocpp_core::{
+    format::{frame::CallResult, message::EncodeDecode},
+    v16::{
+        messages::signed_update_firmware::{
+            SignedUpdateFirmwareRequest, SignedUpdateFirmwareResponse,
+        },
+        types::{CertificateUse, UpdateFirmwareStatus},
+    },
+};
+
+use crate::v16::{
+    cp::ChargePoint,
+    interfaces::ChargePointInterface,
+    state_machine::{
+        firmware::{FirmwareDownloadInfo, FirmwareState, SignedFirmwareInfo},
+        security_event::SecurityEventType,
+    },
+};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) async fn signed_update_firmware_ocpp(
+        &mut self,
+        unique_id: String,
+        req: SignedUpdateFirmwareRequest,
+    ) {
+        let status = if !matches!(self.firmware_state, FirmwareState::Idle) {
+            UpdateFirmwareStatus::Rejected
+        } else {
+            let trust_anchors =
+                self.installed_certificates_of(&CertificateUse::ManufacturerRootCertificate);
+            let valid = !trust_anchors.is_empty()
+                && self
+                    .interface
+                    .interface
+                    .verify_certificate(req.firmware.signing_certificate.clone(), trust_anchors)
+                    .await;
+            if valid {
+                UpdateFirmwareStatus::Accepted
+            } else {
+                UpdateFirmwareStatus::InvalidCertificate
+            }
+        };
+        let payload = SignedUpdateFirmwareResponse {
+            status: status.clone(),
+        };
+        let res = CallResult::new(unique_id, payload);
+        self.send_ws_msg(res.encode()).await;
+        if status == UpdateFirmwareStatus::InvalidCertificate {
+            self.raise_security_event(SecurityEventType::InvalidFirmwareSigningCertificate, None)
+                .await;
+        }
+        if status != UpdateFirmwareStatus::Accepted {
+            return;
+        }
+        self.firmware_request_id = Some(req.request_id);
+        let info = FirmwareDownloadInfo {
+            retry_left: req.retries.map(|t| t + 1).unwrap_or(1),
+            retry_interval: req.retry_interval.unwrap_or(0),
+            location: req.firmware.location,
+            retrieve_date: req.firmware.retrieve_date_time,
+            install_date: req.firmware.install_date_time,
+            signed: Some(SignedFirmwareInfo {
+                signing_certificate: req.firmware.signing_certificate,
+                signature: req.firmware.signature,
+            }),
+        };
+        self.schedule_firmware_download(info).await;
+    }
+}
@@ -3,21 +3,33 @@ use rocpp_core::{
     format::{frame::CallResult, message::EncodeDecode},
     v16::{
         messages::unlock_connector::{UnlockConnectorRequest, UnlockConnectorResponse},
-        types::UnlockStatus,
+        types::{Reason, UnlockStatus},
     },
 };
 
-use crate::v16::{cp::ChargePoint, interfaces::ChargePointInterface};
+use crate::v16::{
+    cp::ChargePoint, interfaces::ChargePointInterface, state_machine::connector::ConnectorState,
+};
 
 impl<I: ChargePointInterface> ChargePoint<I> {
     pub(crate) async fn unlock_connector_ocpp(
         &mut self,
         unique_id: String,
-        _req: UnlockConnectorRequest,
+        req: UnlockConnectorRequest,
     ) {
-        let payload = UnlockConnectorResponse {
-            status: UnlockStatus::NotSupported,
+        let connector_id = req.connector_id.wrapping_sub(1);
+        let status = if connector_id < self.configs.number_of_connectors.value
+            && self.interface.interface.has_cable_lock(connector_id).await
+        {
+            if let ConnectorState::Transaction { .. } = &self.connector_state[connector_id] {
+                self.stop_transaction(connector_id, None, Some(Reason::UnlockCommand))
+                    .await;
+            }
+            self.unlock_connector(connector_id).await
+        } else {
+            UnlockStatus::NotSupported
         };
+        let payload = UnlockConnectorResponse { status };
         let res = CallResult::new(unique_id, payload);
         self.send_ws_msg(res.encode()).await;
     }
@@ -1,15 +1,12 @@
 use alloc::string::String;
 use rocpp_core::{
     format::{frame::CallResult, message::EncodeDecode},
-    v16::{
-        messages::update_firmware::{UpdateFirmwareRequest, UpdateFirmwareResponse},
-        types::FirmwareStatus,
-    },
+    v16::messages::update_firmware::{UpdateFirmwareRequest, UpdateFirmwareResponse},
 };
 
 use crate::v16::{
     cp::ChargePoint,
-    interfaces::{ChargePointInterface, TimerId},
+    interfaces::ChargePointInterface,
     state_machine::firmware::{FirmwareDownloadInfo, FirmwareState},
 };
 
@@ -24,21 +21,15 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         self.send_ws_msg(res.encode()).await;
         match self.firmware_state {
             FirmwareState::Idle => {
-                let diff = req.retrieve_date - self.get_time().await.unwrap();
-                if diff.num_seconds() > 0 {
-                    self.firmware_state = FirmwareState::New(req);
-                    self.add_timeout(TimerId::Firmware, diff.num_seconds() as u64)
-                        .await;
-                } else {
-                    self.send_firmware_status_notification(FirmwareStatus::Downloading)
-                        .await;
-                    self.try_firmware_download(FirmwareDownloadInfo {
-                        retry_left: req.retries.map(|t| t + 1).unwrap_or(1),
-                        retry_interval: req.retry_interval.unwrap_or(0),
-                        location: req.location,
-                    })
-                    .await;
-                }
+                let info = FirmwareDownloadInfo {
+                    retry_left: req.retries.map(|t| t + 1).unwrap_or(1),
+                    retry_interval: req.retry_interval.unwrap_or(0),
+                    location: req.location,
+                    retrieve_date: req.retrieve_date,
+                    install_date: None,
+                    signed: None,
+                };
+                self.schedule_firmware_download(info).await;
             }
             _ => {
                 // already going on
@@ -25,7 +25,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 }
             }
         } else {
-            unreachable!();
+            log::warn!("authorize response without a pending request");
         }
     }
 }
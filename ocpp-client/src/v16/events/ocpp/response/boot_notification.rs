@@ -4,7 +4,7 @@ use rocpp_core::v16::{
 
 use crate::v16::{
     cp::{ChargePoint, OcppError},
-    interfaces::{ChargePointInterface, TimerId},
+    interfaces::{ChargePointInterface, StateChange, TimeSource, TimerId},
     state_machine::boot::BootState,
 };
 
@@ -19,9 +19,16 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 let backoff;
                 match res {
                     Ok(t) => {
-                        self.set_time(t.current_time).await;
+                        self.set_time(t.current_time, TimeSource::CentralSystem)
+                            .await;
                         self.registration_status = t.status;
                         backoff = t.interval;
+                        if self.registration_status != prev {
+                            self.notify_state_change(StateChange::RegistrationStatus(
+                                self.registration_status.clone(),
+                            ))
+                            .await;
+                        }
                     }
                     Err(e) => {
                         log::error!("boot_notification_response error: {:?}", e);
@@ -29,6 +36,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     }
                 }
                 if self.registration_status == RegistrationStatus::Accepted {
+                    self.reset_reconnect_backoff();
                     if backoff > 0 {
                         self.configs
                             .heartbeat_interval
@@ -49,8 +57,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 }
             }
             _ => {
-                // boot response received while not in WaitingForResponse state
-                unreachable!();
+                log::warn!("boot notification response while not waiting for one");
             }
         }
     }
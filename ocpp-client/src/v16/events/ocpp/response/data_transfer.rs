@@ -6,12 +6,17 @@ use crate::v16::{
 };
 
 impl<I: ChargePointInterface> ChargePoint<I> {
-    pub(crate) fn _data_transfer_response(&mut self, res: Result<DataTransferResponse, OcppError>) {
-        match res {
-            Ok(_) => {}
+    pub(crate) async fn data_transfer_response(
+        &mut self,
+        res: Result<DataTransferResponse, OcppError>,
+    ) {
+        let res = match res {
+            Ok(t) => Some(t),
             Err(e) => {
-                log::error!("boot_notification_response error: {:?}", e);
+                log::error!("data_transfer_response error: {:?}", e);
+                None
             }
-        }
+        };
+        self.interface.interface.data_transfer_response(res).await;
     }
 }
@@ -2,7 +2,7 @@ use rocpp_core::v16::messages::heart_beat::HeartbeatResponse;
 
 use crate::v16::{
     cp::{ChargePoint, OcppError},
-    interfaces::ChargePointInterface,
+    interfaces::{ChargePointInterface, TimeSource},
     state_machine::heartbeat::HeartbeatState,
 };
 
@@ -10,7 +10,8 @@ impl<I: ChargePointInterface> ChargePoint<I> {
     pub(crate) async fn heartbeat_response(&mut self, res: Result<HeartbeatResponse, OcppError>) {
         match res {
             Ok(t) => {
-                self.set_time(t.current_time).await;
+                self.set_time(t.current_time, TimeSource::CentralSystem)
+                    .await;
                 if let HeartbeatState::WaitingForResponse = &self.heartbeat_state {
                     self.set_sleep_heartbeat().await;
                 }
@@ -0,0 +1,20 @@
+use rocpp_core::v16::messages::log_status_notification::LogStatusNotificationResponse;
+
+use crate::v16::{
+    cp::{ChargePoint, OcppError},
+    interfaces::ChargePointInterface,
+};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) fn log_status_notification_response(
+        &mut self,
+        res: Result<LogStatusNotificationResponse, OcppError>,
+    ) {
+        match res {
+            Ok(_) => {}
+            Err(e) => {
+                log::error!("log_status_notification_response error: {:?}", e);
+            }
+        }
+    }
+}
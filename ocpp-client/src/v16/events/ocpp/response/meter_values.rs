@@ -14,7 +14,8 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         let local_transaction_id = match &self.transacion_current_event {
             Some(TransactionEvent::Meter(t)) => t.local_transaction_id,
             _ => {
-                unreachable!();
+                log::warn!("meter values response without a queued meter values event");
+                return;
             }
         };
         match res {
@@ -24,7 +25,9 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             }
             Err(e) => {
                 log::error!("meter_values_response error: {:?}", e);
-                if self.transaction_event_retries == self.configs.transaction_message_attempts.value
+                if !e.is_retryable()
+                    || self.transaction_event_retries
+                        == self.configs.transaction_message_attempts.value
                 {
                     self.pop_event(local_transaction_id, None, None).await;
                     self.process_transaction().await;
@@ -0,0 +1,30 @@
+use rocpp_core::v16::messages::security_event_notification::SecurityEventNotificationResponse;
+
+use crate::v16::{
+    cp::{ChargePoint, OcppError},
+    interfaces::ChargePointInterface,
+};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) async fn security_event_notification_response(
+        &mut self,
+        res: Result<SecurityEventNotificationResponse, OcppError>,
+    ) {
+        if let Some(index) = self.security_event_in_flight.take() {
+            match res {
+                Ok(_) => {
+                    self.security_event_delivered(index).await;
+                }
+                Err(OcppError::Protocol(e)) => {
+                    // the central system will not take it, retrying would block the queue
+                    log::error!("security_event_notification_response error: {:?}", e);
+                    self.security_event_delivered(index).await;
+                }
+                Err(e) => {
+                    log::warn!("security_event_notification_response error: {:?}", e);
+                }
+            }
+            self.process_security_events().await;
+        }
+    }
+}
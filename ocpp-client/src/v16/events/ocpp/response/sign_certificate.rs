@@ -0,0 +1,24 @@
+use rocpp_core::v16::{messages::sign_certificate::SignCertificateResponse, types::GenericStatus};
+
+use crate::v16::{
+    cp::{ChargePoint, OcppError},
+    interfaces::ChargePointInterface,
+};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) fn sign_certificate_response(
+        &mut self,
+        res: Result<SignCertificateResponse, OcppError>,
+    ) {
+        match res {
+            Ok(t) => {
+                if t.status == GenericStatus::Rejected {
+                    log::warn!("certificate signing request rejected");
+                }
+            }
+            Err(e) => {
+                log::error!("sign_certificate_response error: {:?}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use rocpp_core::v16::messages::signed_firmware_status_notification::SignedFirmwareStatusNotificationResponse;
+
+use crate::v16::{
+    cp::{ChargePoint, OcppError},
+    interfaces::ChargePointInterface,
+};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) fn signed_firmware_status_notification_response(
+        &mut self,
+        res: Result<SignedFirmwareStatusNotificationResponse, OcppError>,
+    ) {
+        match res {
+            Ok(_) => {}
+            Err(e) => {
+                log::error!(
+                    "signed_firmware_status_notification_response error: {:?}",
+                    e
+                );
+            }
+        }
+    }
+}
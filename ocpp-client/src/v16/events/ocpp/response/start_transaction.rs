@@ -14,7 +14,8 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         let (local_transaction_id, id_tag) = match &self.transacion_current_event {
             Some(TransactionEvent::Start(t)) => (t.local_transaction_id, t.id_tag.clone()),
             _ => {
-                unreachable!();
+                log::warn!("start transaction response without a queued start event");
+                return;
             }
         };
         match res {
@@ -30,7 +31,9 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             }
             Err(e) => {
                 log::error!("start_transaction_response error: {:?}", e);
-                if self.transaction_event_retries == self.configs.transaction_message_attempts.value
+                if !e.is_retryable()
+                    || self.transaction_event_retries
+                        == self.configs.transaction_message_attempts.value
                 {
                     self.pop_event(Some(local_transaction_id), None, None).await;
                     self.process_transaction().await;
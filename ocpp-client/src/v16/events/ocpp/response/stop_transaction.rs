@@ -14,13 +14,14 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         let (local_transaction_id, id_tag) = match &self.transacion_current_event {
             Some(TransactionEvent::Stop(t)) => (t.local_transaction_id, t.id_tag.clone()),
             _ => {
-                unreachable!();
+                log::warn!("stop transaction response without a queued stop event");
+                return;
             }
         };
         let meter_tx = self
             .transaction_stop_meter_val_count
             .get(&local_transaction_id)
-            .map(|f| *f)
+            .copied()
             .unwrap_or(0);
         match res {
             Ok(t) => {
@@ -33,7 +34,9 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             }
             Err(e) => {
                 log::error!("stop_transaction_response error: {:?}", e);
-                if self.transaction_event_retries == self.configs.transaction_message_attempts.value
+                if !e.is_retryable()
+                    || self.transaction_event_retries
+                        == self.configs.transaction_message_attempts.value
                 {
                     self.pop_event(Some(local_transaction_id), None, Some(meter_tx))
                         .await;
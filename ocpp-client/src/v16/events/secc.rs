@@ -141,6 +141,9 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     .await;
                     self.stop_transaction(connector_id, None, Some(Reason::EVDisconnected))
                         .await;
+                    if self.configs.unlock_connector_on_evside_disconnect.value {
+                        self.unlock_connector(connector_id).await;
+                    }
                     return;
                 }
                 self.change_connector_state_with_error_code(
@@ -170,6 +173,9 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                             info,
                         )
                         .await;
+                        if self.configs.unlock_connector_on_evside_disconnect.value {
+                            self.unlock_connector(connector_id).await;
+                        }
                     }
                     SeccState::Faulty => {
                         self.change_connector_state_with_error_code(
@@ -1,6 +1,6 @@
 use rocpp_core::{
     format::error::GenericError,
-    v16::types::{FirmwareStatus, ReadingContext},
+    v16::types::{ReadingContext, SignedFirmwareStatus},
 };
 
 use crate::v16::{
@@ -10,46 +10,47 @@ use crate::v16::{
         boot::BootState,
         call::OutgoingCallState,
         connector::{ConnectorState, StatusNotificationState},
-        firmware::{FirmwareDownloadInfo, FirmwareState},
+        firmware::FirmwareState,
         heartbeat::HeartbeatState,
         meter::MeterDataKind,
+        ping::PingState,
         transaction::TransactionEventState,
     },
 };
 
 impl<I: ChargePointInterface> ChargePoint<I> {
     pub(crate) async fn handle_timeout(&mut self, id: TimerId) {
-        self.remove_timeout(id.clone()).await;
+        self.remove_timeout(id).await;
         match id {
-            TimerId::Boot => {
-                match &self.boot_state {
-                    BootState::Sleeping => {
-                        self.send_boot_notification().await;
-                    }
-                    _ => {
-                        // boot expiry received while not in WaitingForResponse state
-                        unreachable!();
-                    }
+            TimerId::Boot => match &self.boot_state {
+                BootState::Sleeping => {
+                    self.send_boot_notification().await;
                 }
-            }
-            TimerId::Heartbeat => {
-                match &self.heartbeat_state {
-                    HeartbeatState::Sleeping => {
-                        self.send_heartbeat().await;
-                    }
-                    _ => {
-                        // heartbeat expiry received while not in Sleeping state
-                        unreachable!();
-                    }
+                _ => {
+                    log::warn!("ignoring stale timeout {:?}", id);
                 }
-            }
+            },
+            TimerId::Heartbeat => match &self.heartbeat_state {
+                HeartbeatState::Sleeping if self.may_skip_heartbeat().await => {
+                    self.set_sleep_heartbeat().await;
+                }
+                HeartbeatState::Sleeping => {
+                    self.send_heartbeat().await;
+                }
+                _ => {
+                    log::warn!("ignoring stale timeout {:?}", id);
+                }
+            },
             TimerId::Call => match &self.outgoing_call_state {
                 OutgoingCallState::WaitingForResponse { .. } => {
                     self.handle_call_response(Err(OcppError::Other(GenericError::TimeOut)), true)
                         .await;
                 }
-                _ => {
-                    unreachable!();
+                OutgoingCallState::Backoff => {
+                    self.on_call_backoff_timeout().await;
+                }
+                OutgoingCallState::Idle => {
+                    log::warn!("ignoring stale timeout {:?}", id);
                 }
             },
             TimerId::StatusNotification(connector_id) => {
@@ -58,7 +59,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                         self.send_status_notification(connector_id).await;
                     }
                     _ => {
-                        unreachable!();
+                        log::warn!("ignoring stale timeout {:?}", id);
                     }
                 }
             }
@@ -68,7 +69,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                         .await;
                 }
                 _ => {
-                    unreachable!();
+                    log::warn!("ignoring stale timeout {:?}", id);
                 }
             },
             TimerId::Reservation(connector_id) => match &self.connector_state[connector_id] {
@@ -88,28 +89,22 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     }
                 }
                 _ => {
-                    unreachable!();
+                    log::warn!("ignoring stale timeout {:?}", id);
                 }
             },
             TimerId::Firmware => {
                 match core::mem::replace(&mut self.firmware_state, FirmwareState::Idle) {
-                    FirmwareState::New(t) => {
-                        self.send_firmware_status_notification(FirmwareStatus::Downloading)
-                            .await;
-                        self.try_firmware_download(FirmwareDownloadInfo {
-                            retry_left: t.retries.unwrap_or(1),
-                            retry_interval: t.retry_interval.unwrap_or(0),
-                            location: t.location,
-                        })
-                        .await;
-                    }
-                    FirmwareState::DownloadSleep(t) => {
-                        self.send_firmware_status_notification(FirmwareStatus::Downloading)
+                    FirmwareState::New(t) | FirmwareState::DownloadSleep(t) => {
+                        self.send_firmware_status_notification(SignedFirmwareStatus::Downloading)
                             .await;
                         self.try_firmware_download(t).await;
                     }
-                    _ => {
-                        unreachable!();
+                    FirmwareState::InstallScheduled(_) => {
+                        self.schedule_firmware_install(None).await;
+                    }
+                    state => {
+                        self.firmware_state = state;
+                        log::warn!("ignoring stale timeout {:?}", id);
                     }
                 }
             }
@@ -139,7 +134,11 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 self.set_aligned_meter_sleep_state().await;
             }
             TimerId::MeterSampled(connector_id) => {
-                let local_transaction_id = self.active_local_transactions[connector_id].unwrap().0;
+                let Some((local_transaction_id, _)) = self.active_local_transactions[connector_id]
+                else {
+                    log::warn!("ignoring stale timeout {:?}", id);
+                    return;
+                };
                 self.add_meter_event(
                     connector_id,
                     Some(local_transaction_id),
@@ -156,13 +155,33 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 .await;
                 self.set_sampled_meter_sleep_state(connector_id).await;
             }
+            TimerId::ChargingLimit(connector_id) => {
+                self.update_charging_limit(connector_id).await;
+            }
+            TimerId::Reconnect => {
+                self.connect().await;
+            }
+            TimerId::SecurityProfileFallback => {
+                self.security_profile_fallback().await;
+            }
+            TimerId::Ping => match &self.ping_state {
+                PingState::Sleeping => {
+                    self.send_ping().await;
+                }
+                PingState::WaitingForPong => {
+                    self.on_pong_timeout().await;
+                }
+                PingState::Idle => {
+                    log::warn!("ignoring stale timeout {:?}", id);
+                }
+            },
             TimerId::Transaction => match &self.transaction_event_state {
                 TransactionEventState::Sleeping => {
                     self.transaction_event_state = TransactionEventState::Idle;
                     self.process_transaction().await;
                 }
                 _ => {
-                    unreachable!();
+                    log::warn!("ignoring stale timeout {:?}", id);
                 }
             },
         }
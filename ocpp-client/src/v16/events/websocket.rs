@@ -1,4 +1,4 @@
-use alloc::string::String;
+use alloc::{format, string::String};
 use rocpp_core::{
     format::{
         frame::{Call, CallError},
@@ -7,19 +7,29 @@ use rocpp_core::{
     v16::{protocol_error::ProtocolError, types::RegistrationStatus},
 };
 
-use crate::v16::{cp::ChargePoint, interfaces::ChargePointInterface};
+use crate::v16::{
+    cp::ChargePoint, interfaces::ChargePointInterface,
+    state_machine::security_event::SecurityEventType,
+};
 
 impl<I: ChargePointInterface> ChargePoint<I> {
     pub(crate) async fn ws_connected(&mut self) {
         self.ws_connected = true;
+        self.on_ping_connected().await;
+        self.on_security_profile_connected().await;
         self.on_boot_connected().await;
     }
 
     pub(crate) async fn ws_disconnected(&mut self) {
         self.ws_connected = false;
+        self.on_ping_disconnected().await;
         self.on_outgoing_offline().await;
         self.on_boot_disconnected().await;
-        self.connect(self.cms_url.clone()).await;
+        self.schedule_reconnect().await;
+    }
+
+    pub(crate) async fn ws_connect_failed(&mut self) {
+        self.schedule_failed_reconnect().await;
     }
 
     pub(crate) async fn send_error(&mut self, uid: String, err: ProtocolError) {
@@ -57,6 +67,12 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     })
                     .await;
                 }
+                "CertificateSigned" => {
+                    self.handle_call(call, |srv, unique_id, req| async {
+                        srv.certificate_signed_ocpp(unique_id, req).await;
+                    })
+                    .await;
+                }
                 "ClearChargingProfile" => {
                     self.handle_call(call, |srv, unique_id, req| async {
                         srv.clear_charging_profile_ocpp(unique_id, req).await;
@@ -69,6 +85,18 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     })
                     .await;
                 }
+                "DeleteCertificate" => {
+                    self.handle_call(call, |srv, unique_id, req| async {
+                        srv.delete_certificate_ocpp(unique_id, req).await;
+                    })
+                    .await;
+                }
+                "ExtendedTriggerMessage" => {
+                    self.handle_call(call, |srv, unique_id, req| async {
+                        srv.extended_trigger_message_ocpp(unique_id, req).await;
+                    })
+                    .await;
+                }
                 "GetCompositeSchedule" => {
                     self.handle_call(call, |srv, unique_id, req| async {
                         srv.get_composite_schedule_ocpp(unique_id, req).await;
@@ -87,12 +115,30 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     })
                     .await;
                 }
+                "GetInstalledCertificateIds" => {
+                    self.handle_call(call, |srv, unique_id, req| async {
+                        srv.get_installed_certificate_ids_ocpp(unique_id, req).await;
+                    })
+                    .await;
+                }
                 "GetLocalListVersion" => {
                     self.handle_call(call, |srv, unique_id, req| async {
                         srv.get_local_list_version_ocpp(unique_id, req).await;
                     })
                     .await;
                 }
+                "GetLog" => {
+                    self.handle_call(call, |srv, unique_id, req| async {
+                        srv.get_log_ocpp(unique_id, req).await;
+                    })
+                    .await;
+                }
+                "InstallCertificate" => {
+                    self.handle_call(call, |srv, unique_id, req| async {
+                        srv.install_certificate_ocpp(unique_id, req).await;
+                    })
+                    .await;
+                }
                 "RemoteStartTransaction" => {
                     self.handle_call(call, |srv, unique_id, req| async {
                         srv.remote_start_transaction_ocpp(unique_id, req).await;
@@ -129,6 +175,12 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     })
                     .await;
                 }
+                "SignedUpdateFirmware" => {
+                    self.handle_call(call, |srv, unique_id, req| async {
+                        srv.signed_update_firmware_ocpp(unique_id, req).await;
+                    })
+                    .await;
+                }
                 "TriggerMessage" => {
                     self.handle_call(call, |srv, unique_id, req| async {
                         srv.trigger_message_ocpp(unique_id, req).await;
@@ -160,6 +212,11 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     self.send_error(uid, ProtocolError::FormationViolation)
                         .await;
                 }
+                self.raise_security_event(
+                    SecurityEventType::InvalidMessages,
+                    Some(invalid.err_msg),
+                )
+                .await;
             }
         }
     }
@@ -182,15 +239,24 @@ impl<I: ChargePointInterface> ChargePoint<I> {
 
         match serde_json::from_value::<T>(call.payload) {
             Ok(val) => {
-                if self.is_registered(&call.action) {
+                // a pending shutdown takes no new work, only the responses to
+                // the call in flight are still processed
+                if self.pending_shutdown {
+                    self.send_error(uid, ProtocolError::InternalError).await;
+                } else if self.is_registered(&call.action) {
                     handler(self, uid, val).await;
                 } else {
                     self.send_error(uid, ProtocolError::SecurityError).await;
                 }
             }
-            Err(_) => {
+            Err(e) => {
                 self.send_error(uid, ProtocolError::FormationViolation)
                     .await;
+                self.raise_security_event(
+                    SecurityEventType::InvalidMessages,
+                    Some(format!("{}: {}", call.action, e)),
+                )
+                .await;
             }
         }
     }
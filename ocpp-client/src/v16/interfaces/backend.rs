@@ -6,19 +6,48 @@ use alloc::{
     vec::Vec,
 };
 
-use rocpp_core::v16::{
-    messages::{reserve_now::ReserveNowRequest, status_notification::StatusNotificationRequest},
-    types::{AvailabilityType, ChargePointErrorCode, IdTagInfo, Measurand},
+use serde::de::DeserializeOwned;
+
+use rocpp_core::{
+    format::frame::Call,
+    v16::{
+        messages::{
+            reserve_now::ReserveNowRequest, set_charging_profile::SetChargingProfileRequest,
+            status_notification::StatusNotificationRequest,
+        },
+        types::{AvailabilityType, ChargePointErrorCode, IdTagInfo, Measurand},
+    },
 };
 
 use crate::v16::state_machine::{
     auth::LocalListChange,
+    certificate::InstalledCertificate,
+    clock::ClockAnchor,
     connector::ConnectorState,
-    firmware::FirmwareInstallStatus,
+    firmware::{FirmwareInstallStatus, FirmwareJob},
+    security_event::SecurityEvent,
     transaction::{MeterValueLocal, TransactionEvent},
 };
 
-use super::{ChargePointInterface, MeterDataType, SeccState};
+use super::{ChargePointInterface, ClientCertificate, DataLoss, DbError, MeterDataType, SeccState};
+
+// bump when the stored layout changes and add the step to migrate_schema
+const DB_SCHEMA_VERSION: u32 = 3;
+// rows held in memory at once while scanning a table
+const DB_SCAN_PAGE: usize = 16;
+
+// zero padded so the key order of a scan is the index order
+fn security_event_key(index: u64) -> String {
+    format!("event:{:020}", index)
+}
+
+fn held_call_key(index: u64) -> String {
+    format!("call:{:020}", index)
+}
+
+fn parse_json<T: DeserializeOwned>(value: &str) -> Option<T> {
+    serde_json::from_str(value).ok()
+}
 
 pub(crate) struct ChargePointBackend<I: ChargePointInterface> {
     pub interface: I,
@@ -43,61 +72,382 @@ impl<I: ChargePointInterface> ChargePointBackend<I> {
             .parse()
             .unwrap()
     }
-    pub async fn init(&mut self, default_configs: Vec<(String, String)>, clear_db: bool) {
-        self.interface.db_init().await;
-        let mut previous_configs = self.interface.db_get_all("previous_configs").await;
-        let mut default_configs: Vec<_> = default_configs
-            .iter()
-            .map(|t| (t.0.as_str(), t.1.as_str()))
-            .collect();
-        previous_configs.sort();
-        default_configs.sort();
-        if clear_db || default_configs != previous_configs {
-            self.interface.db_delete_all().await;
+    async fn db_write(&mut self, table: &str, ops: Vec<(&str, Option<&str>)>) {
+        if let Err(e) = self.interface.db_transaction(table, ops).await {
+            log::error!("failed to write table {}: {:?}", table, e);
             self.interface
-                .db_transaction(
-                    "previous_configs",
-                    default_configs
-                        .clone()
-                        .into_iter()
-                        .map(|(key, value)| (key, Some(value)))
-                        .collect(),
-                )
+                .db_data_lost(DataLoss::Unwritable {
+                    table: table.to_string(),
+                })
                 .await;
+        }
+    }
+    async fn db_unreadable(&mut self, table: &str, e: DbError) {
+        log::error!("failed to read table {}: {:?}", table, e);
+        self.interface
+            .db_data_lost(DataLoss::Unreadable {
+                table: table.to_string(),
+            })
+            .await;
+    }
+    // undecodable rows are moved aside so they are kept for inspection but never read again
+    async fn db_quarantine(&mut self, table: &str, rows: Vec<(String, String)>) {
+        for (key, value) in rows {
+            log::error!("quarantining row {} of table {}", key, table);
+            let quarantine_key = format!("{}:{}", table, key);
+            self.db_write(
+                "quarantine",
+                vec![(quarantine_key.as_str(), Some(value.as_str()))],
+            )
+            .await;
+            self.db_write(table, vec![(key.as_str(), None)]).await;
             self.interface
-                .db_transaction(
-                    "config",
-                    default_configs
-                        .into_iter()
-                        .map(|(key, value)| (key, Some(value)))
-                        .collect(),
-                )
+                .db_data_lost(DataLoss::Quarantined {
+                    table: table.to_string(),
+                    key,
+                })
                 .await;
         }
     }
-
-    pub async fn db_get_all_configs(&mut self) -> Vec<(&str, &str)> {
-        self.interface.db_get_all("config").await
+    async fn db_load<T>(
+        &mut self,
+        table: &str,
+        key: &str,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> Option<T> {
+        let res = match self.interface.db_get(table, key).await {
+            Ok(value) => value.map(|value| parse(value).ok_or_else(|| value.to_string())),
+            Err(e) => {
+                self.db_unreadable(table, e).await;
+                None
+            }
+        };
+        match res? {
+            Ok(t) => Some(t),
+            Err(value) => {
+                self.db_quarantine(table, vec![(key.to_string(), value)])
+                    .await;
+                None
+            }
+        }
     }
-    async fn db_get_reservations(&mut self) -> Vec<ReserveNowRequest> {
-        self.interface
-            .db_get_all("reservation")
+    async fn db_load_all<T>(
+        &mut self,
+        table: &str,
+        parse: impl Fn(&str, &str) -> Option<T>,
+    ) -> Vec<T> {
+        let mut res = Vec::new();
+        let mut invalid = Vec::new();
+        match self.interface.db_get_all(table).await {
+            Ok(rows) => {
+                for (key, value) in rows {
+                    match parse(key, value) {
+                        Some(t) => res.push(t),
+                        None => invalid.push((key.to_string(), value.to_string())),
+                    }
+                }
+            }
+            Err(e) => self.db_unreadable(table, e).await,
+        }
+        self.db_quarantine(table, invalid).await;
+        res
+    }
+    // one page of the rows under prefix, with the key to continue after unless
+    // it was the last page
+    async fn db_scan_page<T>(
+        &mut self,
+        table: &str,
+        prefix: &str,
+        start_after: Option<&str>,
+        parse: &impl Fn(&str, &str) -> Option<T>,
+    ) -> (Vec<T>, Option<String>) {
+        let mut res = Vec::new();
+        let mut invalid = Vec::new();
+        let next = match self
+            .interface
+            .db_scan(table, prefix, start_after, DB_SCAN_PAGE)
+            .await
+        {
+            Ok(rows) => {
+                for (key, value) in rows.iter() {
+                    match parse(key, value) {
+                        Some(t) => res.push(t),
+                        None => invalid.push((key.to_string(), value.to_string())),
+                    }
+                }
+                rows.last()
+                    .filter(|_| rows.len() == DB_SCAN_PAGE)
+                    .map(|(key, _)| key.to_string())
+            }
+            Err(e) => {
+                self.db_unreadable(table, e).await;
+                None
+            }
+        };
+        self.db_quarantine(table, invalid).await;
+        (res, next)
+    }
+    // visits the rows under prefix one page at a time, so the whole table is
+    // never held in memory
+    async fn db_for_each<T>(
+        &mut self,
+        table: &str,
+        prefix: &str,
+        parse: impl Fn(&str, &str) -> Option<T>,
+        mut visit: impl FnMut(T),
+    ) {
+        let mut start_after: Option<String> = None;
+        loop {
+            let (page, next) = self
+                .db_scan_page(table, prefix, start_after.as_deref(), &parse)
+                .await;
+            page.into_iter().for_each(&mut visit);
+            match next {
+                Some(key) => start_after = Some(key),
+                None => break,
+            }
+        }
+    }
+    pub async fn init(&mut self, default_configs: Vec<(String, String)>, clear_db: bool) {
+        if let Err(e) = self.interface.db_init().await {
+            log::error!("failed to init the store: {:?}", e);
+        }
+        if clear_db {
+            if let Err(e) = self.interface.db_delete_all().await {
+                log::error!("failed to clear the store: {:?}", e);
+            }
+        }
+        self.migrate_schema().await;
+        self.migrate_configs(default_configs).await;
+    }
+    async fn migrate_schema(&mut self) {
+        let version = self
+            .db_load("schema", "version", |t| t.parse::<u32>().ok())
+            .await
+            .unwrap_or(0);
+        if version == DB_SCHEMA_VERSION {
+            return;
+        }
+        if version > DB_SCHEMA_VERSION {
+            // rows this build cannot decode are quarantined when read
+            log::warn!(
+                "db schema version {} is newer than {}, keeping the data as is",
+                version,
+                DB_SCHEMA_VERSION
+            );
+            return;
+        }
+        // layout changes are migrated here one version at a time, version 0 is
+        // the unversioned layout which version 1 keeps as is
+        log::info!(
+            "migrating db schema from version {} to {}",
+            version,
+            DB_SCHEMA_VERSION
+        );
+        if version < 2 {
+            self.migrate_transaction_queue().await;
+        }
+        if version < 3 {
+            self.migrate_security_log().await;
+        }
+        let version = DB_SCHEMA_VERSION.to_string();
+        self.db_write("schema", vec![("version", Some(version.as_str()))])
+            .await;
+    }
+    // version 2 keeps the queue counters, the stop meter value counts and the
+    // stopped transactions in their own rows so boot does not read every event
+    async fn migrate_transaction_queue(&mut self) {
+        let mut indices: Option<(u64, u64)> = None;
+        let mut stopped = BTreeMap::new();
+        self.db_for_each(
+            "transaction",
+            "event:",
+            |key, value| {
+                let index = key.strip_prefix("event:")?.parse::<u64>().ok()?;
+                Some((index, parse_json::<TransactionEvent>(value)))
+            },
+            |(index, event)| {
+                indices = Some(indices.map_or((index, index), |(tail, head)| {
+                    (tail.min(index), head.max(index))
+                }));
+                if let Some(TransactionEvent::Stop(t)) = event {
+                    stopped.insert(t.local_transaction_id, index);
+                }
+            },
+        )
+        .await;
+        let mut meter_counts: BTreeMap<u32, usize> = BTreeMap::new();
+        self.db_for_each(
+            "transaction",
+            "meter:",
+            |key, _| {
+                let (local_transaction_id, index) = key.strip_prefix("meter:")?.split_once(':')?;
+                Some((
+                    local_transaction_id.parse::<u32>().ok()?,
+                    index.parse::<usize>().ok()?,
+                ))
+            },
+            |(local_transaction_id, index)| {
+                let count = meter_counts.entry(local_transaction_id).or_insert(0);
+                *count = (*count).max(index + 1);
+            },
+        )
+        .await;
+        let (tail, head) = indices.map_or((0, 0), |(tail, head)| (tail, head + 1));
+        let mut ops = vec![
+            ("tail".to_string(), tail.to_string()),
+            ("head".to_string(), head.to_string()),
+        ];
+        for (local_transaction_id, index) in stopped {
+            ops.push((
+                format!("stopped:{}", local_transaction_id),
+                index.to_string(),
+            ));
+        }
+        for (local_transaction_id, count) in meter_counts {
+            ops.push((
+                format!("meter_count:{}", local_transaction_id),
+                count.to_string(),
+            ));
+        }
+        let ops_ref: Vec<(&str, Option<&str>)> = ops
+            .iter()
+            .map(|(k, v)| (k.as_str(), Some(v.as_str())))
+            .collect();
+        self.db_write("transaction", ops_ref).await;
+    }
+    // version 3 zero pads the security log keys and keeps its bounds in counter
+    // rows, the log is capped so it is migrated in one go
+    async fn migrate_security_log(&mut self) {
+        let mut rows = Vec::new();
+        self.db_for_each(
+            "security_event",
+            "event:",
+            |key, value| {
+                let index = key.strip_prefix("event:")?.parse::<u64>().ok()?;
+                Some((index, key.to_string(), value.to_string()))
+            },
+            |row| rows.push(row),
+        )
+        .await;
+        for (index, key, value) in rows.iter() {
+            let new_key = security_event_key(*index);
+            if *key != new_key {
+                self.db_write(
+                    "security_event",
+                    vec![
+                        (key.as_str(), None),
+                        (new_key.as_str(), Some(value.as_str())),
+                    ],
+                )
+                .await;
+            }
+        }
+        let sent = self
+            .db_load("security_event", "sent", |t| t.parse::<u64>().ok())
+            .await
+            .unwrap_or(0);
+        let start = rows
+            .iter()
+            .map(|(index, _, _)| *index)
+            .min()
+            .unwrap_or(sent);
+        let head = rows
+            .iter()
+            .map(|(index, _, _)| *index + 1)
+            .max()
+            .unwrap_or(sent);
+        let (start, head) = (start.to_string(), head.to_string());
+        self.db_write(
+            "security_event",
+            vec![
+                ("start", Some(start.as_str())),
+                ("head", Some(head.as_str())),
+            ],
+        )
+        .await;
+    }
+    // previous_configs holds the defaults the stored values were derived from. a
+    // value that differs from its old default was changed by the csms and is kept,
+    // every other value follows the new default
+    async fn migrate_configs(&mut self, default_configs: Vec<(String, String)>) {
+        let previous: BTreeMap<String, String> = self
+            .db_load_all("previous_configs", |key, value| {
+                Some((key.to_string(), value.to_string()))
+            })
             .await
             .into_iter()
-            .map(|f| serde_json::from_str::<ReserveNowRequest>(&f.1).unwrap())
-            .collect()
+            .collect();
+        let current: BTreeMap<String, String> =
+            self.db_get_all_configs().await.into_iter().collect();
+        let mut previous_ops = Vec::new();
+        let mut config_ops = Vec::new();
+        for (key, value) in default_configs.iter() {
+            if previous.get(key) != Some(value) {
+                previous_ops.push((key.as_str(), Some(value.as_str())));
+            }
+            let changed_by_csms = previous
+                .get(key)
+                .zip(current.get(key))
+                .is_some_and(|(old, current)| old != current);
+            if !changed_by_csms && current.get(key) != Some(value) {
+                log::info!("config {} set to its default {}", key, value);
+                config_ops.push((key.as_str(), Some(value.as_str())));
+            }
+        }
+        let is_removed = |key: &&String| default_configs.iter().all(|(k, _)| k != *key);
+        for key in previous.keys().filter(is_removed) {
+            previous_ops.push((key.as_str(), None));
+        }
+        for key in current.keys().filter(is_removed) {
+            log::info!("config {} removed", key);
+            config_ops.push((key.as_str(), None));
+        }
+        if !previous_ops.is_empty() {
+            self.db_write("previous_configs", previous_ops).await;
+        }
+        if !config_ops.is_empty() {
+            self.db_write("config", config_ops).await;
+        }
+    }
+
+    pub async fn db_get_all_configs(&mut self) -> Vec<(String, String)> {
+        self.db_load_all("config", |key, value| {
+            Some((key.to_string(), value.to_string()))
+        })
+        .await
+    }
+    // rewrites invalid config rows with their defaults, rows without one are dropped
+    pub async fn db_reset_configs(&mut self, keys: Vec<String>, defaults: &[(String, String)]) {
+        for key in keys {
+            let default = defaults
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.as_str());
+            self.db_write("config", vec![(key.as_str(), default)]).await;
+            self.interface
+                .db_data_lost(DataLoss::ConfigReset { key })
+                .await;
+        }
+    }
+    async fn db_get_reservations(&mut self, num_connectors: usize) -> Vec<ReserveNowRequest> {
+        self.db_load_all("reservation", |_, value| {
+            parse_json::<ReserveNowRequest>(value)
+                .filter(|t| (1..=num_connectors).contains(&t.connector_id))
+        })
+        .await
     }
     async fn db_get_operative_state(&mut self, num_connectors: usize) -> Vec<AvailabilityType> {
         let mut availability = vec![AvailabilityType::Operative; num_connectors];
-        self.interface
-            .db_get_all("availabilitytype")
-            .await
-            .iter()
-            .for_each(|(key, value)| {
-                let connector_id: usize = key.parse().unwrap();
-                let kind = serde_json::from_str::<AvailabilityType>(&value).unwrap();
-                availability[connector_id] = kind;
-            });
+        self.db_load_all("availabilitytype", |key, value| {
+            let connector_id = key.parse::<usize>().ok().filter(|t| *t < num_connectors)?;
+            Some((connector_id, parse_json::<AvailabilityType>(value)?))
+        })
+        .await
+        .into_iter()
+        .for_each(|(connector_id, kind)| {
+            availability[connector_id] = kind;
+        });
         availability
     }
     pub async fn db_change_operative_state(
@@ -107,12 +457,11 @@ impl<I: ChargePointInterface> ChargePointBackend<I> {
     ) {
         let key = connector_id.to_string();
         let value = serde_json::to_string(&state).unwrap();
-        self.interface
-            .db_transaction(
-                "availabilitytype",
-                vec![(key.as_str(), Some(value.as_str()))],
-            )
-            .await;
+        self.db_write(
+            "availabilitytype",
+            vec![(key.as_str(), Some(value.as_str()))],
+        )
+        .await;
     }
     pub async fn db_get_connector_state(
         &mut self,
@@ -127,9 +476,9 @@ impl<I: ChargePointInterface> ChargePointBackend<I> {
                 AvailabilityType::Inoperative => ConnectorState::Unavailable(SeccState::Unplugged),
             })
             .collect();
-        let reservations = self.db_get_reservations().await;
+        let reservations = self.db_get_reservations(num_connectors).await;
         for reservation in reservations {
-            let connector_id = reservation.connector_id;
+            let connector_id = reservation.connector_id - 1;
             connector_state[connector_id] = ConnectorState::reserved(
                 reservation.reservation_id,
                 reservation.id_tag,
@@ -137,18 +486,19 @@ impl<I: ChargePointInterface> ChargePointBackend<I> {
                 false,
             );
         }
-        let mut status = Vec::new();
-        for connector_id in 0..num_connectors {
-            status.push(StatusNotificationRequest {
+        let status = connector_state
+            .iter()
+            .enumerate()
+            .map(|(connector_id, state)| StatusNotificationRequest {
                 connector_id: (connector_id + 1),
                 error_code: ChargePointErrorCode::NoError,
                 info: None,
-                status: connector_state[connector_id].get_connector_state(false),
+                status: state.get_connector_state(false),
                 timestamp: None,
                 vendor_id: None,
                 vendor_error_code: None,
-            });
-        }
+            })
+            .collect();
         (connector_state, status)
     }
 
@@ -158,103 +508,170 @@ impl<I: ChargePointInterface> ChargePointBackend<I> {
         u32,
         u64,
         u64,
+        u64,
         BTreeMap<u32, i32>,
         BTreeMap<u32, usize>,
         BTreeMap<u32, usize>,
         BTreeSet<u32>,
     ) {
-        let mut transaction_map: BTreeMap<u32, i32> = BTreeMap::new();
-        let mut transaction_connector_map: BTreeMap<u32, usize> = BTreeMap::new();
-        let mut transaction_stop_meter_val_count: BTreeMap<u32, usize> = BTreeMap::new();
-        let mut unfinished_transactions: BTreeSet<u32> = BTreeSet::new();
-        let mut max_transaction_id: u32 = 0;
-        let mut events = Vec::new();
-        let all = self.interface.db_get_all("transaction").await;
-        for (key, value) in all.iter() {
-            if key.starts_with("transaction_map:") {
-                let payload = key.strip_prefix("transaction_map:").unwrap();
-                let local_transaction_id: u32 = payload.parse().unwrap();
-                let transaction_id: i32 = value.parse().unwrap();
+        let max_transaction_id = self
+            .db_load("transaction", "num_transactions", |t| t.parse().ok())
+            .await
+            .unwrap_or(0);
+        let tail = self
+            .db_load("transaction", "tail", |t| t.parse().ok())
+            .await
+            .unwrap_or(0);
+        let head = self
+            .db_load("transaction", "head", |t| t.parse().ok())
+            .await
+            .unwrap_or(0)
+            .max(tail);
+        // events skipped or dropped in between leave the span larger than the count
+        let len = self
+            .db_load("transaction", "len", |t| t.parse().ok())
+            .await
+            .unwrap_or(head - tail)
+            .min(head - tail);
+        let mut transaction_map = BTreeMap::new();
+        self.db_for_each(
+            "transaction",
+            "transaction_map:",
+            |key, value| {
+                let local_transaction_id = key.strip_prefix("transaction_map:")?;
+                Some((local_transaction_id.parse().ok()?, value.parse().ok()?))
+            },
+            |(local_transaction_id, transaction_id)| {
                 transaction_map.insert(local_transaction_id, transaction_id);
-                unfinished_transactions.insert(local_transaction_id);
-            }
-        }
-        for (key, value) in self.interface.db_get_all("transaction").await {
-            if key.starts_with("event:") {
-                let payload = key.strip_prefix("event:").unwrap();
-                let index: u64 = payload.parse().unwrap();
-                let event = serde_json::from_str::<TransactionEvent>(&value).unwrap();
-                events.push(index);
-                if let Some(local_transaction_id) = event.get_local_transaction_id() {
-                    if !event.is_stop() {
-                        unfinished_transactions.insert(local_transaction_id);
-                    } else {
-                        unfinished_transactions.remove(&local_transaction_id);
-                    }
-                }
-            } else if key.starts_with("transaction_map:") {
-                // already done
-            } else if key.starts_with("transaction_connector_map:") {
-                let payload = key.strip_prefix("transaction_connector_map:").unwrap();
-                let local_transaction_id: u32 = payload.parse().unwrap();
-                let connector_id: usize = value.parse().unwrap();
+            },
+        )
+        .await;
+        let mut transaction_connector_map = BTreeMap::new();
+        self.db_for_each(
+            "transaction",
+            "transaction_connector_map:",
+            |key, value| {
+                let local_transaction_id = key.strip_prefix("transaction_connector_map:")?;
+                Some((local_transaction_id.parse().ok()?, value.parse().ok()?))
+            },
+            |(local_transaction_id, connector_id)| {
                 transaction_connector_map.insert(local_transaction_id, connector_id);
-            } else if key.starts_with("meter:") {
-                let parts: Vec<&str> = key.strip_prefix("meter:").unwrap().split(':').collect();
-                let local_transaction_id: u32 = parts[0].parse().unwrap();
-                if let Some(v) = transaction_stop_meter_val_count.get_mut(&local_transaction_id) {
-                    *v += 1;
-                } else {
-                    transaction_stop_meter_val_count.insert(local_transaction_id, 1);
-                }
-            } else if key.starts_with("num_transactions") {
-                max_transaction_id = value.parse().unwrap();
-            } else {
-                unreachable!();
-            }
-        }
-        let (tail, head) = events
-            .first()
-            .map(|x| *x)
-            .zip(events.last().map(|x| x + 1))
-            .unwrap_or((0, 0));
+            },
+        )
+        .await;
+        let mut transaction_stop_meter_val_count = BTreeMap::new();
+        self.db_for_each(
+            "transaction",
+            "meter_count:",
+            |key, value| {
+                let local_transaction_id = key.strip_prefix("meter_count:")?;
+                Some((local_transaction_id.parse().ok()?, value.parse().ok()?))
+            },
+            |(local_transaction_id, count)| {
+                transaction_stop_meter_val_count.insert(local_transaction_id, count);
+            },
+        )
+        .await;
+        let mut stopped = BTreeSet::new();
+        self.db_for_each(
+            "transaction",
+            "stopped:",
+            |key, _| key.strip_prefix("stopped:")?.parse::<u32>().ok(),
+            |local_transaction_id| {
+                stopped.insert(local_transaction_id);
+            },
+        )
+        .await;
+        // a started transaction without a queued stop was cut short by a reboot
+        let unfinished_transactions = transaction_connector_map
+            .keys()
+            .chain(transaction_map.keys())
+            .filter(|local_transaction_id| !stopped.contains(*local_transaction_id))
+            .copied()
+            .collect();
         (
             max_transaction_id,
             tail,
             head,
+            len,
             transaction_map,
             transaction_connector_map,
             transaction_stop_meter_val_count,
             unfinished_transactions,
         )
     }
-    pub async fn db_get_firmware_state(&mut self) -> FirmwareInstallStatus {
-        let mut res = FirmwareInstallStatus::NA;
-        if let Some(value) = self.interface.db_get("firmware", "state").await {
-            let state = serde_json::from_str(&value).unwrap();
-            res = state;
+    pub async fn db_get_firmware_state(&mut self) -> (FirmwareInstallStatus, Option<i32>) {
+        let res = self
+            .db_load("firmware", "state", parse_json)
+            .await
+            .unwrap_or(FirmwareInstallStatus::NA);
+        let request_id = self
+            .db_load("firmware", "request_id", |t| t.parse().ok())
+            .await;
+        (res, request_id)
+    }
+    // bumps the boot counter and drops the anchors of boots too old to matter
+    pub async fn db_next_boot(&mut self, max_anchors: u64) -> (u64, BTreeMap<u64, ClockAnchor>) {
+        let boot_id = self
+            .db_load("clock", "boot", |t| t.parse::<u64>().ok())
+            .await
+            .unwrap_or(0)
+            + 1;
+        let mut anchors = BTreeMap::new();
+        let mut dropped = Vec::new();
+        let stored = self
+            .db_load_all("clock", |key, value| match key.strip_prefix("anchor:") {
+                Some(boot) => Some(Some((boot.parse::<u64>().ok()?, parse_json(value)?))),
+                None => Some(None),
+            })
+            .await;
+        for (boot, anchor) in stored.into_iter().flatten() {
+            if boot + max_anchors < boot_id {
+                dropped.push(format!("anchor:{}", boot));
+            } else {
+                anchors.insert(boot, anchor);
+            }
         }
-        res
+        let boot = boot_id.to_string();
+        let mut ops = vec![("boot", Some(boot.as_str()))];
+        ops.extend(dropped.iter().map(|t| (t.as_str(), None)));
+        self.db_write("clock", ops).await;
+        (boot_id, anchors)
     }
-    pub async fn db_update_config(&mut self, key: &str, value: &str) {
-        self.interface
-            .db_transaction("config", vec![(key, Some(value))])
+    pub async fn db_update_clock_anchor(&mut self, boot_id: u64, anchor: &ClockAnchor) {
+        let key = format!("anchor:{}", boot_id);
+        let value = serde_json::to_string(anchor).unwrap();
+        self.db_write("clock", vec![(key.as_str(), Some(value.as_str()))])
+            .await;
+    }
+    pub async fn db_get_firmware_job(&mut self) -> Option<FirmwareJob> {
+        self.db_load("firmware", "job", parse_json).await
+    }
+    pub async fn db_update_firmware_job(&mut self, job: Option<&FirmwareJob>) {
+        let value = job.map(|t| serde_json::to_string(t).unwrap());
+        self.db_write("firmware", vec![("job", value.as_deref())])
             .await;
     }
+    pub async fn db_update_config(&mut self, key: &str, value: &str) {
+        self.db_write("config", vec![(key, Some(value))]).await;
+    }
     pub async fn db_get_from_cache(&mut self, id_tag: &str) -> Option<IdTagInfo> {
-        self.interface
-            .db_get("cache", &id_tag)
-            .await
-            .map(|s| serde_json::from_str(s).unwrap())
+        self.db_load("cache", id_tag, parse_json).await
     }
     pub async fn db_update_cache(&mut self, id_tag: &str, info: IdTagInfo) {
         let value = serde_json::to_string(&info).unwrap();
-        self.interface
-            .db_transaction("cache", vec![(id_tag, Some(value.as_str()))])
+        self.db_write("cache", vec![(id_tag, Some(value.as_str()))])
             .await;
     }
     pub async fn db_clear_cache(&mut self) {
-        self.interface.db_delete_table("cache").await;
+        if let Err(e) = self.interface.db_delete_table("cache").await {
+            log::error!("failed to clear the cache: {:?}", e);
+            self.interface
+                .db_data_lost(DataLoss::Unwritable {
+                    table: "cache".to_string(),
+                })
+                .await;
+        }
     }
     pub async fn db_update_local_list(&mut self, version: i32, changes: Vec<LocalListChange>) {
         let mut ops: Vec<(String, Option<String>)> = changes
@@ -271,89 +688,297 @@ impl<I: ChargePointInterface> ChargePointBackend<I> {
             .iter()
             .map(|(k, v)| (k.as_str(), v.as_deref()))
             .collect();
-        self.interface.db_transaction("local_list", ops_ref).await;
+        self.db_write("local_list", ops_ref).await;
     }
     pub async fn db_get_from_local_list(&mut self, id_tag: &str) -> Option<IdTagInfo> {
-        self.interface
-            .db_get("local_list", &id_tag)
-            .await
-            .map(|s| serde_json::from_str(s).unwrap())
+        self.db_load("local_list", id_tag, parse_json).await
     }
-    pub async fn db_local_list_keys(&mut self) -> Vec<&str> {
-        let v = self.interface.db_get_all("local_list").await;
-        let mut res = Vec::new();
-        for (key, _) in v {
-            if key != "version#" {
-                res.push(key);
-            }
-        }
-        res
+    pub async fn db_local_list_keys(&mut self) -> Vec<String> {
+        self.db_load_all("local_list", |key, _| Some(key.to_string()))
+            .await
+            .into_iter()
+            .filter(|key| key != "version#")
+            .collect()
     }
     pub async fn db_get_local_list_version(&mut self) -> Option<i32> {
-        self.interface
-            .db_get("local_list", "version#")
+        self.db_load("local_list", "version#", |t| t.parse().ok())
             .await
-            .map(|t| t.parse().unwrap())
     }
     pub async fn db_get_local_list_entries_count(&mut self) -> usize {
-        let tot = self.interface.db_count_keys("local_list").await;
+        let tot = match self.interface.db_count_keys("local_list").await {
+            Ok(tot) => tot,
+            Err(e) => {
+                self.db_unreadable("local_list", e).await;
+                0
+            }
+        };
         if tot == 0 {
             return 0;
         }
         tot - 1
     }
-    pub async fn db_change_firmware_state(&mut self, state: FirmwareInstallStatus) {
-        let key = "state";
+    pub async fn db_change_firmware_state(
+        &mut self,
+        state: FirmwareInstallStatus,
+        request_id: Option<i32>,
+    ) {
         let value = serde_json::to_string(&state).unwrap();
-        self.interface
-            .db_transaction("firmware", vec![(key, Some(value.as_str()))])
-            .await;
+        let request_id = request_id.map(|t| t.to_string());
+        self.db_write(
+            "firmware",
+            vec![
+                ("state", Some(value.as_str())),
+                ("request_id", request_id.as_deref()),
+                // a recorded install result ends the job
+                ("job", None),
+            ],
+        )
+        .await;
     }
     pub(crate) async fn db_add_reservation(&mut self, reservation: ReserveNowRequest) {
         let key = reservation.reservation_id.to_string();
         let value = serde_json::to_string(&reservation).unwrap();
-        self.interface
-            .db_transaction("reservation", vec![(key.as_str(), Some(value.as_str()))])
+        self.db_write("reservation", vec![(key.as_str(), Some(value.as_str()))])
             .await;
     }
     pub(crate) async fn db_remove_reservation(&mut self, reservation_id: i32) {
-        self.interface
-            .db_transaction(
-                "reservation",
-                vec![(reservation_id.to_string().as_str(), None)],
+        self.db_write(
+            "reservation",
+            vec![(reservation_id.to_string().as_str(), None)],
+        )
+        .await;
+    }
+    pub(crate) async fn db_get_charging_profiles(&mut self) -> Vec<SetChargingProfileRequest> {
+        self.db_load_all("charging_profile", |_, value| parse_json(value))
+            .await
+    }
+    pub(crate) async fn db_add_charging_profile(
+        &mut self,
+        profile: &SetChargingProfileRequest,
+        replaced: Vec<i32>,
+    ) {
+        let mut ops: Vec<(String, Option<String>)> = replaced
+            .into_iter()
+            .map(|id| (id.to_string(), None))
+            .collect();
+        ops.push((
+            profile.cs_charging_profiles.charging_profile_id.to_string(),
+            Some(serde_json::to_string(profile).unwrap()),
+        ));
+        let ops_ref: Vec<(&str, Option<&str>)> = ops
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_deref()))
+            .collect();
+        self.db_write("charging_profile", ops_ref).await;
+    }
+    pub(crate) async fn db_remove_charging_profiles(&mut self, ids: Vec<i32>) {
+        let keys: Vec<String> = ids.into_iter().map(|id| id.to_string()).collect();
+        self.db_write(
+            "charging_profile",
+            keys.iter().map(|k| (k.as_str(), None)).collect(),
+        )
+        .await;
+    }
+    pub(crate) async fn db_get_installed_certificates(&mut self) -> Vec<InstalledCertificate> {
+        self.db_load_all("certificate", |_, value| parse_json(value))
+            .await
+    }
+    pub(crate) async fn db_add_certificate(&mut self, certificate: &InstalledCertificate) {
+        let key = certificate.key();
+        let value = serde_json::to_string(certificate).unwrap();
+        self.db_write("certificate", vec![(key.as_str(), Some(value.as_str()))])
+            .await;
+    }
+    pub(crate) async fn db_remove_certificate(&mut self, certificate: &InstalledCertificate) {
+        self.db_write("certificate", vec![(certificate.key().as_str(), None)])
+            .await;
+    }
+    pub(crate) async fn db_get_client_certificate(&mut self) -> Option<ClientCertificate> {
+        self.db_load("client_certificate", "current", parse_json)
+            .await
+    }
+    pub(crate) async fn db_update_client_certificate(&mut self, certificate: &ClientCertificate) {
+        let value = serde_json::to_string(certificate).unwrap();
+        self.db_write(
+            "client_certificate",
+            vec![("current", Some(value.as_str()))],
+        )
+        .await;
+    }
+    pub(crate) async fn db_get_security_profile_fallback(&mut self) -> Option<u8> {
+        self.db_load("security_profile", "fallback", |t| t.parse::<u8>().ok())
+            .await
+    }
+    pub(crate) async fn db_update_security_profile_fallback(&mut self, fallback: Option<u8>) {
+        let value = fallback.map(|t| t.to_string());
+        self.db_write("security_profile", vec![("fallback", value.as_deref())])
+            .await;
+    }
+    pub(crate) async fn db_get_security_event_data(&mut self) -> (u64, u64, u64) {
+        let start = self
+            .db_load("security_event", "start", |t| t.parse::<u64>().ok())
+            .await
+            .unwrap_or(0);
+        let sent = self
+            .db_load("security_event", "sent", |t| t.parse::<u64>().ok())
+            .await
+            .unwrap_or(start);
+        let head = self
+            .db_load("security_event", "head", |t| t.parse::<u64>().ok())
+            .await
+            .unwrap_or(sent)
+            .max(start);
+        (start, sent.clamp(start, head), head)
+    }
+    pub(crate) async fn db_get_security_event(&mut self, index: u64) -> Option<SecurityEvent> {
+        self.db_load(
+            "security_event",
+            security_event_key(index).as_str(),
+            parse_json,
+        )
+        .await
+    }
+    // a page of the security log in index order, with the index to continue
+    // after unless it was the last page
+    pub(crate) async fn db_get_security_events(
+        &mut self,
+        after: Option<u64>,
+    ) -> (Vec<SecurityEvent>, Option<u64>) {
+        let start_after = after.map(security_event_key);
+        let (events, next) = self
+            .db_scan_page(
+                "security_event",
+                "event:",
+                start_after.as_deref(),
+                &|_, value| parse_json::<SecurityEvent>(value),
             )
             .await;
+        let next = next.and_then(|key| key.strip_prefix("event:")?.parse().ok());
+        (events, next)
     }
-    pub(crate) async fn db_push_transaction_event(&mut self, index: u64, event: TransactionEvent) {
+    pub(crate) async fn db_push_security_event(
+        &mut self,
+        index: u64,
+        event: &SecurityEvent,
+        dropped: Option<u64>,
+        sent: u64,
+    ) {
+        let mut ops = vec![
+            (
+                security_event_key(index),
+                Some(serde_json::to_string(event).unwrap()),
+            ),
+            ("head".to_string(), Some((index + 1).to_string())),
+            ("sent".to_string(), Some(sent.to_string())),
+        ];
+        if let Some(dropped) = dropped {
+            ops.push((security_event_key(dropped), None));
+            ops.push(("start".to_string(), Some((dropped + 1).to_string())));
+        }
+        let ops_ref: Vec<(&str, Option<&str>)> = ops
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_deref()))
+            .collect();
+        self.db_write("security_event", ops_ref).await;
+    }
+    pub(crate) async fn db_update_security_event_sent(&mut self, sent: u64) {
+        self.db_write(
+            "security_event",
+            vec![("sent", Some(sent.to_string().as_str()))],
+        )
+        .await;
+    }
+    // the held calls in the order they were queued, with the index for the next
+    pub(crate) async fn db_get_held_calls(&mut self) -> (Vec<(u64, Call)>, u64) {
+        let mut calls = Vec::new();
+        self.db_for_each(
+            "held_call",
+            "call:",
+            |key, value| {
+                let index = key.strip_prefix("call:")?.parse::<u64>().ok()?;
+                Some((index, parse_json::<Call>(value)?))
+            },
+            |call| calls.push(call),
+        )
+        .await;
+        let head = self
+            .db_load("held_call", "head", |t| t.parse::<u64>().ok())
+            .await
+            .unwrap_or(0)
+            .max(calls.last().map(|(index, _)| index + 1).unwrap_or(0));
+        (calls, head)
+    }
+    pub(crate) async fn db_push_held_call(&mut self, index: u64, call: &Call) {
+        let key = held_call_key(index);
+        let value = serde_json::to_string(call).unwrap();
+        let head = (index + 1).to_string();
+        self.db_write(
+            "held_call",
+            vec![
+                (key.as_str(), Some(value.as_str())),
+                ("head", Some(head.as_str())),
+            ],
+        )
+        .await;
+    }
+    pub(crate) async fn db_remove_held_call(&mut self, index: u64) {
+        self.db_write("held_call", vec![(held_call_key(index).as_str(), None)])
+            .await;
+    }
+    pub(crate) async fn db_push_transaction_event(
+        &mut self,
+        index: u64,
+        event: TransactionEvent,
+        len: u64,
+    ) {
         let key = format!("event:{}", index);
         let value = serde_json::to_string(&event).unwrap();
         let mut ops = Vec::new();
         ops.push((key, Some(value)));
-        if let TransactionEvent::Start(t) = &event {
-            ops.push((
-                format!("transaction_connector_map:{}", t.local_transaction_id),
-                Some(t.connector_id.to_string()),
-            ));
-            ops.push((
-                "num_transactions".to_string(),
-                Some(t.local_transaction_id.to_string()),
-            ));
+        ops.push(("head".to_string(), Some((index + 1).to_string())));
+        ops.push(("len".to_string(), Some(len.to_string())));
+        match &event {
+            TransactionEvent::Start(t) => {
+                ops.push((
+                    format!("transaction_connector_map:{}", t.local_transaction_id),
+                    Some(t.connector_id.to_string()),
+                ));
+                ops.push((
+                    "num_transactions".to_string(),
+                    Some(t.local_transaction_id.to_string()),
+                ));
+            }
+            TransactionEvent::Stop(t) => {
+                ops.push((
+                    format!("stopped:{}", t.local_transaction_id),
+                    Some(index.to_string()),
+                ));
+            }
+            TransactionEvent::Meter(_) => {}
         }
         let ops_ref: Vec<(&str, Option<&str>)> = ops
             .iter()
             .map(|(k, v)| (k.as_str(), v.as_deref()))
             .collect();
-        self.interface.db_transaction("transaction", ops_ref).await;
+        self.db_write("transaction", ops_ref).await;
     }
+    // indices holds the event at the tail and the ones batched with it
     pub(crate) async fn db_pop_transaction_event(
         &mut self,
-        index: u64,
+        indices: &[u64],
         local_transaction_id: Option<u32>,
         transaction_id: Option<i32>,
         meter_tx: Option<usize>,
+        len: u64,
     ) {
         let mut ops = Vec::new();
-        ops.push((format!("event:{}", index), None));
+        for index in indices {
+            ops.push((format!("event:{}", index), None));
+        }
+        if let Some(index) = indices.last() {
+            ops.push(("tail".to_string(), Some((index + 1).to_string())));
+        }
+        ops.push(("len".to_string(), Some(len.to_string())));
         if let Some(local_transaction_id) = local_transaction_id {
             if let Some(transaction_id) = transaction_id {
                 ops.push((
@@ -367,6 +992,8 @@ impl<I: ChargePointInterface> ChargePointBackend<I> {
                     format!("transaction_connector_map:{}", local_transaction_id),
                     None,
                 ));
+                ops.push((format!("stopped:{}", local_transaction_id), None));
+                ops.push((format!("meter_count:{}", local_transaction_id), None));
                 for index in 0..meter_tx {
                     ops.push((format!("meter:{}:{}", local_transaction_id, index), None));
                 }
@@ -376,16 +1003,31 @@ impl<I: ChargePointInterface> ChargePointBackend<I> {
             .iter()
             .map(|(k, v)| (k.as_str(), v.as_deref()))
             .collect();
-        self.interface.db_transaction("transaction", ops_ref).await;
+        self.db_write("transaction", ops_ref).await;
+    }
+    pub(crate) async fn db_drop_transaction_events(&mut self, indices: &[u64], len: u64) {
+        let mut ops = Vec::new();
+        for index in indices {
+            ops.push((format!("event:{}", index), None));
+        }
+        ops.push(("len".to_string(), Some(len.to_string())));
+        let ops_ref: Vec<(&str, Option<&str>)> = ops
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_deref()))
+            .collect();
+        self.db_write("transaction", ops_ref).await;
     }
 
-    pub(crate) async fn db_get_transaction_event(&mut self, index: u64) -> TransactionEvent {
-        let val = self
-            .interface
-            .db_get("transaction", format!("event:{}", index).as_str())
-            .await
-            .unwrap();
-        serde_json::from_str(val).unwrap()
+    pub(crate) async fn db_get_transaction_event(
+        &mut self,
+        index: u64,
+    ) -> Option<TransactionEvent> {
+        self.db_load(
+            "transaction",
+            format!("event:{}", index).as_str(),
+            parse_json,
+        )
+        .await
     }
 
     pub(crate) async fn db_get_all_stop_meter_val(
@@ -395,16 +1037,10 @@ impl<I: ChargePointInterface> ChargePointBackend<I> {
     ) -> Vec<MeterValueLocal> {
         let mut res = Vec::new();
         for index in 0..len {
-            let val = self
-                .interface
-                .db_get(
-                    "transaction",
-                    format!("meter:{}:{}", local_transaction_id, index).as_str(),
-                )
-                .await
-                .unwrap();
-            let data = serde_json::from_str(val).unwrap();
-            res.push(data);
+            let key = format!("meter:{}:{}", local_transaction_id, index);
+            if let Some(data) = self.db_load("transaction", key.as_str(), parse_json).await {
+                res.push(data);
+            }
         }
         res
     }
@@ -416,8 +1052,15 @@ impl<I: ChargePointInterface> ChargePointBackend<I> {
     ) {
         let key = format!("meter:{}:{}", local_transaction_id, index);
         let value = serde_json::to_string(&value).unwrap();
-        self.interface
-            .db_transaction("transaction", vec![(key.as_str(), Some(value.as_str()))])
-            .await;
+        let count_key = format!("meter_count:{}", local_transaction_id);
+        let count = (index + 1).to_string();
+        self.db_write(
+            "transaction",
+            vec![
+                (key.as_str(), Some(value.as_str())),
+                (count_key.as_str(), Some(count.as_str())),
+            ],
+        )
+        .await;
     }
 }
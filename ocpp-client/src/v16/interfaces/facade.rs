@@ -2,24 +2,48 @@ use alloc::{string::String, vec::Vec};
 use core::task::{Context, Poll};
 
 use chrono::{DateTime, Utc};
-use rocpp_core::v16::types::ChargePointStatus;
+use rocpp_core::v16::{
+    messages::data_transfer::{DataTransferRequest, DataTransferResponse},
+    types::{CertificateHashData, ChargePointStatus},
+};
 
 use super::{
-    ChargePointInterface, Diagnostics, DiagnosticsResponse, Firmware, Hardware, HardwareEvent,
-    KeyValueStore, MeterData, MeterDataType, TimeoutScheduler, TimerId, Websocket, WsEvent,
+    ChargePointCommand, ChargePointInterface, ChargePointSnapshot, ChargingLimit,
+    ClientCertificate, CommandResult, Commands, Crypto, DataLoss, DataTransfer, DbError,
+    Diagnostics, DiagnosticsResponse, Firmware, Hardware, HardwareEvent, KeyValueStore, MeterData,
+    MeterDataType, Observer, StateChange, TimeoutScheduler, TimerId, Websocket, WsConnectOptions,
+    WsEvent,
 };
 
-pub struct ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws> {
+pub struct ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr> {
     kv: K,
     diag: D,
     fw: Fw,
     ts: Ts,
     hw: Hw,
     ws: Ws,
+    dt: Dt,
+    cmd: C,
+    ob: Ob,
+    cr: Cr,
 }
 
-impl<K, D, Fw, Ts, Hw, Ws> ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws> {
-    pub fn new(kv: K, diag: D, fw: Fw, ts: Ts, hw: Hw, ws: Ws) -> Self {
+impl<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr>
+    ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr>
+{
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        kv: K,
+        diag: D,
+        fw: Fw,
+        ts: Ts,
+        hw: Hw,
+        ws: Ws,
+        dt: Dt,
+        cmd: C,
+        ob: Ob,
+        cr: Cr,
+    ) -> Self {
         Self {
             kv,
             diag,
@@ -27,38 +51,60 @@ impl<K, D, Fw, Ts, Hw, Ws> ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws> {
             ts,
             hw,
             ws,
+            dt,
+            cmd,
+            ob,
+            cr,
         }
     }
 }
 
-impl<K, D, Fw, Ts, Hw, Ws> KeyValueStore for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws>
+impl<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr> KeyValueStore
+    for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr>
 where
     K: KeyValueStore,
 {
-    async fn db_init(&mut self) {
+    async fn db_init(&mut self) -> Result<(), DbError> {
         self.kv.db_init().await
     }
-    async fn db_transaction(&mut self, table: &str, ops: Vec<(&str, Option<&str>)>) {
+    async fn db_transaction(
+        &mut self,
+        table: &str,
+        ops: Vec<(&str, Option<&str>)>,
+    ) -> Result<(), DbError> {
         self.kv.db_transaction(table, ops).await
     }
-    async fn db_get(&mut self, table: &str, key: &str) -> Option<&str> {
+    async fn db_get(&mut self, table: &str, key: &str) -> Result<Option<&str>, DbError> {
         self.kv.db_get(table, key).await
     }
-    async fn db_get_all(&mut self, table: &str) -> Vec<(&str, &str)> {
+    async fn db_get_all(&mut self, table: &str) -> Result<Vec<(&str, &str)>, DbError> {
         self.kv.db_get_all(table).await
     }
-    async fn db_count_keys(&mut self, table: &str) -> usize {
+    async fn db_scan(
+        &mut self,
+        table: &str,
+        prefix: &str,
+        start_after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<(&str, &str)>, DbError> {
+        self.kv.db_scan(table, prefix, start_after, limit).await
+    }
+    async fn db_count_keys(&mut self, table: &str) -> Result<usize, DbError> {
         self.kv.db_count_keys(table).await
     }
-    async fn db_delete_table(&mut self, table: &str) {
+    async fn db_delete_table(&mut self, table: &str) -> Result<(), DbError> {
         self.kv.db_delete_table(table).await
     }
-    async fn db_delete_all(&mut self) {
+    async fn db_delete_all(&mut self) -> Result<(), DbError> {
         self.kv.db_delete_all().await
     }
+    async fn db_data_lost(&mut self, loss: DataLoss) {
+        self.kv.db_data_lost(loss).await
+    }
 }
 
-impl<K, D, Fw, Ts, Hw, Ws> Diagnostics for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws>
+impl<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr> Diagnostics
+    for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr>
 where
     D: Diagnostics,
 {
@@ -72,12 +118,24 @@ where
     async fn diagnostics_upload(&mut self, location: String, timeout: u64) {
         self.diag.diagnostics_upload(location, timeout).await
     }
+    async fn log_upload(
+        &mut self,
+        location: String,
+        file_name: String,
+        content: String,
+        timeout: u64,
+    ) {
+        self.diag
+            .log_upload(location, file_name, content, timeout)
+            .await
+    }
     fn poll_diagnostics_upload(&mut self, cx: &mut Context<'_>) -> Poll<DiagnosticsResponse> {
         self.diag.poll_diagnostics_upload(cx)
     }
 }
 
-impl<K, D, Fw, Ts, Hw, Ws> Firmware for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws>
+impl<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr> Firmware
+    for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr>
 where
     Fw: Firmware,
 {
@@ -87,15 +145,24 @@ where
     async fn firmware_install(&mut self) {
         self.fw.firmware_install().await
     }
+    async fn firmware_verify(&mut self, signing_certificate: String, signature: String) {
+        self.fw
+            .firmware_verify(signing_certificate, signature)
+            .await
+    }
     fn poll_firmware_download(&mut self, cx: &mut Context<'_>) -> Poll<bool> {
         self.fw.poll_firmware_download(cx)
     }
     fn poll_firmware_install(&mut self, cx: &mut Context<'_>) -> Poll<bool> {
         self.fw.poll_firmware_install(cx)
     }
+    fn poll_firmware_verify(&mut self, cx: &mut Context<'_>) -> Poll<bool> {
+        self.fw.poll_firmware_verify(cx)
+    }
 }
 
-impl<K, D, Fw, Ts, Hw, Ws> TimeoutScheduler for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws>
+impl<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr> TimeoutScheduler
+    for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr>
 where
     Ts: TimeoutScheduler,
 {
@@ -113,13 +180,17 @@ where
     }
 }
 
-impl<K, D, Fw, Ts, Hw, Ws> Hardware for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws>
+impl<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr> Hardware
+    for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr>
 where
     Hw: Hardware,
 {
     async fn get_boot_time(&self) -> u64 {
         self.hw.get_boot_time().await
     }
+    async fn get_rtc_time(&self) -> Option<DateTime<Utc>> {
+        self.hw.get_rtc_time().await
+    }
     async fn hard_reset(&mut self) {
         self.hw.hard_reset().await
     }
@@ -133,6 +204,15 @@ where
     ) -> Option<MeterData> {
         self.hw.get_meter_value(connector_id, kind).await
     }
+    async fn set_charging_limit(&mut self, connector_id: usize, limit: ChargingLimit) {
+        self.hw.set_charging_limit(connector_id, limit).await
+    }
+    async fn has_cable_lock(&self, connector_id: usize) -> bool {
+        self.hw.has_cable_lock(connector_id).await
+    }
+    async fn unlock_connector(&mut self, connector_id: usize) -> bool {
+        self.hw.unlock_connector(connector_id).await
+    }
     fn poll_hardware_events(&mut self, cx: &mut Context<'_>) -> Poll<HardwareEvent> {
         self.hw.poll_hardware_events(cx)
     }
@@ -141,12 +221,13 @@ where
     }
 }
 
-impl<K, D, Fw, Ts, Hw, Ws> Websocket for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws>
+impl<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr> Websocket
+    for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr>
 where
     Ws: Websocket,
 {
-    async fn ws_connect(&mut self, url: String) {
-        self.ws.ws_connect(url).await
+    async fn ws_connect(&mut self, options: WsConnectOptions) {
+        self.ws.ws_connect(options).await
     }
     async fn ws_send(&mut self, msg: String) {
         self.ws.ws_send(msg).await
@@ -154,12 +235,78 @@ where
     async fn ws_close(&mut self) {
         self.ws.ws_close().await
     }
+    async fn ws_ping(&mut self) {
+        self.ws.ws_ping().await
+    }
     fn poll_ws_recv(&mut self, cx: &mut Context<'_>) -> Poll<WsEvent> {
         self.ws.poll_ws_recv(cx)
     }
 }
 
-impl<K, D, Fw, Ts, Hw, Ws> ChargePointInterface for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws>
+impl<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr> DataTransfer
+    for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr>
+where
+    Dt: DataTransfer,
+{
+    async fn data_transfer(&mut self, req: DataTransferRequest) -> DataTransferResponse {
+        self.dt.data_transfer(req).await
+    }
+    async fn data_transfer_response(&mut self, res: Option<DataTransferResponse>) {
+        self.dt.data_transfer_response(res).await
+    }
+    fn poll_data_transfer(&mut self, cx: &mut Context<'_>) -> Poll<DataTransferRequest> {
+        self.dt.poll_data_transfer(cx)
+    }
+}
+
+impl<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr> Commands
+    for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr>
+where
+    C: Commands,
+{
+    async fn command_result(&mut self, command: ChargePointCommand, result: CommandResult) {
+        self.cmd.command_result(command, result).await
+    }
+    fn poll_command(&mut self, cx: &mut Context<'_>) -> Poll<ChargePointCommand> {
+        self.cmd.poll_command(cx)
+    }
+}
+
+impl<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr> Observer
+    for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr>
+where
+    Ob: Observer,
+{
+    async fn state_changed(&mut self, change: StateChange, snapshot: ChargePointSnapshot) {
+        self.ob.state_changed(change, snapshot).await
+    }
+}
+
+impl<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr> Crypto
+    for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr>
+where
+    Cr: Crypto,
+{
+    async fn generate_csr(&mut self, organization: String, common_name: String) -> Option<String> {
+        self.cr.generate_csr(organization, common_name).await
+    }
+    async fn certificate_signed(&mut self, certificate_chain: String) -> Option<ClientCertificate> {
+        self.cr.certificate_signed(certificate_chain).await
+    }
+    async fn certificate_hash_data(&mut self, certificate: String) -> Option<CertificateHashData> {
+        self.cr.certificate_hash_data(certificate).await
+    }
+    async fn verify_certificate(
+        &mut self,
+        certificate: String,
+        trust_anchors: Vec<String>,
+    ) -> bool {
+        self.cr.verify_certificate(certificate, trust_anchors).await
+    }
+}
+
+impl<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr> ChargePointInterface
+    for ChargePointInterfaceFacade<K, D, Fw, Ts, Hw, Ws, Dt, C, Ob, Cr>
 where
     K: KeyValueStore,
     D: Diagnostics,
@@ -167,5 +314,9 @@ where
     Ts: TimeoutScheduler,
     Hw: Hardware,
     Ws: Websocket,
+    Dt: DataTransfer,
+    C: Commands,
+    Ob: Observer,
+    Cr: Crypto,
 {
 }
@@ -2,19 +2,59 @@ use alloc::{string::String, vec::Vec};
 use core::task::{Context, Poll};
 
 use chrono::{DateTime, Utc};
-use rocpp_core::v16::types::{
-    ChargePointErrorCode, ChargePointStatus, Location, Measurand, Phase, UnitOfMeasure,
+use rocpp_core::v16::{
+    messages::data_transfer::{DataTransferRequest, DataTransferResponse},
+    types::{
+        AvailabilityType, CertificateHashData, ChargePointErrorCode, ChargePointStatus,
+        ChargingRateUnitType, DiagnosticsStatus, FirmwareStatus, Location, Measurand, Phase,
+        RegistrationStatus, UnitOfMeasure,
+    },
 };
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum DbError {
+    // the storage medium could not be read or written
+    Io,
+    // the store detected damaged data, e.g. a failed checksum
+    Corrupted,
+}
+
+// records that were dropped or reset while recovering from a storage failure
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataLoss {
+    // an undecodable row was moved to the quarantine table
+    Quarantined { table: String, key: String },
+    // an invalid config value was replaced by its default
+    ConfigReset { key: String },
+    // the table could not be read and was treated as empty
+    Unreadable { table: String },
+    // a write to the table failed
+    Unwritable { table: String },
+}
+
 #[allow(async_fn_in_trait)]
 pub trait KeyValueStore {
-    async fn db_init(&mut self);
-    async fn db_transaction(&mut self, table: &str, ops: Vec<(&str, Option<&str>)>);
-    async fn db_get(&mut self, table: &str, key: &str) -> Option<&str>;
-    async fn db_get_all(&mut self, table: &str) -> Vec<(&str, &str)>;
-    async fn db_count_keys(&mut self, table: &str) -> usize;
-    async fn db_delete_table(&mut self, table: &str);
-    async fn db_delete_all(&mut self);
+    async fn db_init(&mut self) -> Result<(), DbError>;
+    async fn db_transaction(
+        &mut self,
+        table: &str,
+        ops: Vec<(&str, Option<&str>)>,
+    ) -> Result<(), DbError>;
+    async fn db_get(&mut self, table: &str, key: &str) -> Result<Option<&str>, DbError>;
+    async fn db_get_all(&mut self, table: &str) -> Result<Vec<(&str, &str)>, DbError>;
+    // up to `limit` rows whose key starts with `prefix`, in key order and
+    // strictly after `start_after`, so large tables can be read page by page
+    async fn db_scan(
+        &mut self,
+        table: &str,
+        prefix: &str,
+        start_after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<(&str, &str)>, DbError>;
+    async fn db_count_keys(&mut self, table: &str) -> Result<usize, DbError>;
+    async fn db_delete_table(&mut self, table: &str) -> Result<(), DbError>;
+    async fn db_delete_all(&mut self) -> Result<(), DbError>;
+    async fn db_data_lost(&mut self, loss: DataLoss);
 }
 
 //diagnostics
@@ -34,6 +74,14 @@ pub trait Diagnostics {
         stop_time: Option<DateTime<Utc>>,
     ) -> Option<String>;
     async fn diagnostics_upload(&mut self, location: String, timeout: u64);
+    // uploads a log assembled by the charge point, the result comes through poll_diagnostics_upload
+    async fn log_upload(
+        &mut self,
+        location: String,
+        file_name: String,
+        content: String,
+        timeout: u64,
+    );
     fn poll_diagnostics_upload(&mut self, cx: &mut Context<'_>) -> Poll<DiagnosticsResponse>;
 }
 
@@ -43,8 +91,21 @@ pub trait Diagnostics {
 pub trait Firmware {
     async fn firmware_download(&mut self, location: String);
     async fn firmware_install(&mut self);
+    // checks the downloaded image against the signature, the signing certificate is already validated
+    async fn firmware_verify(&mut self, signing_certificate: String, signature: String);
     fn poll_firmware_download(&mut self, cx: &mut Context<'_>) -> Poll<bool>;
     fn poll_firmware_install(&mut self, cx: &mut Context<'_>) -> Poll<bool>;
+    fn poll_firmware_verify(&mut self, cx: &mut Context<'_>) -> Poll<bool>;
+}
+
+// data transfer
+
+#[allow(async_fn_in_trait)]
+pub trait DataTransfer {
+    async fn data_transfer(&mut self, req: DataTransferRequest) -> DataTransferResponse;
+    // None if the request could not be delivered, responses come back in request order
+    async fn data_transfer_response(&mut self, res: Option<DataTransferResponse>);
+    fn poll_data_transfer(&mut self, cx: &mut Context<'_>) -> Poll<DataTransferRequest>;
 }
 
 // time
@@ -61,6 +122,10 @@ pub enum TimerId {
     Firmware,
     MeterAligned,
     MeterSampled(usize),
+    ChargingLimit(usize),
+    Reconnect,
+    SecurityProfileFallback,
+    Ping,
 }
 
 #[allow(async_fn_in_trait)]
@@ -84,6 +149,13 @@ pub struct MeterData {
     pub unit: Option<UnitOfMeasure>,
 }
 
+#[derive(Clone, PartialEq, Debug)]
+pub struct ChargingLimit {
+    pub limit: f32,
+    pub unit: ChargingRateUnitType,
+    pub number_phases: i32,
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub enum SeccState {
     Plugged,
@@ -105,6 +177,8 @@ pub enum HardwareEvent {
 #[allow(async_fn_in_trait)]
 pub trait Hardware {
     async fn get_boot_time(&self) -> u64;
+    // None without a battery backed clock, the time then comes from the central system
+    async fn get_rtc_time(&self) -> Option<DateTime<Utc>>;
     async fn hard_reset(&mut self);
     async fn update_status(&mut self, connector_id: usize, status: ChargePointStatus);
     async fn get_meter_value(
@@ -112,31 +186,190 @@ pub trait Hardware {
         connector_id: usize,
         kind: &MeterDataType,
     ) -> Option<MeterData>;
+    async fn set_charging_limit(&mut self, connector_id: usize, limit: ChargingLimit);
+    async fn has_cable_lock(&self, connector_id: usize) -> bool;
+    async fn unlock_connector(&mut self, connector_id: usize) -> bool;
     fn poll_hardware_events(&mut self, cx: &mut Context<'_>) -> Poll<HardwareEvent>;
     fn poll_reset(&mut self, cx: &mut Context<'_>) -> Poll<()>;
 }
 
+// commands
+
+// connector ids follow the OCPP numbering, 0 addresses the whole charge point
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChargePointCommand {
+    StopTransaction(usize),
+    ChangeAvailability(usize, AvailabilityType),
+    TriggerStatusNotification(usize),
+    SoftReset,
+    Shutdown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandResult {
+    Accepted,
+    Rejected,
+    Scheduled,
+}
+
+#[allow(async_fn_in_trait)]
+pub trait Commands {
+    async fn command_result(&mut self, command: ChargePointCommand, result: CommandResult);
+    fn poll_command(&mut self, cx: &mut Context<'_>) -> Poll<ChargePointCommand>;
+}
+
 //ws
 
+// the private key stays with the Crypto implementation, key_handle names it so the
+// Websocket implementation can use it for the TLS handshake
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ClientCertificate {
+    pub certificate: String,
+    pub key_handle: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasicAuth {
+    pub username: String,
+    pub password: String,
+}
+
+// certificates are PEM encoded
+#[derive(Debug, Clone, PartialEq)]
+pub struct WsConnectOptions {
+    pub url: String,
+    pub security_profile: u8,
+    pub basic_auth: Option<BasicAuth>,
+    pub ca_certificate: Option<String>,
+    pub client_certificate: Option<ClientCertificate>,
+}
+
+// after ws_connect the socket reports Connected once the connection is up and
+// Disconnected once that connection is gone, Msg is only valid in between.
+// ConnectFailed ends an attempt that never connected. events out of this order
+// are logged and dropped. Pong answers the last ws_ping and is only valid while
+// connected too
 #[derive(Debug, PartialEq)]
 pub enum WsEvent {
     Connected,
     Disconnected,
+    ConnectFailed,
     Msg(String),
+    Pong,
 }
 
 #[allow(async_fn_in_trait)]
 pub trait Websocket {
-    async fn ws_connect(&mut self, url: String);
+    // makes exactly one attempt, retries are scheduled by the charge point with
+    // the reconnect backoff
+    async fn ws_connect(&mut self, options: WsConnectOptions);
     async fn ws_send(&mut self, msg: String);
+    // also abandons a pending connect attempt without a ConnectFailed,
+    // Disconnected is only reported when a connection was up
     async fn ws_close(&mut self);
+    // sends a websocket ping control frame, the peer's pong comes back as WsEvent::Pong
+    async fn ws_ping(&mut self);
     fn poll_ws_recv(&mut self, cx: &mut Context<'_>) -> Poll<WsEvent>;
 }
 
+// observer
+
+// connector ids follow the OCPP numbering
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ConnectorSnapshot {
+    pub connector_id: usize,
+    pub status: ChargePointStatus,
+    pub local_transaction_id: Option<u32>,
+    pub transaction_id: Option<i32>,
+}
+
+// Estimated until the clock is set, when only the time of an earlier boot is known
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum TimeSource {
+    CentralSystem,
+    Rtc,
+    Estimated,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ChargePointSnapshot {
+    pub connected: bool,
+    pub online: bool,
+    pub registration_status: RegistrationStatus,
+    pub connectors: Vec<ConnectorSnapshot>,
+    pub firmware_status: FirmwareStatus,
+    pub diagnostics_status: DiagnosticsStatus,
+    pub pending_calls: usize,
+    pub transaction_queue_head: u64,
+    pub transaction_queue_tail: u64,
+    pub time_source: Option<TimeSource>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub enum StateChange {
+    ConnectorStatus {
+        connector_id: usize,
+        status: ChargePointStatus,
+    },
+    TransactionStarted {
+        connector_id: usize,
+        local_transaction_id: u32,
+    },
+    TransactionIdAssigned {
+        connector_id: usize,
+        local_transaction_id: u32,
+        transaction_id: i32,
+    },
+    TransactionStopped {
+        connector_id: usize,
+        local_transaction_id: u32,
+        transaction_id: Option<i32>,
+    },
+    RegistrationStatus(RegistrationStatus),
+    Online,
+    Offline,
+    FirmwareStatus(FirmwareStatus),
+    DiagnosticsStatus(DiagnosticsStatus),
+    // a record from a boot that never learnt the time is sent with a guessed timestamp
+    TimestampEstimated {
+        local_transaction_id: Option<u32>,
+    },
+}
+
+#[allow(async_fn_in_trait)]
+pub trait Observer {
+    async fn state_changed(&mut self, change: StateChange, snapshot: ChargePointSnapshot);
+}
+
+// crypto
+
+// the key pair behind a csr never leaves the implementation, so it can live in a secure element
+#[allow(async_fn_in_trait)]
+pub trait Crypto {
+    async fn generate_csr(&mut self, organization: String, common_name: String) -> Option<String>;
+    // checks the chain against the key of the last csr and keeps that key, returns
+    // the chain with the handle of the key for the next connection
+    async fn certificate_signed(&mut self, certificate_chain: String) -> Option<ClientCertificate>;
+    // None if the certificate can not be parsed
+    async fn certificate_hash_data(&mut self, certificate: String) -> Option<CertificateHashData>;
+    // true if the certificate chains up to one of the trust anchors
+    async fn verify_certificate(&mut self, certificate: String, trust_anchors: Vec<String>)
+        -> bool;
+}
+
 // main
 
 pub trait ChargePointInterface:
-    KeyValueStore + Diagnostics + Firmware + TimeoutScheduler + Hardware + Websocket
+    KeyValueStore
+    + Diagnostics
+    + Firmware
+    + TimeoutScheduler
+    + Hardware
+    + Websocket
+    + DataTransfer
+    + Commands
+    + Observer
+    + Crypto
 {
 }
 
@@ -148,5 +381,8 @@ pub enum ChargePointEvent {
     Timeout(TimerId),
     FirmwareDownload(bool),
     FirmwareInstall(bool),
+    FirmwareVerify(bool),
     Diagnostics(DiagnosticsResponse),
+    DataTransfer(DataTransferRequest),
+    Command(ChargePointCommand),
 }
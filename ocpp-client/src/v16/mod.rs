@@ -4,5 +4,5 @@ mod cp;
 mod events;
 mod interfaces;
 
-pub use cp::{ChargePoint, ChargePointConfig};
+pub use cp::{ChargePoint, ChargePointConfig, SmartChargingConfig};
 pub use interfaces::*;
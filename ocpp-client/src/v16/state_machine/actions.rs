@@ -1,4 +1,5 @@
 use alloc::string::String;
+use rocpp_core::v16::types::UnlockStatus;
 
 use crate::v16::{
     cp::ChargePoint,
@@ -6,9 +7,14 @@ use crate::v16::{
 };
 
 impl<I: ChargePointInterface> ChargePoint<I> {
-    pub(crate) async fn connect(&mut self, cms_url: String) {
-        log::debug!("connect, cms_url: {}", cms_url);
-        self.interface.interface.ws_connect(cms_url).await;
+    pub(crate) async fn connect(&mut self) {
+        let options = self.ws_connect_options();
+        log::debug!(
+            "connect, url: {}, security profile: {}",
+            options.url,
+            options.security_profile
+        );
+        self.interface.interface.ws_connect(options).await;
     }
 
     pub(crate) async fn send_ws_msg(&mut self, msg: String) {
@@ -24,16 +30,60 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             .await;
     }
 
+    pub(crate) async fn start_log_upload(
+        &mut self,
+        location: String,
+        file_name: String,
+        content: String,
+        timeout: u64,
+    ) {
+        log::debug!(
+            "start log upload, location: {}, file_name: {}, timeout: {}",
+            location,
+            file_name,
+            timeout
+        );
+        self.interface
+            .interface
+            .log_upload(location, file_name, content, timeout)
+            .await;
+    }
+
     pub(crate) async fn download_firmware(&mut self, location: String) {
         log::debug!("download firmware, location: {}", location);
         self.interface.interface.firmware_download(location).await;
     }
 
+    pub(crate) async fn verify_firmware(&mut self, signing_certificate: String, signature: String) {
+        log::debug!("verify firmware signature");
+        self.interface
+            .interface
+            .firmware_verify(signing_certificate, signature)
+            .await;
+    }
+
     pub(crate) async fn install_firmware(&mut self) {
         log::debug!("install firmware");
         self.interface.interface.firmware_install().await;
     }
 
+    pub(crate) async fn unlock_connector(&mut self, connector_id: usize) -> UnlockStatus {
+        if !self.interface.interface.has_cable_lock(connector_id).await {
+            return UnlockStatus::NotSupported;
+        }
+        log::debug!("unlock connector: {}", connector_id);
+        if self
+            .interface
+            .interface
+            .unlock_connector(connector_id)
+            .await
+        {
+            UnlockStatus::Unlocked
+        } else {
+            UnlockStatus::UnlockFailed
+        }
+    }
+
     pub(crate) async fn add_timeout(&mut self, timer_id: TimerId, timeout_secs: u64) {
         log::trace!(
             "add timeout, id: {:?}, deadline: {:?}",
@@ -14,8 +14,8 @@ pub(crate) enum LocalListChange {
 impl LocalListChange {
     pub fn get_id_tag(&self) -> &str {
         match self {
-            Self::Upsert { id_tag, .. } => &id_tag,
-            Self::Delete { id_tag } => &id_tag,
+            Self::Upsert { id_tag, .. } => id_tag,
+            Self::Delete { id_tag } => id_tag,
         }
     }
 }
@@ -54,7 +54,6 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             // skip
         } else {
             self.interface.db_update_cache(&id_tag, info).await;
-            return;
         }
     }
 
@@ -2,7 +2,7 @@ use rocpp_core::v16::types::RegistrationStatus;
 
 use crate::v16::{
     cp::ChargePoint,
-    interfaces::{ChargePointInterface, TimerId},
+    interfaces::{ChargePointInterface, StateChange, TimerId},
 };
 
 use super::call::CallAction;
@@ -21,13 +21,17 @@ impl<I: ChargePointInterface> ChargePoint<I> {
     }
 
     pub(crate) async fn notify_online(&mut self) {
+        self.notify_state_change(StateChange::Online).await;
+        self.on_outgoing_online().await;
         self.on_heartbeat_online().await;
         self.on_transaction_online().await;
         self.on_status_notification_online().await;
         self.on_firmware_online().await;
+        self.on_security_event_online().await;
     }
 
     pub(crate) async fn notify_offline(&mut self) {
+        self.notify_state_change(StateChange::Offline).await;
         self.on_heartbeat_offline().await;
         self.on_status_notification_offline();
     }
@@ -42,7 +46,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 }
             }
             _ => {
-                unreachable!();
+                log::warn!("boot notification already in progress when connecting");
             }
         }
     }
@@ -53,8 +57,8 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 self.remove_timeout(TimerId::Boot).await;
             }
             BootState::WaitingForResponse => {
-                // got_boot_response will be called before this
-                unreachable!();
+                // the pending call is failed before this, so the response is dropped
+                log::warn!("boot notification still waiting for a response when disconnecting");
             }
             _ => {}
         }
@@ -1,7 +1,4 @@
-use alloc::{
-    string::{String, ToString},
-    vec::Vec,
-};
+use alloc::{string::ToString, vec::Vec};
 use rocpp_core::{
     format::{
         error::GenericError,
@@ -17,6 +14,8 @@ use crate::v16::{
     interfaces::{ChargePointInterface, TimerId},
 };
 
+use super::clock::Instant;
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
 pub(crate) enum CallAction {
     BootNotification,
@@ -28,6 +27,11 @@ pub(crate) enum CallAction {
     StopTransaction,
     DiagnosticsStatusNotification,
     FirmwareStatusNotification,
+    DataTransfer,
+    SignCertificate,
+    SecurityEventNotification,
+    LogStatusNotification,
+    SignedFirmwareStatusNotification,
 }
 
 impl core::fmt::Display for CallAction {
@@ -42,50 +46,302 @@ impl core::fmt::Display for CallAction {
             CallAction::StopTransaction => "StopTransaction",
             CallAction::DiagnosticsStatusNotification => "DiagnosticsStatusNotification",
             CallAction::FirmwareStatusNotification => "FirmwareStatusNotification",
+            CallAction::DataTransfer => "DataTransfer",
+            CallAction::SignCertificate => "SignCertificate",
+            CallAction::SecurityEventNotification => "SecurityEventNotification",
+            CallAction::LogStatusNotification => "LogStatusNotification",
+            CallAction::SignedFirmwareStatusNotification => "SignedFirmwareStatusNotification",
         };
         write!(f, "{s}")
     }
 }
 
-#[derive(PartialEq)]
+impl CallAction {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s {
+            "BootNotification" => Some(CallAction::BootNotification),
+            "Heartbeat" => Some(CallAction::Heartbeat),
+            "Authorize" => Some(CallAction::Authorize),
+            "StatusNotification" => Some(CallAction::StatusNotification),
+            "StartTransaction" => Some(CallAction::StartTransaction),
+            "MeterValues" => Some(CallAction::MeterValues),
+            "StopTransaction" => Some(CallAction::StopTransaction),
+            "DiagnosticsStatusNotification" => Some(CallAction::DiagnosticsStatusNotification),
+            "FirmwareStatusNotification" => Some(CallAction::FirmwareStatusNotification),
+            "DataTransfer" => Some(CallAction::DataTransfer),
+            "SignCertificate" => Some(CallAction::SignCertificate),
+            "SecurityEventNotification" => Some(CallAction::SecurityEventNotification),
+            "LogStatusNotification" => Some(CallAction::LogStatusNotification),
+            "SignedFirmwareStatusNotification" => {
+                Some(CallAction::SignedFirmwareStatusNotification)
+            }
+            _ => None,
+        }
+    }
+    // boot and heartbeat reschedule themselves, transactions and security
+    // events are queued in the db, a delivery policy would send them twice
+    pub(crate) fn has_own_delivery(&self) -> bool {
+        matches!(
+            self,
+            CallAction::BootNotification
+                | CallAction::Heartbeat
+                | CallAction::StartTransaction
+                | CallAction::MeterValues
+                | CallAction::StopTransaction
+                | CallAction::SecurityEventNotification
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum DeliveryPolicy {
+    // a timeout or a dropped connection goes straight to the response handler
+    BestEffort,
+    // resent up to n more times, held across disconnects until then
+    Retry(u32),
+    // resent until the central system answers, held across disconnects and
+    // kept in the db so a reboot sends it again
+    UntilDelivered,
+}
+
+impl DeliveryPolicy {
+    fn allows_attempt(&self, attempts: u32) -> bool {
+        match self {
+            DeliveryPolicy::BestEffort => false,
+            DeliveryPolicy::Retry(retries) => attempts <= *retries,
+            DeliveryPolicy::UntilDelivered => true,
+        }
+    }
+}
+
+const MAX_UNDELIVERED_CALLS: usize = 64;
+// an adapted timeout never goes below this, in seconds
+const MIN_ADAPTIVE_CALL_TIMEOUT: u64 = 2;
+
+// smoothed round trip time and its variation in micros, the way rfc 6298
+// estimates them
+#[derive(Clone, Copy)]
+pub(crate) struct RttEstimate {
+    srtt: u64,
+    rttvar: u64,
+}
+
+impl RttEstimate {
+    fn new(rtt: u64) -> Self {
+        Self {
+            srtt: rtt,
+            rttvar: rtt / 2,
+        }
+    }
+    fn update(&mut self, rtt: u64) {
+        self.rttvar = (3 * self.rttvar + self.srtt.abs_diff(rtt)) / 4;
+        self.srtt = (7 * self.srtt + rtt) / 8;
+    }
+    fn timeout(&self) -> u64 {
+        (self.srtt + 4 * self.rttvar).div_ceil(1_000_000)
+    }
+}
+
 pub(crate) enum OutgoingCallState {
     Idle,
     WaitingForResponse {
-        unique_id: String,
+        call: Call,
         action: CallAction,
+        attempts: u32,
+        sent_at: Instant,
     },
+    // the central system failed on the last call, it is resent once the call
+    // timer runs out
+    Backoff,
 }
 
 impl<I: ChargePointInterface> ChargePoint<I> {
     pub(crate) async fn on_outgoing_offline(&mut self) {
+        if let OutgoingCallState::Backoff = self.outgoing_call_state {
+            self.remove_timeout(TimerId::Call).await;
+            self.outgoing_call_state = OutgoingCallState::Idle;
+        }
+        // the next connection may take another route
+        self.call_rtt = None;
         self.handle_call_response(Err(OcppError::Other(GenericError::Offline)), false)
             .await;
         let drained: Vec<_> = self.pending_calls.drain(..).collect();
-        for (_, action) in drained {
+        for (call, action, attempts) in drained {
+            self.call_failed(
+                call,
+                action,
+                attempts,
+                OcppError::Other(GenericError::Offline),
+            )
+            .await;
+        }
+    }
+
+    pub(crate) async fn on_call_backoff_timeout(&mut self) {
+        self.outgoing_call_state = OutgoingCallState::Idle;
+        self.process_call().await;
+        self.on_outgoing_idle();
+    }
+
+    // calls held back by their delivery policy go out before anything new
+    pub(crate) async fn on_outgoing_online(&mut self) {
+        while let Some(pending) = self.undelivered_calls.pop_back() {
+            self.pending_calls.push_front(pending);
+        }
+        self.process_call().await;
+    }
+
+    // nothing is going to replay them anymore
+    pub(crate) async fn fail_undelivered_calls(&mut self) {
+        let drained: Vec<_> = self.undelivered_calls.drain(..).collect();
+        for (_, action, _) in drained {
             self.dispatch_response(action, Err(OcppError::Other(GenericError::Offline)))
                 .await;
         }
     }
 
+    // queued ahead of the calls of this boot, the boot they were made on never
+    // got them delivered
+    pub(crate) async fn load_held_calls(&mut self) {
+        let (calls, head) = self.interface.db_get_held_calls().await;
+        self.held_call_head = head;
+        let skip = calls.len().saturating_sub(MAX_UNDELIVERED_CALLS);
+        for (i, (index, call)) in calls.into_iter().enumerate() {
+            let action = match CallAction::parse(&call.action) {
+                Some(action) if i >= skip => action,
+                _ => {
+                    log::warn!("dropping a held {}", call.action);
+                    self.interface.db_remove_held_call(index).await;
+                    continue;
+                }
+            };
+            self.held_calls.insert(call.unique_id.clone(), index);
+            self.undelivered_calls.push_back((call, action, 0));
+        }
+    }
+
+    async fn hold_call(&mut self, call: &Call) {
+        let index = self.held_call_head;
+        self.interface.db_push_held_call(index, call).await;
+        self.held_calls.insert(call.unique_id.clone(), index);
+        self.held_call_head += 1;
+    }
+
+    // the call is done with, delivered or given up on
+    async fn release_call(&mut self, call: &Call) {
+        if let Some(index) = self.held_calls.remove(&call.unique_id) {
+            self.interface.db_remove_held_call(index).await;
+        }
+    }
+
     pub(crate) async fn enqueue_call<T: Serialize>(&mut self, action: CallAction, payload: T) {
         let call = Call {
             unique_id: self.get_uuid(),
             action: action.to_string(),
             payload: serde_json::to_value(payload).unwrap(),
         };
-        self.pending_calls.push_back((call, action));
+        if self.delivery_policy(&action) == DeliveryPolicy::UntilDelivered {
+            self.hold_call(&call).await;
+        }
+        // held until online if the policy allows it, otherwise it goes out and
+        // fails on the call timeout
+        if !self.ws_connected
+            && self.undelivered_calls.len() < MAX_UNDELIVERED_CALLS
+            && self.delivery_policy(&action).allows_attempt(0)
+        {
+            self.undelivered_calls.push_back((call, action, 0));
+            return;
+        }
+        self.pending_calls.push_back((call, action, 0));
         self.process_call().await;
     }
 
     pub(crate) async fn process_call(&mut self) {
+        if self.pending_shutdown {
+            return;
+        }
         if let OutgoingCallState::Idle = self.outgoing_call_state {
-            if let Some((call, action)) = self.pending_calls.pop_front() {
+            if let Some((call, action, attempts)) = self.pending_calls.pop_front() {
+                let timeout = self.call_timeout_for(&action);
                 self.send_ws_msg(call.encode()).await;
                 self.outgoing_call_state = OutgoingCallState::WaitingForResponse {
-                    unique_id: call.unique_id,
+                    call,
                     action,
+                    attempts: attempts + 1,
+                    sent_at: Instant::now(&self.interface).await,
                 };
-                self.add_timeout(TimerId::Call, self.call_timeout).await;
+                self.add_timeout(TimerId::Call, timeout).await;
+            }
+        }
+    }
+
+    // the configured timeout is the ceiling, adapting only shortens it
+    fn call_timeout_for(&self, action: &CallAction) -> u64 {
+        let timeout = self
+            .configs
+            .call_action_timeouts
+            .value
+            .iter()
+            .find(|(t, _)| t == action)
+            .map(|(_, timeout)| *timeout)
+            .unwrap_or(self.call_timeout);
+        match &self.call_rtt {
+            Some(rtt) if self.configs.adaptive_call_timeout.value => {
+                rtt.timeout().max(MIN_ADAPTIVE_CALL_TIMEOUT).min(timeout)
+            }
+            _ => timeout,
+        }
+    }
+
+    async fn sample_rtt(&mut self, sent_at: Instant) {
+        let rtt = Instant::now(&self.interface)
+            .await
+            .duration_since(sent_at)
+            .as_micros();
+        match &mut self.call_rtt {
+            Some(estimate) => estimate.update(rtt),
+            None => self.call_rtt = Some(RttEstimate::new(rtt)),
+        }
+    }
+
+    fn delivery_policy(&self, action: &CallAction) -> DeliveryPolicy {
+        if action.has_own_delivery() {
+            return DeliveryPolicy::BestEffort;
+        }
+        self.configs
+            .call_delivery_policy
+            .value
+            .iter()
+            .find(|(t, _)| t == action)
+            .map(|(_, policy)| policy.clone())
+            .unwrap_or(DeliveryPolicy::BestEffort)
+    }
+
+    async fn call_failed(&mut self, call: Call, action: CallAction, attempts: u32, err: OcppError) {
+        if !self.delivery_policy(&action).allows_attempt(attempts) {
+            self.release_call(&call).await;
+            self.dispatch_response(action, Err(err)).await;
+            return;
+        }
+        if self.ws_connected {
+            log::warn!("{} failed with {:?}, sending it again", action, err);
+            let timeout = self.call_timeout_for(&action);
+            self.pending_calls.push_front((call, action, attempts));
+            // answered right away, resending at once would only get the same
+            if let OcppError::Protocol(_) = err {
+                self.outgoing_call_state = OutgoingCallState::Backoff;
+                self.add_timeout(TimerId::Call, timeout).await;
+            }
+            return;
+        }
+        log::warn!("{} failed with {:?}, holding it until online", action, err);
+        self.undelivered_calls.push_back((call, action, attempts));
+        if self.undelivered_calls.len() > MAX_UNDELIVERED_CALLS {
+            if let Some((call, action, _)) = self.undelivered_calls.pop_front() {
+                log::warn!("too many undelivered calls, dropping a held {}", action);
+                self.release_call(&call).await;
+                self.dispatch_response(action, Err(OcppError::Other(GenericError::Offline)))
+                    .await;
             }
         }
     }
@@ -97,19 +353,48 @@ impl<I: ChargePointInterface> ChargePoint<I> {
     ) {
         if let Some(res) = self.match_call_uid(res) {
             self.remove_timeout(TimerId::Call).await;
-            if let OutgoingCallState::WaitingForResponse { action, .. } =
-                core::mem::replace(&mut self.outgoing_call_state, OutgoingCallState::Idle)
+            if let OutgoingCallState::WaitingForResponse {
+                call,
+                action,
+                attempts,
+                sent_at,
+            } = core::mem::replace(&mut self.outgoing_call_state, OutgoingCallState::Idle)
             {
-                self.dispatch_response(action, res).await;
+                // only answers to a first try, a late answer to an earlier try
+                // would look like a short round trip
+                if attempts == 1 && !matches!(res, Err(OcppError::Other(_))) {
+                    self.sample_rtt(sent_at).await;
+                }
+                // the round trip outgrew the estimate, like rfc 6298 backs off the
+                // timer the configured timeout applies again until new samples come in
+                if let Err(OcppError::Other(GenericError::TimeOut)) = res {
+                    self.call_rtt = None;
+                }
+                match res {
+                    Err(err) if err.is_retryable() => {
+                        self.call_failed(call, action, attempts, err).await;
+                    }
+                    res => {
+                        self.release_call(&call).await;
+                        self.dispatch_response(action, res).await;
+                    }
+                }
                 if check_next {
                     self.process_call().await;
                 }
             }
         }
-        if self.outgoing_call_state == OutgoingCallState::Idle {
+        self.on_outgoing_idle();
+    }
+
+    fn on_outgoing_idle(&mut self) {
+        if let OutgoingCallState::Idle = self.outgoing_call_state {
             if let Some(ResetType::Soft) = self.pending_reset {
                 self.soft_reset();
             }
+            if self.pending_shutdown {
+                self.shutdown_now = true;
+            }
         }
     }
 
@@ -151,6 +436,22 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             CallAction::FirmwareStatusNotification => {
                 self.firmware_status_notification_response(Self::parse_response(res))
             }
+            CallAction::DataTransfer => {
+                self.data_transfer_response(Self::parse_response(res)).await
+            }
+            CallAction::SignCertificate => {
+                self.sign_certificate_response(Self::parse_response(res))
+            }
+            CallAction::SecurityEventNotification => {
+                self.security_event_notification_response(Self::parse_response(res))
+                    .await
+            }
+            CallAction::LogStatusNotification => {
+                self.log_status_notification_response(Self::parse_response(res))
+            }
+            CallAction::SignedFirmwareStatusNotification => {
+                self.signed_firmware_status_notification_response(Self::parse_response(res))
+            }
         }
     }
 
@@ -159,12 +460,12 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         res: Result<CallResponse<ProtocolError>, OcppError>,
     ) -> Option<Result<serde_json::Value, OcppError>> {
         match &self.outgoing_call_state {
-            OutgoingCallState::Idle => None,
-            OutgoingCallState::WaitingForResponse { unique_id, .. } => match res {
-                Ok(CallResponse::CallResult(msg)) if msg.unique_id == *unique_id => {
+            OutgoingCallState::Idle | OutgoingCallState::Backoff => None,
+            OutgoingCallState::WaitingForResponse { call, .. } => match res {
+                Ok(CallResponse::CallResult(msg)) if msg.unique_id == call.unique_id => {
                     Some(Ok(msg.payload))
                 }
-                Ok(CallResponse::CallError(err)) if err.unique_id == *unique_id => {
+                Ok(CallResponse::CallError(err)) if err.unique_id == call.unique_id => {
                     Some(Err(OcppError::Protocol(err.error_code)))
                 }
                 Err(e) => Some(Err(e)),
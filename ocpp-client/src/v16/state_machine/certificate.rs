@@ -0,0 +1,121 @@
+use alloc::{format, string::String, vec::Vec};
+use rocpp_core::v16::{
+    messages::sign_certificate::SignCertificateRequest,
+    types::{CertificateHashData, CertificateUse, DeleteCertificateStatus},
+};
+
+use crate::v16::{
+    cp::ChargePoint,
+    interfaces::{ChargePointInterface, ClientCertificate},
+    state_machine::call::CallAction,
+};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub(crate) struct InstalledCertificate {
+    pub certificate_type: CertificateUse,
+    pub certificate: String,
+    pub hash_data: CertificateHashData,
+}
+
+impl InstalledCertificate {
+    pub fn key(&self) -> String {
+        format!(
+            "{}:{}",
+            self.hash_data.issuer_key_hash, self.hash_data.serial_number
+        )
+    }
+}
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    // installed central system roots take over from the configured ca certificate
+    pub(crate) fn ca_bundle(&self) -> Option<String> {
+        let installed: Vec<&str> = self
+            .installed_certificates
+            .iter()
+            .filter(|t| t.certificate_type == CertificateUse::CentralSystemRootCertificate)
+            .map(|t| t.certificate.as_str())
+            .collect();
+        if installed.is_empty() {
+            self.ca_certificate.clone()
+        } else {
+            Some(installed.join("\n"))
+        }
+    }
+    pub(crate) async fn install_certificate(&mut self, certificate: InstalledCertificate) {
+        self.installed_certificates
+            .retain(|t| t.hash_data != certificate.hash_data);
+        self.interface.db_add_certificate(&certificate).await;
+        self.installed_certificates.push(certificate);
+    }
+    pub(crate) async fn delete_certificate(
+        &mut self,
+        hash_data: &CertificateHashData,
+    ) -> DeleteCertificateStatus {
+        let Some(index) = self
+            .installed_certificates
+            .iter()
+            .position(|t| t.hash_data == *hash_data)
+        else {
+            return DeleteCertificateStatus::NotFound;
+        };
+        let certificate_type = &self.installed_certificates[index].certificate_type;
+        let remaining_roots = self
+            .installed_certificates
+            .iter()
+            .filter(|t| t.certificate_type == CertificateUse::CentralSystemRootCertificate)
+            .count();
+        // never leave a tls profile without a trust anchor
+        if *certificate_type == CertificateUse::CentralSystemRootCertificate
+            && remaining_roots == 1
+            && self.ca_certificate.is_none()
+            && self.configs.security_profile.value >= 2
+        {
+            return DeleteCertificateStatus::Failed;
+        }
+        let certificate = self.installed_certificates.remove(index);
+        self.interface.db_remove_certificate(&certificate).await;
+        DeleteCertificateStatus::Accepted
+    }
+    pub(crate) async fn generate_csr(&mut self) -> Option<String> {
+        let organization = self.configs.cpo_name.value.clone();
+        let common_name = self.charge_point_id();
+        self.interface
+            .interface
+            .generate_csr(organization, common_name)
+            .await
+    }
+    pub(crate) async fn send_sign_certificate(&mut self, csr: String) {
+        let payload = SignCertificateRequest { csr };
+        self.enqueue_call(CallAction::SignCertificate, payload)
+            .await;
+    }
+    pub(crate) async fn update_client_certificate(&mut self, certificate: ClientCertificate) {
+        self.interface
+            .db_update_client_certificate(&certificate)
+            .await;
+        self.client_certificate = Some(certificate);
+    }
+    pub(crate) fn installed_certificate_hashes(
+        &self,
+        certificate_type: &CertificateUse,
+    ) -> Vec<CertificateHashData> {
+        self.installed_certificates
+            .iter()
+            .filter(|t| t.certificate_type == *certificate_type)
+            .map(|t| t.hash_data.clone())
+            .collect()
+    }
+    pub(crate) fn installed_certificates_of(
+        &self,
+        certificate_type: &CertificateUse,
+    ) -> Vec<String> {
+        self.installed_certificates
+            .iter()
+            .filter(|t| t.certificate_type == *certificate_type)
+            .map(|t| t.certificate.clone())
+            .collect()
+    }
+    pub(crate) fn certificate_chain_too_long(&self, certificate_chain: &str) -> bool {
+        certificate_chain.len() > self.configs.certificate_signed_max_chain_size.value
+    }
+}
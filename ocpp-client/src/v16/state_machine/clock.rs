@@ -1,12 +1,40 @@
 use core::time::Duration;
 
-use chrono::{DateTime, Utc};
+use alloc::format;
+use chrono::{DateTime, TimeDelta, Utc};
 
 use crate::v16::{
     cp::ChargePoint,
-    interfaces::{ChargePointBackend, ChargePointInterface},
+    interfaces::{ChargePointBackend, ChargePointInterface, TimeSource},
 };
 
+use super::security_event::SecurityEventType;
+
+// heartbeats correct small drifts, those are not worth a security event or
+// a new clock anchor
+const TIME_CHANGE_EVENT_SECS: i64 = 60;
+// anchors of older boots are dropped, records that old are estimated
+pub(crate) const MAX_CLOCK_ANCHORS: u64 = 16;
+
+// wall clock time at a given uptime of one boot
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug)]
+pub(crate) struct ClockAnchor {
+    pub time: DateTime<Utc>,
+    pub uptime: u64,
+}
+
+impl ClockAnchor {
+    fn time_at(&self, uptime: u64) -> DateTime<Utc> {
+        self.time + TimeDelta::microseconds(uptime as i64 - self.uptime as i64)
+    }
+    // for a later boot. uptime that went backwards means the hardware restarted
+    // in between, so all that is known is that the record is newer than the anchor
+    fn time_after(&self, uptime: u64) -> DateTime<Utc> {
+        let elapsed = uptime.checked_sub(self.uptime).unwrap_or(uptime);
+        self.time + Duration::from_micros(elapsed)
+    }
+}
+
 #[derive(Clone, Copy)]
 pub(crate) struct Instant(u64);
 
@@ -17,6 +45,12 @@ impl Instant {
     pub async fn now<I: ChargePointInterface>(interface: &ChargePointBackend<I>) -> Self {
         Self(interface.interface.get_boot_time().await)
     }
+    pub fn from_micros(micros: u64) -> Self {
+        Self(micros)
+    }
+    pub fn as_micros(self) -> u64 {
+        self.0
+    }
     pub fn duration_since(self, other: Self) -> Self {
         Self(self.0.saturating_sub(other.0))
     }
@@ -30,17 +64,84 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             base_dt + Duration::from_micros(elapsed as u64)
         })
     }
+    // also works for instants from before the time was set
     pub(crate) fn get_time_since(&self, t: Instant) -> Option<DateTime<Utc>> {
         self.base_time.map(|(base_dt, base_instant)| {
-            base_dt + Duration::from_micros(t.duration_since(base_instant).0 as u64)
+            ClockAnchor {
+                time: base_dt,
+                uptime: base_instant.0,
+            }
+            .time_at(t.0)
         })
     }
-    pub(crate) async fn set_time(&mut self, dt: DateTime<Utc>) {
-        let was_uninitialized = self.base_time.is_none();
-        self.base_time = Some((dt, Instant::now(&self.interface).await));
+    // returns None if the record belongs to a boot that never learnt the time
+    pub(crate) fn get_boot_time_since(&self, boot: u64, t: Instant) -> Option<DateTime<Utc>> {
+        if boot == self.boot_id {
+            return self.get_time_since(t);
+        }
+        self.clock_anchors
+            .get(&boot)
+            .map(|anchor| anchor.time_at(t.as_micros()))
+    }
+    // lower bound from the last anchor before that boot
+    pub(crate) fn estimate_boot_time_since(&self, boot: u64, t: Instant) -> Option<DateTime<Utc>> {
+        self.clock_anchors
+            .range(..boot)
+            .next_back()
+            .map(|(_, anchor)| anchor.time_after(t.as_micros()))
+    }
+    pub(crate) async fn restore_time(&mut self) {
+        match self.interface.interface.get_rtc_time().await {
+            Some(time) => self.set_time(time, TimeSource::Rtc).await,
+            None if !self.clock_anchors.is_empty() => {
+                log::warn!("no rtc, offline records may need estimated timestamps");
+                self.time_source = Some(TimeSource::Estimated);
+            }
+            None => {}
+        }
+    }
+    pub(crate) async fn set_time(&mut self, dt: DateTime<Utc>, source: TimeSource) {
+        let previous = self.get_time().await;
+        let was_uninitialized = previous.is_none();
+        let source_changed = self.time_source != Some(source);
+        let now = Instant::now(&self.interface).await;
+        self.base_time = Some((dt, now));
+        self.time_source = Some(source);
+        // the stored anchor is good enough for small corrections, skipping those
+        // spares the flash a write on every heartbeat
+        let jumped = previous.is_none_or(|previous| {
+            (dt - previous).abs() > TimeDelta::seconds(TIME_CHANGE_EVENT_SECS)
+        });
+        if source_changed || jumped {
+            let anchor = ClockAnchor {
+                time: dt,
+                uptime: now.as_micros(),
+            };
+            self.interface
+                .db_update_clock_anchor(self.boot_id, &anchor)
+                .await;
+        }
+        match previous {
+            None => {
+                self.raise_security_event(SecurityEventType::SettingSystemTime, None)
+                    .await;
+            }
+            Some(previous)
+                if (dt - previous).abs() > TimeDelta::seconds(TIME_CHANGE_EVENT_SECS) =>
+            {
+                self.raise_security_event(
+                    SecurityEventType::SettingSystemTime,
+                    Some(format!("changed from {}", previous)),
+                )
+                .await;
+            }
+            _ => {}
+        }
         if was_uninitialized {
             self.set_aligned_meter_sleep_state().await;
+            self.on_firmware_time_known().await;
         }
+        self.update_charging_limits().await;
     }
     pub(crate) fn default_time(&self) -> DateTime<Utc> {
         DateTime::<Utc>::MIN_UTC
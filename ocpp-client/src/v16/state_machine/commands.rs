@@ -0,0 +1,66 @@
+use rocpp_core::v16::types::{AvailabilityStatus, Reason, ResetType};
+
+use crate::v16::{
+    cp::ChargePoint,
+    interfaces::{ChargePointCommand, ChargePointInterface, CommandResult},
+    state_machine::connector::ConnectorState,
+};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) async fn handle_command(&mut self, command: ChargePointCommand) {
+        let result = match command.clone() {
+            ChargePointCommand::StopTransaction(connector_id) => {
+                if connector_id == 0 || connector_id > self.configs.number_of_connectors.value {
+                    CommandResult::Rejected
+                } else if let ConnectorState::Transaction { .. } =
+                    &self.connector_state[connector_id - 1]
+                {
+                    self.stop_transaction(connector_id - 1, None, Some(Reason::Local))
+                        .await;
+                    CommandResult::Accepted
+                } else {
+                    CommandResult::Rejected
+                }
+            }
+            ChargePointCommand::ChangeAvailability(connector_id, kind) => {
+                let (status, changes) = self.change_availability(connector_id, kind).await;
+                for (connector_id, state) in changes {
+                    self.change_connector_state(connector_id, state).await;
+                }
+                match status {
+                    AvailabilityStatus::Accepted => CommandResult::Accepted,
+                    AvailabilityStatus::Rejected => CommandResult::Rejected,
+                    AvailabilityStatus::Scheduled => CommandResult::Scheduled,
+                }
+            }
+            ChargePointCommand::TriggerStatusNotification(connector_id) => {
+                if connector_id > self.configs.number_of_connectors.value {
+                    CommandResult::Rejected
+                } else {
+                    self.trigger_status_notification(connector_id).await;
+                    CommandResult::Accepted
+                }
+            }
+            ChargePointCommand::SoftReset => {
+                if self.pending_reset.is_some() {
+                    CommandResult::Rejected
+                } else {
+                    self.reset(ResetType::Soft, None).await;
+                    CommandResult::Accepted
+                }
+            }
+            ChargePointCommand::Shutdown => {
+                if self.pending_shutdown {
+                    CommandResult::Rejected
+                } else {
+                    self.shutdown();
+                    CommandResult::Accepted
+                }
+            }
+        };
+        self.interface
+            .interface
+            .command_result(command, result)
+            .await;
+    }
+}
@@ -7,10 +7,17 @@ use alloc::{
     vec::Vec,
 };
 
-use rocpp_core::v16::types::{Measurand, Phase};
+use rocpp_core::v16::types::{ChargingRateUnitType, Measurand, Phase};
 
 use crate::v16::interfaces::{ChargePointBackend, ChargePointInterface, MeterDataType};
 
+use super::{
+    call::{CallAction, DeliveryPolicy},
+    transaction::QueueDropPolicy,
+};
+
+type ConfigValidator<T> = Box<dyn Fn(&T) -> bool + Send>;
+
 pub(crate) struct OcppConfig<T> {
     pub key: String,
     pub raw: String,
@@ -20,7 +27,7 @@ pub(crate) struct OcppConfig<T> {
     pub reboot_required: bool,
     pub parser_fn: fn(&str) -> Option<T>,
     pub format_fn: fn(&T) -> String,
-    pub validator: Option<Box<dyn Fn(&T) -> bool + Send>>,
+    pub validator: Option<ConfigValidator<T>>,
 }
 
 impl<T> OcppConfig<T> {
@@ -57,6 +64,10 @@ impl<T> OcppConfig<T> {
         self.format_fn = format_fn;
         self
     }
+    pub fn with_validator(mut self, validator: impl Fn(&T) -> bool + Send + 'static) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
     pub fn read(mut self) -> Self {
         self.read = true;
         self
@@ -126,7 +137,29 @@ macro_rules! config_key_map {
             "ResetRetries" => reset_retries,
             "GetConfigurationMaxKeys" => get_configuration_max_keys,
             "SupportedFeatureProfiles" => supported_feature_profiles,
-            "UnlockConnectorOnEVSideDisconnect" => unlock_connector_on_evside_disconnect
+            "UnlockConnectorOnEVSideDisconnect" => unlock_connector_on_evside_disconnect,
+            "ChargeProfileMaxStackLevel" => charge_profile_max_stack_level,
+            "ChargingScheduleAllowedChargingRateUnit" => charging_schedule_allowed_charging_rate_unit,
+            "ChargingScheduleMaxPeriods" => charging_schedule_max_periods,
+            "MaxChargingProfilesInstalled" => max_charging_profiles_installed,
+            "ReconnectBackoffInitialInterval" => reconnect_backoff_initial_interval,
+            "ReconnectBackoffMaxInterval" => reconnect_backoff_max_interval,
+            "SecurityProfile" => security_profile,
+            "AuthorizationKey" => authorization_key,
+            "SecurityProfileFallbackTimeout" => security_profile_fallback_timeout,
+            "CertificateSignedMaxChainSize" => certificate_signed_max_chain_size,
+            "CertificateStoreMaxLength" => certificate_store_max_length,
+            "CpoName" => cpo_name,
+            "SecurityEventNotificationEnabled" => security_event_notification_enabled,
+            "MeterValuesBatchMaxLength" => meter_values_batch_max_length,
+            "TransactionQueueMaxLength" => transaction_queue_max_length,
+            "TransactionQueueDropPolicy" => transaction_queue_drop_policy,
+            "CallDeliveryPolicy" => call_delivery_policy,
+            "CallActionTimeouts" => call_action_timeouts,
+            "AdaptiveCallTimeout" => adaptive_call_timeout,
+            "WebSocketPingInterval" => web_socket_ping_interval,
+            "WebSocketPongTimeout" => web_socket_pong_timeout,
+            "HeartbeatMaxSkipInterval" => heartbeat_max_skip_interval
         } $(, $args)*)
     };
 }
@@ -139,9 +172,7 @@ macro_rules! gen_update_match {
             $(
                 $key_str => $this.init_config(|s| &mut s.$field, $key.to_string(), $raw),
             )+
-            _ => {
-                unreachable!();
-            }
+            _ => false,
         }
     };
 }
@@ -178,6 +209,28 @@ pub(crate) struct OcppConfigs {
     pub get_configuration_max_keys: OcppConfig<usize>,
     pub supported_feature_profiles: OcppConfig<String>,
     pub unlock_connector_on_evside_disconnect: OcppConfig<bool>,
+    pub charge_profile_max_stack_level: OcppConfig<usize>,
+    pub charging_schedule_allowed_charging_rate_unit: OcppConfig<Vec<ChargingRateUnitType>>,
+    pub charging_schedule_max_periods: OcppConfig<usize>,
+    pub max_charging_profiles_installed: OcppConfig<usize>,
+    pub reconnect_backoff_initial_interval: OcppConfig<u64>,
+    pub reconnect_backoff_max_interval: OcppConfig<u64>,
+    pub security_profile: OcppConfig<u8>,
+    pub authorization_key: OcppConfig<String>,
+    pub security_profile_fallback_timeout: OcppConfig<u64>,
+    pub certificate_signed_max_chain_size: OcppConfig<usize>,
+    pub certificate_store_max_length: OcppConfig<usize>,
+    pub cpo_name: OcppConfig<String>,
+    pub security_event_notification_enabled: OcppConfig<bool>,
+    pub meter_values_batch_max_length: OcppConfig<usize>,
+    pub transaction_queue_max_length: OcppConfig<u64>,
+    pub transaction_queue_drop_policy: OcppConfig<QueueDropPolicy>,
+    pub call_delivery_policy: OcppConfig<Vec<(CallAction, DeliveryPolicy)>>,
+    pub call_action_timeouts: OcppConfig<Vec<(CallAction, u64)>>,
+    pub adaptive_call_timeout: OcppConfig<bool>,
+    pub web_socket_ping_interval: OcppConfig<u64>,
+    pub web_socket_pong_timeout: OcppConfig<u64>,
+    pub heartbeat_max_skip_interval: OcppConfig<u64>,
 }
 
 impl OcppConfigs {
@@ -209,22 +262,22 @@ impl OcppConfigs {
                 .reboot_required(),
             meter_values_sampled_data: OcppConfig::<Vec<MeterDataType>>::new()
                 .with_parse(MeterDataType::parse_meter_data)
-                .with_format_fn(MeterDataType::format_meter_data)
+                .with_format_fn(|v| MeterDataType::format_meter_data(v))
                 .read()
                 .write(),
             stop_transaction_sampled_data: OcppConfig::<Vec<MeterDataType>>::new()
                 .with_parse(MeterDataType::parse_meter_data)
-                .with_format_fn(MeterDataType::format_meter_data)
+                .with_format_fn(|v| MeterDataType::format_meter_data(v))
                 .read()
                 .write(),
             meter_values_aligned_data: OcppConfig::<Vec<MeterDataType>>::new()
                 .with_parse(MeterDataType::parse_meter_data)
-                .with_format_fn(MeterDataType::format_meter_data)
+                .with_format_fn(|v| MeterDataType::format_meter_data(v))
                 .read()
                 .write(),
             stop_transaction_aligned_data: OcppConfig::<Vec<MeterDataType>>::new()
                 .with_parse(MeterDataType::parse_meter_data)
-                .with_format_fn(MeterDataType::format_meter_data)
+                .with_format_fn(|v| MeterDataType::format_meter_data(v))
                 .read()
                 .write(),
             meter_values_sampled_data_max_length: OcppConfig::<usize>::new().with_std().read(),
@@ -242,29 +295,98 @@ impl OcppConfigs {
             reset_retries: OcppConfig::<usize>::new().with_std().read().write(),
             get_configuration_max_keys: OcppConfig::<usize>::new().with_std().read(),
             supported_feature_profiles: OcppConfig::<String>::new().with_std().read(),
-            unlock_connector_on_evside_disconnect: OcppConfig::<bool>::new().with_std().read(),
+            unlock_connector_on_evside_disconnect: OcppConfig::<bool>::new()
+                .with_std()
+                .read()
+                .write(),
+            charge_profile_max_stack_level: OcppConfig::<usize>::new().with_std().read(),
+            charging_schedule_allowed_charging_rate_unit:
+                OcppConfig::<Vec<ChargingRateUnitType>>::new()
+                    .with_parse(parse_charging_rate_units)
+                    .with_format_fn(|v| format_charging_rate_units(v))
+                    .read(),
+            charging_schedule_max_periods: OcppConfig::<usize>::new().with_std().read(),
+            max_charging_profiles_installed: OcppConfig::<usize>::new().with_std().read(),
+            reconnect_backoff_initial_interval: OcppConfig::<u64>::new().with_std().read().write(),
+            reconnect_backoff_max_interval: OcppConfig::<u64>::new().with_std().read().write(),
+            security_profile: OcppConfig::<u8>::new().with_std().read().write(),
+            authorization_key: OcppConfig::<String>::new()
+                .with_std()
+                .write()
+                .with_validator(|v| (16..=40).contains(&v.len())),
+            security_profile_fallback_timeout: OcppConfig::<u64>::new().with_std().read().write(),
+            certificate_signed_max_chain_size: OcppConfig::<usize>::new().with_std().read(),
+            certificate_store_max_length: OcppConfig::<usize>::new().with_std().read(),
+            cpo_name: OcppConfig::<String>::new().with_std().read().write(),
+            security_event_notification_enabled: OcppConfig::<bool>::new()
+                .with_std()
+                .read()
+                .write(),
+            meter_values_batch_max_length: OcppConfig::<usize>::new().with_std().read().write(),
+            transaction_queue_max_length: OcppConfig::<u64>::new().with_std().read().write(),
+            transaction_queue_drop_policy: OcppConfig::<QueueDropPolicy>::new()
+                .with_parse(parse_queue_drop_policy)
+                .with_format_fn(format_queue_drop_policy)
+                .read()
+                .write(),
+            call_delivery_policy: OcppConfig::<Vec<(CallAction, DeliveryPolicy)>>::new()
+                .with_parse(parse_call_delivery_policy)
+                .with_format_fn(|v| format_call_delivery_policy(v))
+                .read()
+                .write(),
+            call_action_timeouts: OcppConfig::<Vec<(CallAction, u64)>>::new()
+                .with_parse(parse_call_action_timeouts)
+                .with_format_fn(|v| format_call_action_timeouts(v))
+                .read()
+                .write(),
+            adaptive_call_timeout: OcppConfig::<bool>::new().with_std().read().write(),
+            web_socket_ping_interval: OcppConfig::<u64>::new().with_std().read().write(),
+            web_socket_pong_timeout: OcppConfig::<u64>::new()
+                .with_std()
+                .read()
+                .write()
+                .with_validator(|v| *v > 0),
+            heartbeat_max_skip_interval: OcppConfig::<u64>::new().with_std().read().write(),
         }
     }
-    pub fn build(db_configs: Vec<(&str, &str)>) -> Self {
+    // values that fail to parse fall back to their defaults, the keys of those
+    // are returned so the stored rows can be repaired
+    pub fn build(
+        defaults: &[(String, String)],
+        db_configs: Vec<(String, String)>,
+    ) -> (Self, Vec<String>) {
         let mut config = Self::new();
+        for (key, value) in defaults {
+            let key = key.as_str();
+            let value = value.clone();
+            if !config_key_map!(gen_update_match, config, key, value) {
+                panic!("invalid default for config {}", key);
+            }
+        }
+        let mut invalid = Vec::new();
         for (key, value) in db_configs {
-            let value = value.to_string();
-            config_key_map!(gen_update_match, config, key, value)
+            let key_ref = key.as_str();
+            if !config_key_map!(gen_update_match, config, key_ref, value) {
+                log::error!("invalid stored value for config {}", key);
+                invalid.push(key);
+            }
         }
-        config
+        (config, invalid)
     }
     fn init_config<T>(
         &mut self,
         accessor: fn(&mut Self) -> &mut OcppConfig<T>,
         key: String,
         raw: String,
-    ) {
+    ) -> bool {
         let cfg_ref = accessor(self);
-
-        let actual = (cfg_ref.parser_fn)(&raw).unwrap();
+        let Some(actual) = (cfg_ref.parser_fn)(&raw) else {
+            return false;
+        };
         cfg_ref.key = key;
         cfg_ref.raw = raw;
         cfg_ref.value = actual;
+        true
     }
 }
 
@@ -306,7 +428,7 @@ impl MeterDataType {
             })
             .collect()
     }
-    fn format_meter_data(v: &Vec<MeterDataType>) -> String {
+    fn format_meter_data(v: &[MeterDataType]) -> String {
         v.iter()
             .map(|data| {
                 let m = &data.measurand;
@@ -328,3 +450,89 @@ impl MeterDataType {
             .join(",")
     }
 }
+
+fn parse_charging_rate_units(s: &str) -> Option<Vec<ChargingRateUnitType>> {
+    s.split(',')
+        .map(|token| match token.trim() {
+            "Current" => Some(ChargingRateUnitType::A),
+            "Power" => Some(ChargingRateUnitType::W),
+            _ => None,
+        })
+        .collect()
+}
+
+fn parse_queue_drop_policy(s: &str) -> Option<QueueDropPolicy> {
+    match s {
+        "ThinSampled" => Some(QueueDropPolicy::ThinSampled),
+        "DropOldest" => Some(QueueDropPolicy::DropOldest),
+        _ => None,
+    }
+}
+
+fn format_queue_drop_policy(v: &QueueDropPolicy) -> String {
+    match v {
+        QueueDropPolicy::ThinSampled => "ThinSampled",
+        QueueDropPolicy::DropOldest => "DropOldest",
+    }
+    .to_string()
+}
+
+// comma separated Action=Policy pairs, the policy is BestEffort, Retry:<n>
+// or UntilDelivered
+fn parse_call_delivery_policy(s: &str) -> Option<Vec<(CallAction, DeliveryPolicy)>> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            let (action, policy) = token.split_once('=')?;
+            let action = CallAction::parse(action.trim()).filter(|t| !t.has_own_delivery())?;
+            let policy = match policy.trim() {
+                "BestEffort" => DeliveryPolicy::BestEffort,
+                "UntilDelivered" => DeliveryPolicy::UntilDelivered,
+                t => DeliveryPolicy::Retry(t.strip_prefix("Retry:")?.parse().ok()?),
+            };
+            Some((action, policy))
+        })
+        .collect()
+}
+
+fn format_call_delivery_policy(v: &[(CallAction, DeliveryPolicy)]) -> String {
+    v.iter()
+        .map(|(action, policy)| match policy {
+            DeliveryPolicy::BestEffort => format!("{action}=BestEffort"),
+            DeliveryPolicy::Retry(retries) => format!("{action}=Retry:{retries}"),
+            DeliveryPolicy::UntilDelivered => format!("{action}=UntilDelivered"),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// comma separated Action=seconds pairs, actions not listed use the call timeout
+fn parse_call_action_timeouts(s: &str) -> Option<Vec<(CallAction, u64)>> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            let (action, timeout) = token.split_once('=')?;
+            let timeout = timeout.trim().parse().ok().filter(|t| *t > 0)?;
+            Some((CallAction::parse(action.trim())?, timeout))
+        })
+        .collect()
+}
+
+fn format_call_action_timeouts(v: &[(CallAction, u64)]) -> String {
+    v.iter()
+        .map(|(action, timeout)| format!("{action}={timeout}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn format_charging_rate_units(v: &[ChargingRateUnitType]) -> String {
+    v.iter()
+        .map(|unit| match unit {
+            ChargingRateUnitType::A => "Current",
+            ChargingRateUnitType::W => "Power",
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -6,7 +6,7 @@ use rocpp_core::v16::{
 
 use crate::v16::{
     cp::ChargePoint,
-    interfaces::{ChargePointInterface, SeccState, TimerId},
+    interfaces::{ChargePointInterface, SeccState, StateChange, TimerId},
 };
 
 use super::call::CallAction;
@@ -176,7 +176,10 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     }
                 }
                 _ => {
-                    unreachable!();
+                    log::warn!(
+                        "status notification already online for connector {}",
+                        connector_id + 1
+                    );
                 }
             }
         }
@@ -196,9 +199,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     self.connector_status_notification_state[connector_id] =
                         StatusNotificationState::Offline(Some(last_sent.clone()))
                 }
-                _ => {
-                    unreachable!();
-                }
+                StatusNotificationState::Offline(_) => {}
             }
         }
     }
@@ -218,11 +219,16 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 .await;
             self.update_status_notification_state(
                 connector_id,
-                new_status_notification_state,
+                new_status_notification_state.clone(),
                 error_code.unwrap_or(ChargePointErrorCode::NoError),
                 info,
             )
             .await;
+            self.notify_state_change(StateChange::ConnectorStatus {
+                connector_id: connector_id + 1,
+                status: new_status_notification_state,
+            })
+            .await;
         }
     }
     pub(crate) async fn change_connector_state_with_error_code(
@@ -283,10 +289,8 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             vendor_error_code: None,
         };
         match &self.connector_status_notification_state[connector_id] {
-            StatusNotificationState::Idle => {
-                if previous_status != status {
-                    self.stabilize(connector_id, previous_status).await;
-                }
+            StatusNotificationState::Idle if previous_status != status => {
+                self.stabilize(connector_id, previous_status).await;
             }
             StatusNotificationState::Stabilizing(last_sent) => {
                 if *last_sent != status {
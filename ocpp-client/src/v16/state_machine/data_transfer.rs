@@ -0,0 +1,15 @@
+use rocpp_core::v16::messages::data_transfer::DataTransferRequest;
+
+use crate::v16::{
+    cp::ChargePoint, interfaces::ChargePointInterface, state_machine::call::CallAction,
+};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) async fn send_data_transfer(&mut self, req: DataTransferRequest) {
+        if !self.call_permission() {
+            self.interface.interface.data_transfer_response(None).await;
+            return;
+        }
+        self.enqueue_call(CallAction::DataTransfer, req).await;
+    }
+}
@@ -1,17 +1,32 @@
 use alloc::string::String;
 use rocpp_core::v16::{
-    messages::diagnostics_status_notification::DiagnosticsStatusNotificationRequest,
-    types::DiagnosticsStatus,
+    messages::{
+        diagnostics_status_notification::DiagnosticsStatusNotificationRequest,
+        log_status_notification::LogStatusNotificationRequest,
+    },
+    types::{DiagnosticsStatus, UploadLogStatus},
 };
 
-use crate::v16::{cp::ChargePoint, interfaces::ChargePointInterface};
+use crate::v16::{
+    cp::ChargePoint,
+    interfaces::{ChargePointInterface, StateChange},
+};
 
 use super::call::CallAction;
 
+// an upload started by GetLog, reported through LogStatusNotification
+pub(crate) struct LogUploadInfo {
+    pub request_id: i32,
+    pub file_name: String,
+    // None for the diagnostics log, the interface provides the file then
+    pub content: Option<String>,
+}
+
 pub(crate) struct DiagnosticsUploadInfo {
     pub retry_left: u64,
     pub retry_interval: u64,
     pub location: String,
+    pub log: Option<LogUploadInfo>,
 }
 
 pub(crate) enum DiagnosticsState {
@@ -21,31 +36,96 @@ pub(crate) enum DiagnosticsState {
 
 impl<I: ChargePointInterface> ChargePoint<I> {
     pub(crate) async fn send_diagnostics_status_notification(&mut self, status: DiagnosticsStatus) {
+        self.notify_state_change(StateChange::DiagnosticsStatus(status.clone()))
+            .await;
         let payload = DiagnosticsStatusNotificationRequest { status };
         self.enqueue_call(CallAction::DiagnosticsStatusNotification, payload)
             .await;
     }
+    pub(crate) async fn send_log_status_notification(
+        &mut self,
+        status: UploadLogStatus,
+        request_id: Option<i32>,
+    ) {
+        let payload = LogStatusNotificationRequest { status, request_id };
+        self.enqueue_call(CallAction::LogStatusNotification, payload)
+            .await;
+    }
+    pub(crate) async fn send_upload_status(
+        &mut self,
+        log: Option<&LogUploadInfo>,
+        status: DiagnosticsStatus,
+    ) {
+        match log {
+            Some(log) => {
+                let status = match status {
+                    DiagnosticsStatus::Idle => UploadLogStatus::Idle,
+                    DiagnosticsStatus::Uploaded => UploadLogStatus::Uploaded,
+                    DiagnosticsStatus::UploadFailed => UploadLogStatus::UploadFailure,
+                    DiagnosticsStatus::Uploading => UploadLogStatus::Uploading,
+                };
+                self.send_log_status_notification(status, Some(log.request_id))
+                    .await;
+            }
+            None => self.send_diagnostics_status_notification(status).await,
+        }
+    }
     pub(crate) async fn try_diagnostrics_upload(&mut self) {
         if let DiagnosticsState::Uploading(t) =
             core::mem::replace(&mut self.diagnostics_state, DiagnosticsState::Idle)
         {
             if t.retry_left == 0 {
-                self.send_diagnostics_status_notification(DiagnosticsStatus::UploadFailed)
+                self.send_upload_status(t.log.as_ref(), DiagnosticsStatus::UploadFailed)
                     .await;
             } else {
-                self.start_diagnostics_upload(t.location.clone(), t.retry_interval)
-                    .await;
+                match &t.log {
+                    Some(LogUploadInfo {
+                        file_name,
+                        content: Some(content),
+                        ..
+                    }) => {
+                        self.start_log_upload(
+                            t.location.clone(),
+                            file_name.clone(),
+                            content.clone(),
+                            t.retry_interval,
+                        )
+                        .await;
+                    }
+                    _ => {
+                        self.start_diagnostics_upload(t.location.clone(), t.retry_interval)
+                            .await;
+                    }
+                }
                 self.diagnostics_state = DiagnosticsState::Uploading(t);
             }
         } else {
-            unreachable!();
+            log::warn!("no diagnostics upload to retry");
         }
     }
-    pub(crate) async fn trigger_diagnostics_status_notification(&mut self) {
-        let status = match self.diagnostics_state {
+    pub(crate) fn diagnostics_status(&self) -> DiagnosticsStatus {
+        match self.diagnostics_state {
             DiagnosticsState::Idle => DiagnosticsStatus::Idle,
+            DiagnosticsState::Uploading(DiagnosticsUploadInfo { log: Some(_), .. }) => {
+                DiagnosticsStatus::Idle
+            }
             DiagnosticsState::Uploading { .. } => DiagnosticsStatus::Uploading,
+        }
+    }
+    pub(crate) async fn trigger_diagnostics_status_notification(&mut self) {
+        let payload = DiagnosticsStatusNotificationRequest {
+            status: self.diagnostics_status(),
+        };
+        self.enqueue_call(CallAction::DiagnosticsStatusNotification, payload)
+            .await;
+    }
+    pub(crate) async fn trigger_log_status_notification(&mut self) {
+        let (status, request_id) = match &self.diagnostics_state {
+            DiagnosticsState::Uploading(DiagnosticsUploadInfo { log: Some(log), .. }) => {
+                (UploadLogStatus::Uploading, Some(log.request_id))
+            }
+            _ => (UploadLogStatus::Idle, None),
         };
-        self.send_diagnostics_status_notification(status).await;
+        self.send_log_status_notification(status, request_id).await;
     }
 }
@@ -1,27 +1,50 @@
 use alloc::string::String;
+use chrono::{DateTime, Utc};
 use rocpp_core::v16::{
     messages::{
         firmware_status_notification::FirmwareStatusNotificationRequest,
-        update_firmware::UpdateFirmwareRequest,
+        signed_firmware_status_notification::SignedFirmwareStatusNotificationRequest,
     },
-    types::FirmwareStatus,
+    types::{FirmwareStatus, SignedFirmwareStatus},
 };
 
-use crate::v16::{cp::ChargePoint, interfaces::ChargePointInterface};
+use crate::v16::{
+    cp::ChargePoint,
+    interfaces::{ChargePointInterface, StateChange, TimerId},
+};
 
 use super::call::CallAction;
 
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub(crate) struct SignedFirmwareInfo {
+    pub signing_certificate: String,
+    pub signature: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub(crate) struct FirmwareDownloadInfo {
     pub retry_left: u64,
     pub retry_interval: u64,
     pub location: String,
+    pub retrieve_date: DateTime<Utc>,
+    pub install_date: Option<DateTime<Utc>>,
+    pub signed: Option<SignedFirmwareInfo>,
+}
+
+// persisted until the update finishes, so it survives reboots
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct FirmwareJob {
+    pub request_id: Option<i32>,
+    pub info: FirmwareDownloadInfo,
 }
 
 pub(crate) enum FirmwareState {
     Idle,
-    New(UpdateFirmwareRequest),
+    New(FirmwareDownloadInfo),
     Downloading(FirmwareDownloadInfo),
     DownloadSleep(FirmwareDownloadInfo),
+    Verifying(FirmwareDownloadInfo),
+    InstallScheduled(DateTime<Utc>),
     WaitingForTransactionToFinish,
     Installing,
 }
@@ -42,27 +65,119 @@ impl FirmwareState {
     }
 }
 
+// the plain notification only knows a subset of the signed states
+fn unsigned_firmware_status(status: &SignedFirmwareStatus) -> Option<FirmwareStatus> {
+    match status {
+        SignedFirmwareStatus::Downloaded => Some(FirmwareStatus::Downloaded),
+        SignedFirmwareStatus::DownloadFailed => Some(FirmwareStatus::DownloadFailed),
+        SignedFirmwareStatus::Downloading => Some(FirmwareStatus::Downloading),
+        SignedFirmwareStatus::Idle => Some(FirmwareStatus::Idle),
+        SignedFirmwareStatus::InstallationFailed => Some(FirmwareStatus::InstallationFailed),
+        SignedFirmwareStatus::Installing => Some(FirmwareStatus::Installing),
+        SignedFirmwareStatus::Installed => Some(FirmwareStatus::Installed),
+        _ => None,
+    }
+}
+
 impl<I: ChargePointInterface> ChargePoint<I> {
     pub(crate) async fn on_firmware_online(&mut self) {
-        let last_firmware_state = self.interface.db_get_firmware_state().await;
+        let (last_firmware_state, request_id) = self.interface.db_get_firmware_state().await;
         let status = match last_firmware_state {
-            FirmwareInstallStatus::InstallationSuccess => FirmwareStatus::Installed,
-            FirmwareInstallStatus::InstallationFailed => FirmwareStatus::InstallationFailed,
-            _ => return,
+            FirmwareInstallStatus::InstallationSuccess => SignedFirmwareStatus::Installed,
+            FirmwareInstallStatus::InstallationFailed => SignedFirmwareStatus::InstallationFailed,
+            _ => {
+                self.resume_firmware_job().await;
+                return;
+            }
         };
+        self.firmware_request_id = request_id;
         self.send_firmware_status_notification(status).await;
         self.interface
-            .db_change_firmware_state(FirmwareInstallStatus::NA)
+            .db_change_firmware_state(FirmwareInstallStatus::NA, None)
             .await;
     }
-    pub(crate) async fn send_firmware_status_notification(&mut self, status: FirmwareStatus) {
-        let payload = FirmwareStatusNotificationRequest { status };
-        self.enqueue_call(CallAction::FirmwareStatusNotification, payload)
-            .await;
+    // a job left in the db while idle was cut short by a reboot. the downloaded
+    // image is not kept, so everything past the download starts over
+    async fn resume_firmware_job(&mut self) {
+        if !matches!(self.firmware_state, FirmwareState::Idle) {
+            return;
+        }
+        if let Some(job) = self.interface.db_get_firmware_job().await {
+            log::info!("resuming firmware update from {}", job.info.location);
+            self.firmware_request_id = job.request_id;
+            self.schedule_firmware_download(job.info).await;
+        }
+    }
+    pub(crate) async fn update_firmware_job(&mut self, info: Option<&FirmwareDownloadInfo>) {
+        let job = info.map(|info| FirmwareJob {
+            request_id: self.firmware_request_id,
+            info: info.clone(),
+        });
+        self.interface.db_update_firmware_job(job.as_ref()).await;
+    }
+    // signed updates report through SignedFirmwareStatusNotification until they finish
+    pub(crate) async fn send_firmware_status_notification(&mut self, status: SignedFirmwareStatus) {
+        let unsigned = unsigned_firmware_status(&status);
+        if let Some(unsigned) = &unsigned {
+            self.notify_state_change(StateChange::FirmwareStatus(unsigned.clone()))
+                .await;
+        }
+        let finished = matches!(
+            status,
+            SignedFirmwareStatus::DownloadFailed
+                | SignedFirmwareStatus::InvalidSignature
+                | SignedFirmwareStatus::InstallationFailed
+                | SignedFirmwareStatus::InstallVerificationFailed
+                | SignedFirmwareStatus::Installed
+        );
+        match self.firmware_request_id {
+            Some(request_id) => {
+                let payload = SignedFirmwareStatusNotificationRequest {
+                    status,
+                    request_id: Some(request_id),
+                };
+                self.enqueue_call(CallAction::SignedFirmwareStatusNotification, payload)
+                    .await;
+            }
+            None => {
+                if let Some(status) = unsigned {
+                    let payload = FirmwareStatusNotificationRequest { status };
+                    self.enqueue_call(CallAction::FirmwareStatusNotification, payload)
+                        .await;
+                }
+            }
+        }
+        if finished {
+            self.firmware_request_id = None;
+        }
+    }
+    // without a clock the job waits in New until set_time picks it up
+    pub(crate) async fn schedule_firmware_download(&mut self, info: FirmwareDownloadInfo) {
+        self.update_firmware_job(Some(&info)).await;
+        let delay = self
+            .get_time()
+            .await
+            .map(|now| (info.retrieve_date - now).num_seconds());
+        match delay {
+            Some(delay) if delay <= 0 => {
+                self.send_firmware_status_notification(SignedFirmwareStatus::Downloading)
+                    .await;
+                self.try_firmware_download(info).await;
+            }
+            _ => {
+                self.firmware_state = FirmwareState::New(info);
+                if let Some(delay) = delay {
+                    self.add_timeout(TimerId::Firmware, delay as u64).await;
+                }
+                self.send_firmware_status_notification(SignedFirmwareStatus::DownloadScheduled)
+                    .await;
+            }
+        }
     }
     pub(crate) async fn try_firmware_download(&mut self, info: FirmwareDownloadInfo) {
         if info.retry_left == 0 {
-            self.send_firmware_status_notification(FirmwareStatus::DownloadFailed)
+            self.update_firmware_job(None).await;
+            self.send_firmware_status_notification(SignedFirmwareStatus::DownloadFailed)
                 .await;
             self.firmware_state = FirmwareState::Idle;
         } else {
@@ -70,21 +185,97 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             self.firmware_state = FirmwareState::Downloading(info);
         }
     }
+    pub(crate) async fn firmware_downloaded(&mut self, info: FirmwareDownloadInfo) {
+        match &info.signed {
+            Some(signed) => {
+                let signing_certificate = signed.signing_certificate.clone();
+                let signature = signed.signature.clone();
+                self.firmware_state = FirmwareState::Verifying(info);
+                self.verify_firmware(signing_certificate, signature).await;
+            }
+            None => self.schedule_firmware_install(info.install_date).await,
+        }
+    }
+    pub(crate) async fn schedule_firmware_install(&mut self, install_date: Option<DateTime<Utc>>) {
+        let now = self.get_time().await;
+        match install_date {
+            Some(install_date) if now.is_none_or(|now| install_date > now) => {
+                self.firmware_state = FirmwareState::InstallScheduled(install_date);
+                // without a clock this waits for set_time instead of a timer
+                if let Some(now) = now {
+                    let delay = (install_date - now).num_seconds() as u64;
+                    self.add_timeout(TimerId::Firmware, delay).await;
+                }
+                self.send_firmware_status_notification(SignedFirmwareStatus::InstallScheduled)
+                    .await;
+            }
+            _ if self.active_local_transactions.iter().all(|f| f.is_none()) => {
+                self.try_firmware_install().await;
+            }
+            _ => {
+                self.firmware_state = FirmwareState::WaitingForTransactionToFinish;
+                for connector_id in 0..self.configs.number_of_connectors.value {
+                    self.sync_connector_states(connector_id, None, None).await;
+                }
+            }
+        }
+    }
+    // picks up whatever was deferred while the clock was unknown
+    pub(crate) async fn on_firmware_time_known(&mut self) {
+        match core::mem::replace(&mut self.firmware_state, FirmwareState::Idle) {
+            FirmwareState::New(info) => self.schedule_firmware_download(info).await,
+            FirmwareState::InstallScheduled(install_date) => {
+                self.schedule_firmware_install(Some(install_date)).await
+            }
+            state => self.firmware_state = state,
+        }
+    }
     pub(crate) async fn try_firmware_install(&mut self) {
         self.firmware_state = FirmwareState::Installing;
-        self.send_firmware_status_notification(FirmwareStatus::Installing)
+        self.send_firmware_status_notification(SignedFirmwareStatus::Installing)
             .await;
         self.install_firmware().await;
     }
-    pub(crate) async fn trigger_firmware_status_notification(&mut self) {
-        let status = match self.firmware_state {
+    pub(crate) fn firmware_status(&self) -> FirmwareStatus {
+        match self.firmware_state {
             FirmwareState::Idle => FirmwareStatus::Idle,
             FirmwareState::New(_) => FirmwareStatus::Idle,
             FirmwareState::Downloading(_) => FirmwareStatus::Downloading,
             FirmwareState::DownloadSleep(_) => FirmwareStatus::Downloading,
+            FirmwareState::Verifying(_) => FirmwareStatus::Downloaded,
+            FirmwareState::InstallScheduled(_) => FirmwareStatus::Downloaded,
             FirmwareState::WaitingForTransactionToFinish => FirmwareStatus::Downloaded,
             FirmwareState::Installing => FirmwareStatus::Installing,
+        }
+    }
+    fn signed_firmware_status(&self) -> SignedFirmwareStatus {
+        match self.firmware_state {
+            FirmwareState::Idle => SignedFirmwareStatus::Idle,
+            FirmwareState::New(_) => SignedFirmwareStatus::DownloadScheduled,
+            FirmwareState::Downloading(_) => SignedFirmwareStatus::Downloading,
+            FirmwareState::DownloadSleep(_) => SignedFirmwareStatus::Downloading,
+            FirmwareState::Verifying(_) => SignedFirmwareStatus::Downloaded,
+            FirmwareState::InstallScheduled(_) => SignedFirmwareStatus::InstallScheduled,
+            FirmwareState::WaitingForTransactionToFinish => match self.firmware_request_id {
+                Some(_) => SignedFirmwareStatus::SignatureVerified,
+                None => SignedFirmwareStatus::Downloaded,
+            },
+            FirmwareState::Installing => SignedFirmwareStatus::Installing,
+        }
+    }
+    pub(crate) async fn trigger_firmware_status_notification(&mut self) {
+        let payload = FirmwareStatusNotificationRequest {
+            status: self.firmware_status(),
         };
-        self.send_firmware_status_notification(status).await;
+        self.enqueue_call(CallAction::FirmwareStatusNotification, payload)
+            .await;
+    }
+    pub(crate) async fn trigger_signed_firmware_status_notification(&mut self) {
+        let payload = SignedFirmwareStatusNotificationRequest {
+            status: self.signed_firmware_status(),
+            request_id: self.firmware_request_id,
+        };
+        self.enqueue_call(CallAction::SignedFirmwareStatusNotification, payload)
+            .await;
     }
 }
@@ -47,8 +47,9 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 self.set_sleep_heartbeat().await;
             }
             _ => {
-                // heartbet state must be idle in offline state
-                unreachable!();
+                // heartbeat state is idle while offline, restart it if it is not
+                log::warn!("heartbeat already running when coming online, restarting it");
+                self.set_sleep_heartbeat().await;
             }
         }
     }
@@ -58,9 +59,9 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 self.remove_timeout(TimerId::Heartbeat).await;
             }
             HeartbeatState::Idle => {}
-            _ => {
-                // heartbeat_response will be called before this
-                unreachable!();
+            HeartbeatState::WaitingForResponse => {
+                // the pending call is failed before this, so the response is dropped
+                log::warn!("heartbeat still waiting for a response when going offline");
             }
         }
         self.heartbeat_state = HeartbeatState::Idle;
@@ -40,7 +40,9 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 self.aligned_meter_state = MeterState::Sleep;
                 self.add_timeout(TimerId::MeterAligned, rem).await;
             } else {
-                unreachable!();
+                // started from set_time once the clock is known
+                log::debug!("clock aligned data deferred until the time is known");
+                self.aligned_meter_state = MeterState::Idle;
             }
         }
     }
@@ -51,7 +53,10 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     self.set_sampled_meter_sleep_state(connector_id).await;
                 }
                 _ => {
-                    unreachable!();
+                    log::warn!(
+                        "sampled meter data already running for connector {}",
+                        connector_id + 1
+                    );
                 }
             }
         }
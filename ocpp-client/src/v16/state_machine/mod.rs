@@ -2,14 +2,23 @@ pub(crate) mod actions;
 pub(crate) mod auth;
 pub(crate) mod boot;
 pub(crate) mod call;
+pub(crate) mod certificate;
 pub(crate) mod clock;
+pub(crate) mod commands;
 pub(crate) mod connector;
+pub(crate) mod data_transfer;
 pub(crate) mod diagnostics;
 pub(crate) mod firmware;
 pub(crate) mod heartbeat;
 pub(crate) mod meter;
+pub(crate) mod observer;
 pub(crate) mod operations;
+pub(crate) mod ping;
+pub(crate) mod reconnect;
 pub(crate) mod reservation;
+pub(crate) mod security;
+pub(crate) mod security_event;
+pub(crate) mod smart_charging;
 pub(crate) mod transaction;
 #[macro_use]
 pub(crate) mod config;
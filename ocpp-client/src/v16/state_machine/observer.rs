@@ -0,0 +1,43 @@
+use alloc::vec::Vec;
+
+use crate::v16::{
+    cp::ChargePoint,
+    interfaces::{ChargePointInterface, ChargePointSnapshot, ConnectorSnapshot, StateChange},
+};
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) fn snapshot(&self) -> ChargePointSnapshot {
+        let connectors: Vec<ConnectorSnapshot> = self
+            .connector_status_notification
+            .iter()
+            .zip(self.active_local_transactions.iter())
+            .enumerate()
+            .map(|(connector_id, (status, transaction))| ConnectorSnapshot {
+                connector_id: connector_id + 1,
+                status: status.status.clone(),
+                local_transaction_id: transaction.map(|(local_id, _)| local_id),
+                transaction_id: transaction.and_then(|(_, transaction_id)| transaction_id),
+            })
+            .collect();
+        ChargePointSnapshot {
+            connected: self.ws_connected,
+            online: self.call_permission(),
+            registration_status: self.registration_status.clone(),
+            connectors,
+            firmware_status: self.firmware_status(),
+            diagnostics_status: self.diagnostics_status(),
+            pending_calls: self.pending_calls.len() + self.undelivered_calls.len(),
+            transaction_queue_head: self.transaction_head,
+            transaction_queue_tail: self.transaction_tail,
+            time_source: self.time_source,
+        }
+    }
+    pub(crate) async fn notify_state_change(&mut self, change: StateChange) {
+        log::debug!("state change: {:?}", change);
+        let snapshot = self.snapshot();
+        self.interface
+            .interface
+            .state_changed(change, snapshot)
+            .await;
+    }
+}
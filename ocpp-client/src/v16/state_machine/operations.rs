@@ -1,12 +1,16 @@
-use alloc::string::String;
-use rocpp_core::v16::types::{Reason, RegistrationStatus, ResetType};
+use alloc::{format, string::String, vec::Vec};
+use rocpp_core::v16::types::{
+    AvailabilityStatus, AvailabilityType, Reason, RegistrationStatus, ResetType,
+};
 
 use crate::v16::{
     cp::ChargePoint,
-    interfaces::{ChargePointInterface, TimerId},
+    interfaces::{ChargePointInterface, SeccState, TimerId},
 };
 
-use super::{call::OutgoingCallState, connector::ConnectorState};
+use super::{
+    call::OutgoingCallState, connector::ConnectorState, security_event::SecurityEventType,
+};
 
 impl<I: ChargePointInterface> ChargePoint<I> {
     pub(crate) fn call_permission(&self) -> bool {
@@ -109,6 +113,11 @@ impl<I: ChargePointInterface> ChargePoint<I> {
     }
     pub(crate) async fn reset(&mut self, kind: ResetType, reason: Option<Reason>) {
         self.pending_reset = Some(kind.clone());
+        self.raise_security_event(
+            SecurityEventType::ResetOrReboot,
+            Some(format!("{:?}, {:?}", kind, reason)),
+        )
+        .await;
         let reason = reason.or(match kind {
             ResetType::Hard => Some(Reason::HardReset),
             ResetType::Soft => Some(Reason::SoftReset),
@@ -121,7 +130,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         }
         match kind {
             ResetType::Soft => {
-                if self.outgoing_call_state == OutgoingCallState::Idle {
+                if let OutgoingCallState::Idle = self.outgoing_call_state {
                     self.soft_reset();
                 }
             }
@@ -130,4 +139,99 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             }
         }
     }
+    pub(crate) fn shutdown(&mut self) {
+        self.pending_shutdown = true;
+        if let OutgoingCallState::Idle = self.outgoing_call_state {
+            self.shutdown_now = true;
+        }
+    }
+    pub(crate) async fn change_availability(
+        &mut self,
+        ocpp_connector_id: usize,
+        kind: AvailabilityType,
+    ) -> (AvailabilityStatus, Vec<(usize, ConnectorState)>) {
+        let mut changes = Vec::new();
+        let mut pending = false;
+        if ocpp_connector_id > self.configs.number_of_connectors.value {
+            return (AvailabilityStatus::Rejected, changes);
+        }
+        for connector_id in if ocpp_connector_id == 0 {
+            0..self.configs.number_of_connectors.value
+        } else {
+            (ocpp_connector_id - 1)..(ocpp_connector_id)
+        } {
+            match &kind {
+                AvailabilityType::Operative => {
+                    if let ConnectorState::Unavailable(secc_state) =
+                        &self.connector_state[connector_id]
+                    {
+                        match secc_state {
+                            SeccState::Plugged => {
+                                changes.push((connector_id, ConnectorState::plugged()))
+                            }
+                            SeccState::Unplugged => {
+                                changes.push((connector_id, ConnectorState::idle()))
+                            }
+                            SeccState::Faulty => {
+                                changes.push((connector_id, ConnectorState::faulty()))
+                            }
+                        }
+                    }
+                }
+                AvailabilityType::Inoperative => match &self.connector_state[connector_id] {
+                    ConnectorState::Idle => {
+                        changes.push((
+                            connector_id,
+                            ConnectorState::unavailabe(SeccState::Unplugged),
+                        ));
+                    }
+                    ConnectorState::Plugged => {
+                        changes
+                            .push((connector_id, ConnectorState::unavailabe(SeccState::Plugged)));
+                    }
+                    ConnectorState::Authorized { .. } => {
+                        self.remove_timeout(TimerId::Authorize(connector_id)).await;
+                        changes.push((
+                            connector_id,
+                            ConnectorState::unavailabe(SeccState::Unplugged),
+                        ));
+                    }
+                    ConnectorState::Transaction { .. } => {
+                        pending = true;
+                        self.pending_inoperative_changes[connector_id] = true;
+                    }
+                    ConnectorState::Finishing => {
+                        changes
+                            .push((connector_id, ConnectorState::unavailabe(SeccState::Plugged)));
+                    }
+                    ConnectorState::Reserved {
+                        is_plugged,
+                        reservation_id,
+                        ..
+                    } => {
+                        let secc_state = if *is_plugged {
+                            SeccState::Plugged
+                        } else {
+                            SeccState::Unplugged
+                        };
+                        self.remove_reservation(connector_id, *reservation_id).await;
+                        changes.push((connector_id, ConnectorState::unavailabe(secc_state)));
+                    }
+                    ConnectorState::Faulty => {
+                        changes.push((connector_id, ConnectorState::unavailabe(SeccState::Faulty)));
+                    }
+                    _ => {}
+                },
+            }
+            self.interface
+                .db_change_operative_state(connector_id, kind.clone())
+                .await;
+        }
+        let status = if pending {
+            AvailabilityStatus::Scheduled
+        } else {
+            AvailabilityStatus::Accepted
+        };
+        (status, changes)
+    }
 }
@@ -0,0 +1,95 @@
+use crate::v16::{
+    cp::ChargePoint,
+    interfaces::{ChargePointInterface, TimeSource, TimerId},
+};
+
+use super::clock::Instant;
+
+pub(crate) enum PingState {
+    Idle,
+    Sleeping,
+    WaitingForPong,
+}
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) async fn set_sleep_ping(&mut self) {
+        let interval = self.configs.web_socket_ping_interval.value;
+        if interval == 0 {
+            self.ping_state = PingState::Idle;
+            return;
+        }
+        self.add_timeout(TimerId::Ping, interval).await;
+        self.ping_state = PingState::Sleeping;
+    }
+    pub(crate) async fn send_ping(&mut self) {
+        self.interface.interface.ws_ping().await;
+        self.add_timeout(TimerId::Ping, self.configs.web_socket_pong_timeout.value)
+            .await;
+        self.ping_state = PingState::WaitingForPong;
+    }
+    pub(crate) async fn on_pong_timeout(&mut self) {
+        // a half open connection never reports Disconnected by itself, closing it
+        // makes the socket report it and the usual reconnect follows
+        log::warn!(
+            "no pong within {}s, closing the connection",
+            self.configs.web_socket_pong_timeout.value
+        );
+        self.ping_state = PingState::Idle;
+        self.interface.interface.ws_close().await;
+    }
+    pub(crate) async fn got_pong(&mut self) {
+        self.last_pong = Some(Instant::now(&self.interface).await);
+        match &self.ping_state {
+            PingState::WaitingForPong => {
+                self.remove_timeout(TimerId::Ping).await;
+                self.set_sleep_ping().await;
+            }
+            _ => {
+                log::debug!("ignoring unsolicited pong");
+            }
+        }
+    }
+    pub(crate) async fn on_ping_connected(&mut self) {
+        self.last_pong = None;
+        self.set_sleep_ping().await;
+    }
+    pub(crate) async fn on_ping_disconnected(&mut self) {
+        match &self.ping_state {
+            PingState::Sleeping | PingState::WaitingForPong => {
+                self.remove_timeout(TimerId::Ping).await;
+            }
+            PingState::Idle => {}
+        }
+        self.ping_state = PingState::Idle;
+        self.last_pong = None;
+    }
+    // true while a pong came back within the heartbeat interval, the spec lets
+    // the heartbeat be skipped then. Heartbeats also sync the clock, so one still
+    // goes out once the last sync is older than HeartbeatMaxSkipInterval
+    pub(crate) async fn may_skip_heartbeat(&self) -> bool {
+        if self.configs.web_socket_ping_interval.value == 0 {
+            return false;
+        }
+        let (Some(last_pong), Some((_, synced_at))) = (self.last_pong, self.base_time) else {
+            return false;
+        };
+        if !matches!(self.time_source, Some(TimeSource::CentralSystem)) {
+            return false;
+        }
+        let now = Instant::now(&self.interface).await;
+        let since_pong = now.duration_since(last_pong).as_micros();
+        let since_sync = now.duration_since(synced_at).as_micros();
+        since_pong
+            < self
+                .configs
+                .heartbeat_interval
+                .value
+                .saturating_mul(1_000_000)
+            && since_sync
+                < self
+                    .configs
+                    .heartbeat_max_skip_interval
+                    .value
+                    .saturating_mul(1_000_000)
+    }
+}
@@ -0,0 +1,48 @@
+use rand::Rng;
+
+use crate::v16::{
+    cp::ChargePoint,
+    interfaces::{ChargePointInterface, TimerId},
+};
+
+const MAX_BACKOFF_EXPONENT: u32 = 16;
+// failed attempts are never retried right away, even with the backoff disabled
+const MIN_FAILED_CONNECT_DELAY: u64 = 1;
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    fn reconnect_delay(&mut self) -> u64 {
+        let initial = self.configs.reconnect_backoff_initial_interval.value;
+        if initial == 0 {
+            return 0;
+        }
+        let max = self
+            .configs
+            .reconnect_backoff_max_interval
+            .value
+            .max(initial);
+        let exponent = self.reconnect_attempts.min(MAX_BACKOFF_EXPONENT);
+        let backoff = initial.saturating_mul(1 << exponent).min(max);
+        // equal jitter, keeps at least half of the backoff
+        let half = backoff / 2;
+        (half + self.rng.random_range(0..=backoff - half)).max(1)
+    }
+    pub(crate) async fn schedule_reconnect(&mut self) {
+        let delay = self.reconnect_delay();
+        self.reconnect_attempts = self.reconnect_attempts.saturating_add(1);
+        if delay == 0 {
+            self.connect().await;
+        } else {
+            log::info!("reconnecting in {}s", delay);
+            self.add_timeout(TimerId::Reconnect, delay).await;
+        }
+    }
+    pub(crate) async fn schedule_failed_reconnect(&mut self) {
+        let delay = self.reconnect_delay().max(MIN_FAILED_CONNECT_DELAY);
+        self.reconnect_attempts = self.reconnect_attempts.saturating_add(1);
+        log::info!("connect attempt failed, retrying in {}s", delay);
+        self.add_timeout(TimerId::Reconnect, delay).await;
+    }
+    pub(crate) fn reset_reconnect_backoff(&mut self) {
+        self.reconnect_attempts = 0;
+    }
+}
@@ -0,0 +1,128 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+use crate::v16::{
+    cp::ChargePoint,
+    interfaces::{BasicAuth, ChargePointInterface, TimerId, WsConnectOptions},
+};
+
+use super::security_event::SecurityEventType;
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) fn charge_point_id(&self) -> String {
+        self.cms_url
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    }
+    pub(crate) fn ws_connect_options(&self) -> WsConnectOptions {
+        let security_profile = self.configs.security_profile.value;
+        let url = match (security_profile, self.cms_url.strip_prefix("ws://")) {
+            (2 | 3, Some(rest)) => format!("wss://{}", rest),
+            _ => self.cms_url.clone(),
+        };
+        let basic_auth = matches!(security_profile, 1 | 2).then(|| BasicAuth {
+            username: self.charge_point_id(),
+            password: self.configs.authorization_key.value.clone(),
+        });
+        WsConnectOptions {
+            url,
+            security_profile,
+            basic_auth,
+            ca_certificate: (security_profile >= 2).then(|| self.ca_bundle()).flatten(),
+            client_certificate: (security_profile == 3)
+                .then(|| self.client_certificate.clone())
+                .flatten(),
+        }
+    }
+    fn security_profile_supported(&self, security_profile: u8) -> bool {
+        let has_key = !self.configs.authorization_key.value.is_empty();
+        let has_ca = self.ca_bundle().is_some();
+        match security_profile {
+            0 => true,
+            1 => has_key,
+            2 => has_key && has_ca,
+            3 => has_ca && self.client_certificate.is_some(),
+            _ => false,
+        }
+    }
+    pub(crate) async fn upgrade_security_profile(&mut self, security_profile: u8) -> bool {
+        let current = self.configs.security_profile.value;
+        if security_profile <= current || !self.security_profile_supported(security_profile) {
+            return false;
+        }
+        self.security_profile_fallback = Some(current);
+        self.interface
+            .db_update_security_profile_fallback(Some(current))
+            .await;
+        self.configs
+            .security_profile
+            .update(security_profile, &mut self.interface)
+            .await;
+        true
+    }
+    pub(crate) async fn apply_security_profile(&mut self) {
+        self.add_timeout(
+            TimerId::SecurityProfileFallback,
+            self.configs.security_profile_fallback_timeout.value,
+        )
+        .await;
+        self.interface.interface.ws_close().await;
+    }
+    // an upgrade still pending at power loss gets a new fallback timeout, the
+    // time already spent on it is not known without a clock
+    pub(crate) async fn restore_security_profile_fallback(&mut self) {
+        if let Some(security_profile) = self.security_profile_fallback {
+            log::info!(
+                "security profile {} not confirmed yet, falling back to {} if no connection",
+                self.configs.security_profile.value,
+                security_profile
+            );
+            self.add_timeout(
+                TimerId::SecurityProfileFallback,
+                self.configs.security_profile_fallback_timeout.value,
+            )
+            .await;
+        }
+    }
+    pub(crate) async fn on_security_profile_connected(&mut self) {
+        if self.security_profile_fallback.take().is_some() {
+            self.remove_timeout(TimerId::SecurityProfileFallback).await;
+            self.interface
+                .db_update_security_profile_fallback(None)
+                .await;
+        }
+    }
+    pub(crate) async fn security_profile_fallback(&mut self) {
+        if let Some(security_profile) = self.security_profile_fallback.take() {
+            log::warn!(
+                "could not connect with security profile {}, falling back to {}",
+                self.configs.security_profile.value,
+                security_profile
+            );
+            self.raise_security_event(
+                SecurityEventType::FailedToAuthenticateAtCentralSystem,
+                Some(format!(
+                    "security profile {}",
+                    self.configs.security_profile.value
+                )),
+            )
+            .await;
+            self.configs
+                .security_profile
+                .update(security_profile, &mut self.interface)
+                .await;
+            self.interface
+                .db_update_security_profile_fallback(None)
+                .await;
+            if !self.ws_connected {
+                self.remove_timeout(TimerId::Reconnect).await;
+                self.connect().await;
+            }
+        }
+    }
+}
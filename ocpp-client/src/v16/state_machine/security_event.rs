@@ -0,0 +1,157 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use chrono::{DateTime, Utc};
+use rocpp_core::v16::messages::security_event_notification::SecurityEventNotificationRequest;
+
+use crate::v16::{cp::ChargePoint, interfaces::ChargePointInterface};
+
+use super::{call::CallAction, transaction::TransactionTime};
+
+// oldest entries are dropped past this, delivered or not
+const MAX_SECURITY_LOG_ENTRIES: u64 = 1000;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub(crate) enum SecurityEventType {
+    FirmwareUpdated,
+    FailedToAuthenticateAtCentralSystem,
+    SettingSystemTime,
+    StartupOfTheDevice,
+    ResetOrReboot,
+    InvalidMessages,
+    ReconfigurationOfSecurityParameters,
+    InvalidFirmwareSignature,
+    InvalidFirmwareSigningCertificate,
+}
+
+impl SecurityEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityEventType::FirmwareUpdated => "FirmwareUpdated",
+            SecurityEventType::FailedToAuthenticateAtCentralSystem => {
+                "FailedToAuthenticateAtCentralSystem"
+            }
+            SecurityEventType::SettingSystemTime => "SettingSystemTime",
+            SecurityEventType::StartupOfTheDevice => "StartupOfTheDevice",
+            SecurityEventType::ResetOrReboot => "ResetOrReboot",
+            SecurityEventType::InvalidMessages => "InvalidMessages",
+            SecurityEventType::ReconfigurationOfSecurityParameters => {
+                "ReconfigurationOfSecurityParameters"
+            }
+            SecurityEventType::InvalidFirmwareSignature => "InvalidFirmwareSignature",
+            SecurityEventType::InvalidFirmwareSigningCertificate => {
+                "InvalidFirmwareSigningCertificate"
+            }
+        }
+    }
+    // non critical events only end up in the security log
+    pub fn is_critical(&self) -> bool {
+        matches!(
+            self,
+            SecurityEventType::FirmwareUpdated
+                | SecurityEventType::SettingSystemTime
+                | SecurityEventType::StartupOfTheDevice
+                | SecurityEventType::ResetOrReboot
+        )
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub(crate) struct SecurityEvent {
+    pub kind: SecurityEventType,
+    pub timestamp: TransactionTime,
+    pub tech_info: Option<String>,
+}
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) async fn raise_security_event(
+        &mut self,
+        kind: SecurityEventType,
+        tech_info: Option<String>,
+    ) {
+        log::info!("security event: {} {:?}", kind.as_str(), tech_info);
+        let event = SecurityEvent {
+            kind,
+            timestamp: self.get_transaction_time().await,
+            tech_info,
+        };
+        let index = self.security_event_head;
+        self.security_event_head += 1;
+        let mut dropped = None;
+        if self.security_event_head - self.security_log_start > MAX_SECURITY_LOG_ENTRIES {
+            dropped = Some(self.security_log_start);
+            self.security_log_start += 1;
+            if self.security_event_tail < self.security_log_start {
+                log::warn!("security log full, dropping an undelivered event");
+                self.security_event_tail = self.security_log_start;
+            }
+        }
+        self.interface
+            .db_push_security_event(index, &event, dropped, self.security_event_tail)
+            .await;
+        self.process_security_events().await;
+    }
+    pub(crate) async fn on_security_event_online(&mut self) {
+        self.process_security_events().await;
+    }
+    pub(crate) async fn process_security_events(&mut self) {
+        while self.call_permission()
+            && self.security_event_in_flight.is_none()
+            && self.security_event_tail < self.security_event_head
+        {
+            let index = self.security_event_tail;
+            let Some(event) = self.interface.db_get_security_event(index).await else {
+                self.security_event_delivered(index).await;
+                continue;
+            };
+            if !(event.kind.is_critical() && self.configs.security_event_notification_enabled.value)
+            {
+                self.security_event_delivered(index).await;
+                continue;
+            }
+            let payload = SecurityEventNotificationRequest {
+                kind: event.kind.as_str().to_string(),
+                timestamp: self.parse_transaction_time(event.timestamp),
+                tech_info: event.tech_info,
+            };
+            self.security_event_in_flight = Some(index);
+            self.enqueue_call(CallAction::SecurityEventNotification, payload)
+                .await;
+        }
+    }
+    pub(crate) async fn security_event_delivered(&mut self, index: u64) {
+        self.security_event_tail = self.security_event_tail.max(index + 1);
+        self.interface
+            .db_update_security_event_sent(self.security_event_tail)
+            .await;
+    }
+    pub(crate) async fn security_log(
+        &mut self,
+        oldest: Option<DateTime<Utc>>,
+        latest: Option<DateTime<Utc>>,
+    ) -> String {
+        let mut res = String::new();
+        let mut after = None;
+        // read page by page, only the formatted lines are kept
+        loop {
+            let (events, next) = self.interface.db_get_security_events(after).await;
+            for event in events {
+                let timestamp = self.parse_transaction_time(event.timestamp);
+                if oldest.is_some_and(|t| timestamp < t) || latest.is_some_and(|t| timestamp > t) {
+                    continue;
+                }
+                res += &format!("{} {}", timestamp, event.kind.as_str());
+                if let Some(tech_info) = event.tech_info {
+                    res += &format!(" {}", tech_info);
+                }
+                res.push('\n');
+            }
+            match next {
+                Some(index) => after = Some(index),
+                None => break,
+            }
+        }
+        res
+    }
+}
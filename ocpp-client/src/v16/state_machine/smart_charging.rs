@@ -0,0 +1,251 @@
+use alloc::vec::Vec;
+use chrono::{DateTime, Utc};
+use rocpp_core::v16::{
+    composite_schedule::{composite_schedule, CompositeScheduleParams},
+    messages::set_charging_profile::SetChargingProfileRequest,
+    types::{
+        ChargingProfile, ChargingProfileKindType, ChargingProfilePurposeType, ChargingRateUnitType,
+        ChargingSchedule,
+    },
+};
+
+use crate::v16::{
+    cp::ChargePoint,
+    interfaces::{ChargePointInterface, ChargingLimit, TimerId},
+};
+
+const CHARGING_LIMIT_LOOKAHEAD: i32 = 24 * 3600;
+
+impl<I: ChargePointInterface> ChargePoint<I> {
+    pub(crate) fn validate_charging_profile(
+        &self,
+        connector_id: i32,
+        profile: &ChargingProfile,
+    ) -> bool {
+        if connector_id < 0 || connector_id as usize > self.configs.number_of_connectors.value {
+            return false;
+        }
+        if profile.stack_level as usize > self.configs.charge_profile_max_stack_level.value {
+            return false;
+        }
+        let schedule = &profile.charging_schedule;
+        if !self
+            .configs
+            .charging_schedule_allowed_charging_rate_unit
+            .value
+            .contains(&schedule.charging_rate_unit)
+        {
+            return false;
+        }
+        let periods = &schedule.charging_schedule_period;
+        if periods.is_empty()
+            || periods.len() > self.configs.charging_schedule_max_periods.value
+            || periods[0].start_period != 0
+            || periods
+                .windows(2)
+                .any(|w| w[0].start_period >= w[1].start_period)
+            || periods.iter().any(|p| p.limit < 0.0)
+        {
+            return false;
+        }
+        if let (Some(valid_from), Some(valid_to)) = (profile.valid_from, profile.valid_to) {
+            if valid_from >= valid_to {
+                return false;
+            }
+        }
+        match profile.charging_profile_kind {
+            ChargingProfileKindType::Absolute => {
+                if schedule.start_schedule.is_none() {
+                    return false;
+                }
+            }
+            ChargingProfileKindType::Recurring => {
+                if schedule.start_schedule.is_none() || profile.recurrency_kind.is_none() {
+                    return false;
+                }
+            }
+            ChargingProfileKindType::Relative => {}
+        }
+        match profile.charging_profile_purpose {
+            ChargingProfilePurposeType::ChargePointMaxProfile => {
+                connector_id == 0 && profile.transaction_id.is_none()
+            }
+            ChargingProfilePurposeType::TxDefaultProfile => profile.transaction_id.is_none(),
+            ChargingProfilePurposeType::TxProfile => {
+                if connector_id == 0 {
+                    return false;
+                }
+                match self.active_local_transactions[connector_id as usize - 1] {
+                    Some((_, transaction_id)) => profile
+                        .transaction_id
+                        .is_none_or(|id| Some(id) == transaction_id),
+                    None => false,
+                }
+            }
+        }
+    }
+    pub(crate) async fn add_charging_profile(&mut self, req: SetChargingProfileRequest) -> bool {
+        let profile = &req.cs_charging_profiles;
+        let replaced: Vec<i32> = self
+            .charging_profiles
+            .iter()
+            .filter(|f| {
+                let installed = &f.cs_charging_profiles;
+                installed.charging_profile_id == profile.charging_profile_id
+                    || (f.connector_id == req.connector_id
+                        && installed.stack_level == profile.stack_level
+                        && installed.charging_profile_purpose == profile.charging_profile_purpose)
+            })
+            .map(|f| f.cs_charging_profiles.charging_profile_id)
+            .collect();
+        if self.charging_profiles.len() - replaced.len()
+            >= self.configs.max_charging_profiles_installed.value
+        {
+            return false;
+        }
+        self.interface
+            .db_add_charging_profile(&req, replaced.clone())
+            .await;
+        self.charging_profiles
+            .retain(|f| !replaced.contains(&f.cs_charging_profiles.charging_profile_id));
+        self.charging_profiles.push(req);
+        self.update_charging_limits().await;
+        true
+    }
+    pub(crate) async fn clear_charging_profiles<F>(&mut self, filter: F) -> bool
+    where
+        F: Fn(&SetChargingProfileRequest) -> bool,
+    {
+        let ids: Vec<i32> = self
+            .charging_profiles
+            .iter()
+            .filter(|f| filter(f))
+            .map(|f| f.cs_charging_profiles.charging_profile_id)
+            .collect();
+        if ids.is_empty() {
+            return false;
+        }
+        self.charging_profiles
+            .retain(|f| !ids.contains(&f.cs_charging_profiles.charging_profile_id));
+        self.interface.db_remove_charging_profiles(ids).await;
+        self.update_charging_limits().await;
+        true
+    }
+    pub(crate) async fn remove_transaction_charging_profiles(&mut self, connector_id: usize) {
+        self.clear_charging_profiles(|f| {
+            f.connector_id == connector_id as i32 + 1
+                && f.cs_charging_profiles.charging_profile_purpose
+                    == ChargingProfilePurposeType::TxProfile
+        })
+        .await;
+    }
+    pub(crate) fn installed_charging_profiles(
+        &self,
+    ) -> impl Iterator<Item = (usize, &ChargingProfile)> {
+        self.charging_profiles
+            .iter()
+            .map(|f| (f.connector_id as usize, &f.cs_charging_profiles))
+    }
+    pub(crate) fn composite_schedule_params(
+        &self,
+        connector_id: usize,
+        start: DateTime<Utc>,
+        duration: i32,
+        charging_rate_unit: ChargingRateUnitType,
+    ) -> CompositeScheduleParams {
+        let transaction = connector_id.checked_sub(1).and_then(|connector_id| {
+            self.active_local_transactions[connector_id]
+                .zip(self.active_transaction_start[connector_id])
+        });
+        CompositeScheduleParams {
+            connector_id,
+            start,
+            duration,
+            charging_rate_unit,
+            transaction_start: transaction.and_then(|(_, start)| self.get_time_since(start)),
+            transaction_id: transaction.and_then(|((_, transaction_id), _)| transaction_id),
+            supply_voltage: self.smart_charging.supply_voltage,
+            default_number_phases: self.smart_charging.default_number_phases,
+        }
+    }
+    pub(crate) async fn get_composite_schedule(
+        &self,
+        connector_id: usize,
+        duration: i32,
+        charging_rate_unit: ChargingRateUnitType,
+    ) -> Option<ChargingSchedule> {
+        let start = self.get_time().await?;
+        let default_limit = self.default_charging_limit(&charging_rate_unit);
+        let params =
+            self.composite_schedule_params(connector_id, start, duration, charging_rate_unit);
+        Some(composite_schedule(
+            self.installed_charging_profiles(),
+            &params,
+            default_limit,
+        ))
+    }
+    pub(crate) fn default_charging_rate_unit(&self) -> ChargingRateUnitType {
+        self.configs
+            .charging_schedule_allowed_charging_rate_unit
+            .value
+            .first()
+            .cloned()
+            .unwrap_or(ChargingRateUnitType::A)
+    }
+    fn default_charging_limit(&self, charging_rate_unit: &ChargingRateUnitType) -> f32 {
+        match charging_rate_unit {
+            ChargingRateUnitType::A => self.smart_charging.default_limit_amps,
+            ChargingRateUnitType::W => self.smart_charging.default_limit_watts,
+        }
+    }
+    pub(crate) async fn update_charging_limits(&mut self) {
+        for connector_id in 0..self.configs.number_of_connectors.value {
+            self.update_charging_limit(connector_id).await;
+        }
+    }
+    pub(crate) async fn update_charging_limit(&mut self, connector_id: usize) {
+        let Some(now) = self.get_time().await else {
+            return;
+        };
+        let charging_rate_unit = self.default_charging_rate_unit();
+        let default_limit = self.default_charging_limit(&charging_rate_unit);
+        let params = self.composite_schedule_params(
+            connector_id + 1,
+            now,
+            CHARGING_LIMIT_LOOKAHEAD,
+            charging_rate_unit.clone(),
+        );
+        let schedule =
+            composite_schedule(self.installed_charging_profiles(), &params, default_limit);
+        let mut periods = schedule.charging_schedule_period.into_iter();
+        let Some(current) = periods.next() else {
+            return;
+        };
+        let limit = ChargingLimit {
+            limit: current.limit,
+            unit: charging_rate_unit,
+            number_phases: current
+                .number_phases
+                .unwrap_or(self.smart_charging.default_number_phases),
+        };
+        if self.applied_charging_limits[connector_id].as_ref() != Some(&limit) {
+            log::info!(
+                "applying charging limit for connector: {}, limit: {:?}",
+                connector_id,
+                limit
+            );
+            self.interface
+                .interface
+                .set_charging_limit(connector_id, limit.clone())
+                .await;
+            self.applied_charging_limits[connector_id] = Some(limit);
+        }
+        // start_period is truncated to whole seconds, never schedule a zero timeout
+        let next = periods
+            .next()
+            .map_or(CHARGING_LIMIT_LOOKAHEAD, |t| t.start_period)
+            .max(1);
+        self.add_timeout(TimerId::ChargingLimit(connector_id), next as u64)
+            .await;
+    }
+}
@@ -1,36 +1,50 @@
-use alloc::{collections::btree_set::BTreeSet, string::String, vec::Vec};
+use alloc::{collections::btree_set::BTreeSet, string::String, vec, vec::Vec};
 use chrono::{DateTime, Utc};
 use rocpp_core::v16::{
     messages::{
         meter_values::MeterValuesRequest, start_transaction::StartTransactionRequest,
         stop_transaction::StopTransactionRequest,
     },
-    types::{MeterValue, Reason, SampledValue},
+    types::{MeterValue, ReadingContext, Reason, SampledValue},
 };
 use serde::Serialize;
 
 use crate::v16::{
     cp::ChargePoint,
-    interfaces::{ChargePointInterface, SeccState},
+    interfaces::{ChargePointInterface, SeccState, StateChange},
 };
 
 use super::{call::CallAction, clock::Instant, connector::ConnectorState, firmware::FirmwareState};
 
+// unaligned times keep their boot, the uptime is meaningless in any other
 #[derive(Clone)]
 pub(crate) enum TransactionTime {
-    Unaligned(Instant),
+    Unaligned { boot: u64, uptime: Instant },
     Known(DateTime<Utc>),
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum StoredTransactionTime {
+    Known(DateTime<Utc>),
+    Unaligned { boot: u64, uptime: u64 },
+    // written by older versions, boot 0 never matches an anchor
+    Unknown,
+}
+
 impl Serialize for TransactionTime {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
         match self {
-            TransactionTime::Known(dt) => dt.serialize(serializer),
-            TransactionTime::Unaligned(_) => serializer.serialize_none(),
+            TransactionTime::Known(dt) => StoredTransactionTime::Known(*dt),
+            TransactionTime::Unaligned { boot, uptime } => StoredTransactionTime::Unaligned {
+                boot: *boot,
+                uptime: uptime.as_micros(),
+            },
         }
+        .serialize(serializer)
     }
 }
 
@@ -39,11 +53,17 @@ impl<'de> serde::de::Deserialize<'de> for TransactionTime {
     where
         D: serde::de::Deserializer<'de>,
     {
-        let opt = Option::<DateTime<Utc>>::deserialize(deserializer)?;
-        match opt {
-            Some(dt) => Ok(TransactionTime::Known(dt)),
-            None => Ok(TransactionTime::Unaligned(Instant::default())),
-        }
+        Ok(match StoredTransactionTime::deserialize(deserializer)? {
+            StoredTransactionTime::Known(dt) => TransactionTime::Known(dt),
+            StoredTransactionTime::Unaligned { boot, uptime } => TransactionTime::Unaligned {
+                boot,
+                uptime: Instant::from_micros(uptime),
+            },
+            StoredTransactionTime::Unknown => TransactionTime::Unaligned {
+                boot: 0,
+                uptime: Instant::default(),
+            },
+        })
     }
 }
 
@@ -79,6 +99,16 @@ pub(crate) struct MeterValuesEvent {
     pub meter_value: Vec<MeterValueLocal>,
 }
 
+impl MeterValuesEvent {
+    // readings without a context default to Sample.Periodic
+    fn is_sampled(&self) -> bool {
+        self.meter_value
+            .iter()
+            .flat_map(|t| &t.sampled_value)
+            .all(|t| matches!(t.context, None | Some(ReadingContext::SamplePeriodic)))
+    }
+}
+
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub(crate) struct StopTransactionEvent {
     pub local_transaction_id: u32,
@@ -96,6 +126,16 @@ pub(crate) enum TransactionEvent {
     Stop(StopTransactionEvent),
 }
 
+// what is dropped first once the queue outgrows TransactionQueueMaxLength,
+// start and stop events are always kept
+#[derive(Clone, Default, PartialEq)]
+pub(crate) enum QueueDropPolicy {
+    // every other periodic sample, then the oldest meter values
+    #[default]
+    ThinSampled,
+    DropOldest,
+}
+
 #[derive(Clone)]
 pub(crate) enum TransactionEventState {
     Idle,
@@ -103,19 +143,6 @@ pub(crate) enum TransactionEventState {
     WaitingForResponse,
 }
 
-impl TransactionEvent {
-    pub fn is_stop(&self) -> bool {
-        matches!(self, TransactionEvent::Stop(_))
-    }
-    pub fn get_local_transaction_id(&self) -> Option<u32> {
-        match self {
-            TransactionEvent::Start(t) => Some(t.local_transaction_id),
-            TransactionEvent::Meter(t) => t.local_transaction_id,
-            TransactionEvent::Stop(t) => Some(t.local_transaction_id),
-        }
-    }
-}
-
 impl<I: ChargePointInterface> ChargePoint<I> {
     pub(crate) async fn pop_event(
         &mut self,
@@ -135,6 +162,16 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     self.active_local_transactions[connector_id] =
                         Some((local_transaction_id, Some(transaction_id)));
                 }
+                if let Some(connector_id) =
+                    self.transaction_connector_map.get(&local_transaction_id)
+                {
+                    self.notify_state_change(StateChange::TransactionIdAssigned {
+                        connector_id: connector_id + 1,
+                        local_transaction_id,
+                        transaction_id,
+                    })
+                    .await;
+                }
             }
             if meter_tx.is_some() {
                 self.transaction_map.remove(&local_transaction_id);
@@ -143,34 +180,161 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     .remove(&local_transaction_id);
             }
         }
+        let mut indices = vec![self.transaction_tail];
+        indices.append(&mut self.transaction_batch);
+        self.transaction_queue_len = self
+            .transaction_queue_len
+            .saturating_sub(indices.len() as u64);
         self.interface
             .db_pop_transaction_event(
-                self.transaction_tail,
+                &indices,
                 local_transaction_id,
                 transaction_id,
                 meter_tx,
+                self.transaction_queue_len,
             )
             .await;
-        self.transaction_tail += 1;
+        self.transaction_tail = indices.last().map_or(self.transaction_tail, |t| t + 1);
         self.transaction_event_state = TransactionEventState::Idle;
         self.transaction_event_retries = 0;
-        if self.transaction_tail != self.transaction_head {
-            self.transacion_current_event = Some(
-                self.interface
-                    .db_get_transaction_event(self.transaction_tail)
-                    .await,
+        self.load_transaction_event().await;
+    }
+    // events lost to storage corruption are skipped
+    pub(crate) async fn load_transaction_event(&mut self) {
+        self.transacion_current_event = None;
+        while self.transaction_tail != self.transaction_head {
+            if let Some(event) = self
+                .interface
+                .db_get_transaction_event(self.transaction_tail)
+                .await
+            {
+                self.transacion_current_event = Some(event);
+                return;
+            }
+            self.transaction_tail += 1;
+        }
+    }
+    // consecutive meter values of one connector and transaction go out in one
+    // request, so a long offline period is not replayed one sample at a time
+    async fn batch_meter_events(&mut self) {
+        let max_len = self.configs.meter_values_batch_max_length.value;
+        let mut batch = match self.transacion_current_event.take() {
+            Some(TransactionEvent::Meter(t)) => t,
+            event => {
+                self.transacion_current_event = event;
+                return;
+            }
+        };
+        let mut index = self
+            .transaction_batch
+            .last()
+            .copied()
+            .unwrap_or(self.transaction_tail)
+            + 1;
+        while index < self.transaction_head && batch.meter_value.len() < max_len {
+            match self.interface.db_get_transaction_event(index).await {
+                Some(TransactionEvent::Meter(t))
+                    if t.connector_id == batch.connector_id
+                        && t.local_transaction_id == batch.local_transaction_id
+                        && batch.meter_value.len() + t.meter_value.len() <= max_len =>
+                {
+                    batch.meter_value.extend(t.meter_value);
+                    self.transaction_batch.push(index);
+                }
+                // dropped by the queue budget or lost to corruption
+                None => {}
+                Some(_) => break,
+            }
+            index += 1;
+        }
+        self.transacion_current_event = Some(TransactionEvent::Meter(batch));
+    }
+    // drops meter values once the queue outgrows its budget. it compacts to
+    // three quarters of the budget so this does not run for every new event
+    async fn compact_transaction_queue(&mut self) {
+        let max_len = self.configs.transaction_queue_max_length.value;
+        if max_len == 0 || self.transaction_queue_len <= max_len {
+            return;
+        }
+        let excess = self.transaction_queue_len - (max_len - max_len / 4);
+        // the current event may be in flight
+        let first = match &self.transacion_current_event {
+            Some(_) => {
+                self.transaction_batch
+                    .last()
+                    .copied()
+                    .unwrap_or(self.transaction_tail)
+                    + 1
+            }
+            None => self.transaction_tail,
+        };
+        let mut dropped = BTreeSet::new();
+        if self.configs.transaction_queue_drop_policy.value == QueueDropPolicy::ThinSampled {
+            let mut keep = true;
+            for index in first..self.transaction_head {
+                if dropped.len() as u64 == excess {
+                    break;
+                }
+                if let Some(TransactionEvent::Meter(t)) =
+                    self.interface.db_get_transaction_event(index).await
+                {
+                    if t.is_sampled() {
+                        if !keep {
+                            dropped.insert(index);
+                        }
+                        keep = !keep;
+                    }
+                }
+            }
+        }
+        for index in first..self.transaction_head {
+            if dropped.len() as u64 == excess {
+                break;
+            }
+            if dropped.contains(&index) {
+                continue;
+            }
+            if let Some(TransactionEvent::Meter(_)) =
+                self.interface.db_get_transaction_event(index).await
+            {
+                dropped.insert(index);
+            }
+        }
+        if dropped.is_empty() {
+            log::warn!(
+                "transaction queue holds {} events, over its limit of {}, but nothing can be dropped",
+                self.transaction_queue_len,
+                max_len
             );
-        } else {
-            self.transacion_current_event = None;
+            return;
         }
+        log::warn!(
+            "transaction queue over its limit of {}, dropping {} meter values events",
+            max_len,
+            dropped.len()
+        );
+        let dropped: Vec<u64> = dropped.into_iter().collect();
+        self.transaction_queue_len -= dropped.len() as u64;
+        self.interface
+            .db_drop_transaction_events(&dropped, self.transaction_queue_len)
+            .await;
     }
     pub(crate) async fn process_transaction(&mut self) {
         loop {
             if self.call_permission() {
+                if let (TransactionEventState::Idle, 0) = (
+                    &self.transaction_event_state,
+                    self.transaction_event_retries,
+                ) {
+                    self.batch_meter_events().await;
+                }
                 if let (TransactionEventState::Idle, Some(tx)) = (
                     self.transaction_event_state.clone(),
                     self.transacion_current_event.clone(),
                 ) {
+                    if self.transaction_event_retries == 0 {
+                        self.report_estimated_time(&tx).await;
+                    }
                     match tx {
                         TransactionEvent::Start(t) => {
                             self.transaction_event_state =
@@ -195,7 +359,6 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                                 }
                                 Err(_) => {
                                     //corresponsing transaction_id not found, droping
-                                    assert!(self.transaction_event_retries == 0);
                                     self.pop_event(t.local_transaction_id, None, None).await;
                                 }
                             }
@@ -211,11 +374,10 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                                 self.enqueue_call(CallAction::StopTransaction, req).await;
                             } else {
                                 //corresponsing transaction_id not found, droping
-                                assert!(self.transaction_event_retries == 0);
                                 let meter_tx = self
                                     .transaction_stop_meter_val_count
                                     .get(&t.local_transaction_id)
-                                    .map(|f| *f)
+                                    .copied()
                                     .unwrap_or(0);
                                 self.pop_event(Some(t.local_transaction_id), None, Some(meter_tx))
                                     .await;
@@ -234,7 +396,10 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         if let Some(date) = self.get_time().await {
             TransactionTime::Known(date)
         } else {
-            TransactionTime::Unaligned(Instant::now(&self.interface).await)
+            TransactionTime::Unaligned {
+                boot: self.boot_id,
+                uptime: Instant::now(&self.interface).await,
+            }
         }
     }
     pub(crate) async fn add_transaction_event(&mut self, mut event: TransactionEvent) {
@@ -257,17 +422,15 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             }
             _ => {}
         }
+        self.transaction_queue_len += 1;
         self.interface
-            .db_push_transaction_event(self.transaction_head, event)
+            .db_push_transaction_event(self.transaction_head, event, self.transaction_queue_len)
             .await;
 
         self.transaction_head += 1;
+        self.compact_transaction_queue().await;
         if self.transacion_current_event.is_none() {
-            self.transacion_current_event = Some(
-                self.interface
-                    .db_get_transaction_event(self.transaction_tail)
-                    .await,
-            );
+            self.load_transaction_event().await;
         }
         self.process_transaction().await;
     }
@@ -305,6 +468,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         let local_transaction_id = self.local_transaction_id + 1;
         self.local_transaction_id += 1;
         self.active_local_transactions[connector_id] = Some((local_transaction_id, None));
+        self.active_transaction_start[connector_id] = Some(Instant::now(&self.interface).await);
         self.change_connector_state(
             connector_id,
             ConnectorState::transaction(
@@ -329,8 +493,14 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             timestamp: self.get_transaction_time().await,
         };
         self.start_meter_data(connector_id).await;
+        self.update_charging_limit(connector_id).await;
         self.add_transaction_event(TransactionEvent::Start(start_event))
             .await;
+        self.notify_state_change(StateChange::TransactionStarted {
+            connector_id: connector_id + 1,
+            local_transaction_id,
+        })
+        .await;
     }
 
     pub(crate) async fn stop_transaction(
@@ -339,6 +509,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
         id_tag: Option<String>,
         reason: Option<Reason>,
     ) {
+        let stopped_transaction = self.active_local_transactions[connector_id];
         let (new_state, stop_event) = match &self.connector_state[connector_id] {
             ConnectorState::Transaction {
                 secc_state,
@@ -357,6 +528,7 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                     }
                 };
                 self.active_local_transactions[connector_id] = None;
+                self.active_transaction_start[connector_id] = None;
                 let meter_stop = self
                     .interface
                     .get_start_stop_meter_value(connector_id)
@@ -372,12 +544,24 @@ impl<I: ChargePointInterface> ChargePoint<I> {
                 (new_state, TransactionEvent::Stop(stop_event))
             }
             _ => {
-                unreachable!();
+                log::warn!("no transaction to stop on connector {}", connector_id + 1);
+                return;
             }
         };
         self.stop_meter_data(connector_id).await;
+        self.remove_transaction_charging_profiles(connector_id)
+            .await;
+        self.update_charging_limit(connector_id).await;
         self.change_connector_state(connector_id, new_state).await;
         self.add_transaction_event(stop_event).await;
+        if let Some((local_transaction_id, transaction_id)) = stopped_transaction {
+            self.notify_state_change(StateChange::TransactionStopped {
+                connector_id: connector_id + 1,
+                local_transaction_id,
+                transaction_id,
+            })
+            .await;
+        }
         if self.active_local_transactions.iter().all(|f| f.is_none()) {
             if let FirmwareState::WaitingForTransactionToFinish = &self.firmware_state {
                 self.try_firmware_install().await;
@@ -478,10 +662,52 @@ impl<I: ChargePointInterface> ChargePoint<I> {
             }),
         }
     }
-    fn parse_transaction_time(&self, time: TransactionTime) -> DateTime<Utc> {
+    pub(crate) fn parse_transaction_time(&self, time: TransactionTime) -> DateTime<Utc> {
         match time {
             TransactionTime::Known(t) => t,
-            TransactionTime::Unaligned(t) => self.get_time_since(t).unwrap_or(self.default_time()),
+            TransactionTime::Unaligned { boot, uptime } => self
+                .get_boot_time_since(boot, uptime)
+                .or_else(|| {
+                    log::warn!("estimating the timestamp of a record from boot {}", boot);
+                    self.estimate_boot_time_since(boot, uptime)
+                })
+                .unwrap_or(self.default_time()),
+        }
+    }
+    async fn report_estimated_time(&mut self, event: &TransactionEvent) {
+        let (local_transaction_id, estimated) = match event {
+            TransactionEvent::Start(t) => (
+                Some(t.local_transaction_id),
+                self.is_estimated_time(&t.timestamp),
+            ),
+            TransactionEvent::Meter(t) => (
+                t.local_transaction_id,
+                t.meter_value
+                    .iter()
+                    .any(|f| self.is_estimated_time(&f.timestamp)),
+            ),
+            TransactionEvent::Stop(t) => (
+                Some(t.local_transaction_id),
+                self.is_estimated_time(&t.timestamp)
+                    || t.transaction_data
+                        .iter()
+                        .flatten()
+                        .any(|f| self.is_estimated_time(&f.timestamp)),
+            ),
+        };
+        if estimated {
+            self.notify_state_change(StateChange::TimestampEstimated {
+                local_transaction_id,
+            })
+            .await;
+        }
+    }
+    pub(crate) fn is_estimated_time(&self, time: &TransactionTime) -> bool {
+        match time {
+            TransactionTime::Known(_) => false,
+            TransactionTime::Unaligned { boot, uptime } => {
+                self.get_boot_time_since(*boot, *uptime).is_none()
+            }
         }
     }
 }
@@ -3,6 +3,7 @@ pub mod tc_002_cs;
 pub mod tc_003_cs;
 pub mod tc_004_1_cs;
 pub mod tc_004_2_cs;
+pub mod tc_005_1_cs;
 pub mod tc_005_2_cs;
 pub mod tc_005_3_cs;
 pub mod tc_007_cs;
@@ -15,7 +16,9 @@ pub mod tc_013_cs;
 pub mod tc_014_cs;
 pub mod tc_015_cs;
 pub mod tc_016_cs;
+pub mod tc_017_1_cs;
 pub mod tc_017_2_cs;
+pub mod tc_018_1_cs;
 pub mod tc_018_2_cs;
 pub mod tc_019_cs;
 pub mod tc_021_cs;
@@ -52,13 +55,19 @@ pub mod tc_047_cs;
 pub mod tc_048_1_cs;
 pub mod tc_048_2_cs;
 pub mod tc_048_3_cs;
+pub mod tc_049_cs;
 pub mod tc_051_cs;
 pub mod tc_052_cs;
 pub mod tc_053_cs;
 pub mod tc_054_cs;
 pub mod tc_055_cs;
+pub mod tc_056_cs;
+pub mod tc_057_cs;
+pub mod tc_058_cs;
 pub mod tc_061_cs;
 pub mod tc_062_cs;
+pub mod tc_066_cs;
+pub mod tc_067_cs;
 pub mod tc_068_cs;
 pub mod tc_069_cs;
 pub mod tc_070_cs;
@@ -16,7 +16,7 @@ pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
     let transaction_id = 1;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
     let id_tag_info = IdTagInfo {
         expiry_date: None,
         parent_id_tag: None,
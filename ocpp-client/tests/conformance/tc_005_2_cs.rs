@@ -17,7 +17,7 @@ pub async fn run() {
     let connector_id = 1;
     let transaction_id = 1;
 
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
 
     let chain = test_chain!(
         ChargingState::default(num_connectors, connector_id, transaction_id, id_tag)
@@ -35,9 +35,7 @@ pub async fn run() {
         }),
         respond(StopTransactionResponse { id_tag_info: None }),
         any_order(2),
-        call(UnlockConnectorRequest {
-            connector_id: connector_id
-        }),
+        call(UnlockConnectorRequest { connector_id }),
         await_ws_msg(UnlockConnectorResponse {
             status: UnlockStatus::NotSupported
         }),
@@ -17,7 +17,7 @@ pub async fn run() {
     let connector_id = 1;
     let transaction_id = 1;
 
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
 
     let chain = test_chain!(
         ChargingState::default(num_connectors, connector_id, transaction_id, id_tag.clone())
@@ -28,9 +28,7 @@ pub async fn run() {
             status: ChargePointStatus::SuspendedEV
         }),
         respond(StatusNotificationResponse {}),
-        call(RemoteStopTransactionRequest {
-            transaction_id: transaction_id
-        }),
+        call(RemoteStopTransactionRequest { transaction_id }),
         await_ws_msg(RemoteStopTransactionResponse {
             status: RemoteStartStopStatus::Accepted
         }),
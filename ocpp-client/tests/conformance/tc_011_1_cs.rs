@@ -54,7 +54,7 @@ fn get_authorize_remote_transaction_requests_true_chain(
         );
     }
 
-    let chain = test_chain!(
+    test_chain!(
         chain,
         plug(connector_id),
         await_ws_msg(StatusNotificationRequest {
@@ -77,9 +77,7 @@ fn get_authorize_remote_transaction_requests_true_chain(
             transaction_id
         }),
         any_order(2),
-    );
-
-    chain
+    )
 }
 
 fn change_authorize_remote_transaction_requests(
@@ -102,7 +100,7 @@ pub async fn run() {
     let connector_id = 1;
     let transaction_id1 = 1;
     let transaction_id2 = 2;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
     let id_tag_info = IdTagInfo {
         expiry_date: None,
         parent_id_tag: None,
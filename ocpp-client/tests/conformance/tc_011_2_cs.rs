@@ -52,7 +52,7 @@ fn get_authorize_remote_transaction_requests_chain(
         );
     }
 
-    let res = test_chain!(
+    test_chain!(
         res,
         await_ws_msg(StatusNotificationRequest {
             connector_id: connector_id,
@@ -64,9 +64,7 @@ fn get_authorize_remote_transaction_requests_chain(
             status: ChargePointStatus::Available
         }),
         respond(StatusNotificationResponse {}),
-    );
-
-    res
+    )
 }
 
 fn change_authorize_remote_transaction_requests(
@@ -87,7 +85,7 @@ fn change_authorize_remote_transaction_requests(
 pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
     let id_tag_info = IdTagInfo {
         expiry_date: None,
         parent_id_tag: None,
@@ -16,7 +16,7 @@ pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
     let transaction_id = 1;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
 
     let chain = test_chain!(
         ChargingState::default(num_connectors, connector_id, transaction_id, id_tag.clone())
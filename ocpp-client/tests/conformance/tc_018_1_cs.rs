@@ -0,0 +1,45 @@
+use rocpp_core::v16::{
+    messages::{
+        status_notification::{StatusNotificationRequest, StatusNotificationResponse},
+        stop_transaction::{StopTransactionRequest, StopTransactionResponse},
+        unlock_connector::{UnlockConnectorRequest, UnlockConnectorResponse},
+    },
+    types::{ChargePointStatus, Reason, UnlockStatus},
+};
+
+use crate::{
+    state::reusable_states::{ChargingState, ReusableState},
+    test_chain,
+};
+
+pub async fn run() {
+    let num_connectors = 2;
+    let connector_id = 1;
+    let transaction_id = 1;
+
+    let id_tag = "1234".to_string();
+
+    let chain = test_chain!(
+        ChargingState::default(num_connectors, connector_id, transaction_id, id_tag)
+            .get_test_chain()
+            .with_cable_locks(),
+        call(UnlockConnectorRequest { connector_id }),
+        await_ws_msg(StopTransactionRequest {
+            transaction_id: transaction_id,
+            id_tag: None,
+            reason: Some(Reason::UnlockCommand)
+        }),
+        respond(StopTransactionResponse { id_tag_info: None }),
+        await_ws_msg(UnlockConnectorResponse {
+            status: UnlockStatus::Unlocked
+        }),
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: connector_id,
+            status: ChargePointStatus::Finishing
+        }),
+        respond(StatusNotificationResponse {}),
+    )
+    .check_unlocked(vec![connector_id]);
+
+    chain.run(15, vec![], None).await;
+}
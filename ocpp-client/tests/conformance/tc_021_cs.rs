@@ -58,7 +58,7 @@ pub async fn run() {
                     .map_err(|_| "expected CallResult, found CallError")?;
                 let (value, _) = get_single(res, "MeterValueSampleInterval")?;
                 let value = value.ok_or("expected value")?;
-                if value != "10".to_string() {
+                if value != "10" {
                     return Err("Invalid MeterValueSampleInterval");
                 }
                 Ok(())
@@ -14,7 +14,7 @@ use crate::{
 pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
 
     let id_tag_info = IdTagInfo {
         expiry_date: None,
@@ -28,9 +28,7 @@ pub async fn run() {
         await_ws_msg(AuthorizeRequest {
             id_tag: id_tag.clone()
         }),
-        respond(AuthorizeResponse {
-            id_tag_info: id_tag_info
-        }),
+        respond(AuthorizeResponse { id_tag_info }),
         plug(connector_id),
         await_ws_msg(StatusNotificationRequest {
             connector_id: connector_id,
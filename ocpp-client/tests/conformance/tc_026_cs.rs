@@ -14,8 +14,8 @@ pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
     let transaction_id = 1;
-    let id_tag1 = format!("1234");
-    let id_tag2 = format!("2345");
+    let id_tag1 = "1234".to_string();
+    let id_tag2 = "2345".to_string();
 
     let chain = test_chain!(
         ChargingState::default(num_connectors, connector_id, transaction_id, id_tag1)
@@ -14,9 +14,7 @@ pub async fn run() {
 
     let chain = test_chain!(
         BootState::default(num_connectors).get_test_chain(),
-        call(UnlockConnectorRequest {
-            connector_id: connector_id
-        }),
+        call(UnlockConnectorRequest { connector_id }),
         await_ws_msg(UnlockConnectorResponse {
             status: UnlockStatus::NotSupported
         })
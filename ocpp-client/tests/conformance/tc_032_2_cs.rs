@@ -12,7 +12,7 @@ pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
     let transaction_id = 1;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
 
     let base_dir = std::env::temp_dir();
     let db_dir = Some(base_dir.join("tc_032_2"));
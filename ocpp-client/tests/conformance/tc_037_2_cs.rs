@@ -20,7 +20,7 @@ pub async fn run() {
     let connector_id = 1;
     let transaction_id = 1;
     let list_version = 1;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
     let id_tag_info = IdTagInfo {
         expiry_date: None,
         parent_id_tag: None,
@@ -58,7 +58,7 @@ pub async fn run() {
         }),
         respond(StartTransactionResponse {
             id_tag_info: id_tag_info.clone(),
-            transaction_id: transaction_id
+            transaction_id
         }),
         await_ws_msg(StatusNotificationRequest {
             connector_id: connector_id,
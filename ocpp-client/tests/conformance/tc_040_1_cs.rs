@@ -14,8 +14,8 @@ pub async fn run() {
     let chain = test_chain!(
         BootState::default(num_connectors).get_test_chain(),
         call(ChangeConfigurationRequest {
-            key: format!("Testing"),
-            value: format!("true")
+            key: "Testing".to_string(),
+            value: "true".to_string()
         }),
         await_ws_msg(ChangeConfigurationResponse {
             status: ConfigurationStatus::NotSupported
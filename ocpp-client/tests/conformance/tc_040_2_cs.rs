@@ -14,8 +14,8 @@ pub async fn run() {
     let chain = test_chain!(
         BootState::default(num_connectors).get_test_chain(),
         call(ChangeConfigurationRequest {
-            key: format!("MeterValueSampleInterval"),
-            value: format!("-1")
+            key: "MeterValueSampleInterval".to_string(),
+            value: "-1".to_string()
         }),
         await_ws_msg(ChangeConfigurationResponse {
             status: ConfigurationStatus::Rejected
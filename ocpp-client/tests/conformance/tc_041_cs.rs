@@ -11,7 +11,7 @@ use crate::{
 pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
 
     let chain = test_chain!(
         BootState::default(num_connectors).get_test_chain(),
@@ -10,7 +10,7 @@ use crate::{
 
 pub async fn run() {
     let num_connectors = 2;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
     let id_tag_info = IdTagInfo {
         expiry_date: None,
         parent_id_tag: None,
@@ -22,7 +22,7 @@ pub async fn run() {
         call(SendLocalListRequest {
             list_version: 1,
             local_authorization_list: Some(vec![AuthorizationData {
-                id_tag: id_tag,
+                id_tag,
                 id_tag_info: Some(id_tag_info)
             }]),
             update_type: UpdateType::Full
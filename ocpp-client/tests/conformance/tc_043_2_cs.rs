@@ -16,7 +16,7 @@ pub async fn run() {
 
     let auth_data_full = vec![
         AuthorizationData {
-            id_tag: format!("1234"),
+            id_tag: "1234".to_string(),
             id_tag_info: Some(IdTagInfo {
                 expiry_date: None,
                 parent_id_tag: None,
@@ -24,7 +24,7 @@ pub async fn run() {
             }),
         },
         AuthorizationData {
-            id_tag: format!("2345"),
+            id_tag: "2345".to_string(),
             id_tag_info: Some(IdTagInfo {
                 expiry_date: None,
                 parent_id_tag: None,
@@ -34,12 +34,12 @@ pub async fn run() {
     ];
 
     let auth_data_differential1 = vec![AuthorizationData {
-        id_tag: format!("2345"),
+        id_tag: "2345".to_string(),
         id_tag_info: None,
     }];
 
     let auth_data_differential2 = vec![AuthorizationData {
-        id_tag: format!("2345"),
+        id_tag: "2345".to_string(),
         id_tag_info: Some(IdTagInfo {
             expiry_date: None,
             parent_id_tag: None,
@@ -10,14 +10,14 @@ use crate::{
 
 pub async fn run() {
     let num_connectors = 2;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
 
     let chain = test_chain!(
         BootState::default(num_connectors).get_test_chain(),
         call(SendLocalListRequest {
             list_version: 2,
             local_authorization_list: Some(vec![AuthorizationData {
-                id_tag: id_tag,
+                id_tag,
                 id_tag_info: None
             }]),
             update_type: UpdateType::Full
@@ -12,7 +12,7 @@ pub async fn run() {
     let num_connectors = 2;
     let auth_data_full = vec![
         AuthorizationData {
-            id_tag: format!("1234"),
+            id_tag: "1234".to_string(),
             id_tag_info: Some(IdTagInfo {
                 expiry_date: None,
                 parent_id_tag: None,
@@ -20,7 +20,7 @@ pub async fn run() {
             }),
         },
         AuthorizationData {
-            id_tag: format!("2345"),
+            id_tag: "2345".to_string(),
             id_tag_info: Some(IdTagInfo {
                 expiry_date: None,
                 parent_id_tag: None,
@@ -30,7 +30,7 @@ pub async fn run() {
     ];
 
     let auth_data_differential = vec![AuthorizationData {
-        id_tag: format!("2345"),
+        id_tag: "2345".to_string(),
         id_tag_info: None,
     }];
 
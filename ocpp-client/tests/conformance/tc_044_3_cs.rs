@@ -16,7 +16,7 @@ use crate::{
 
 pub async fn run() {
     let num_connectors = 2;
-    let location = format!("download_success:install:fail");
+    let location = "download_success:install:fail".to_string();
 
     let chain = test_chain!(
         BootState::default(num_connectors).get_test_chain(),
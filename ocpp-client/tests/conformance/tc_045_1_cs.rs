@@ -15,7 +15,7 @@ use crate::{
 
 pub async fn run() {
     let num_connectors = 2;
-    let location = format!("valid_location");
+    let location = "valid_location".to_string();
 
     let chain = test_chain!(
         BootState::default(num_connectors).get_test_chain(),
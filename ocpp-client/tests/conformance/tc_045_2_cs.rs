@@ -15,7 +15,7 @@ use crate::{
 
 pub async fn run() {
     let num_connectors = 2;
-    let location = format!("invalid_location");
+    let location = "invalid_location".to_string();
 
     let chain = test_chain!(
         BootState::default(num_connectors).get_test_chain(),
@@ -21,7 +21,7 @@ pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
     let transaction_id = 1;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
 
     let id_tag_info = IdTagInfo {
         expiry_date: None,
@@ -76,8 +76,8 @@ pub async fn run() {
             reservation_id: Some(reservation_id)
         }),
         respond(StartTransactionResponse {
-            id_tag_info: id_tag_info,
-            transaction_id: transaction_id
+            id_tag_info,
+            transaction_id
         }),
         await_ws_msg(StatusNotificationRequest {
             connector_id: connector_id,
@@ -16,7 +16,7 @@ use crate::{
 pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
 
     let reservation_expiry_date = Utc::now().checked_add_months(Months::new(1)).unwrap();
     let reservation_id = 1;
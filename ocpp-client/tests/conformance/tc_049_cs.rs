@@ -0,0 +1,74 @@
+use rocpp_core::v16::{
+    messages::data_transfer::{DataTransferRequest, DataTransferResponse},
+    types::DataTransferStatus,
+};
+
+use crate::{
+    state::reusable_states::{BootState, ReusableState},
+    test_chain,
+};
+
+pub async fn run() {
+    let num_connectors = 2;
+
+    let mut chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        call(DataTransferRequest {
+            vendor_id: "rocpp".to_string(),
+            message_id: Some("echo".to_string()),
+            data: Some("hello".to_string())
+        }),
+        await_ws_msg(DataTransferResponse {
+            status: DataTransferStatus::Accepted,
+            data: Some("hello".to_string())
+        }),
+        call(DataTransferRequest {
+            vendor_id: "unknown".to_string(),
+            message_id: None,
+            data: None
+        }),
+        await_ws_msg(DataTransferResponse {
+            status: DataTransferStatus::UnknownVendorId
+        }),
+        call(DataTransferRequest {
+            vendor_id: "rocpp".to_string(),
+            message_id: Some("unknown".to_string()),
+            data: None
+        }),
+        await_ws_msg(DataTransferResponse {
+            status: DataTransferStatus::UnknownMessageId
+        }),
+    )
+    .send_data_transfer(DataTransferRequest {
+        vendor_id: "rocpp".to_string(),
+        message_id: Some("status".to_string()),
+        data: Some("1".to_string()),
+    });
+    chain = test_chain!(
+        chain,
+        await_ws_msg(DataTransferRequest {
+            vendor_id: "rocpp".to_string(),
+            message_id: Some("status".to_string()),
+            data: Some("1".to_string())
+        }),
+        respond(DataTransferResponse {
+            status: DataTransferStatus::Accepted,
+            data: Some("ok".to_string())
+        }),
+        // a round trip makes sure the response above has been handed to the application
+        call(DataTransferRequest {
+            vendor_id: "rocpp".to_string(),
+            message_id: Some("echo".to_string()),
+            data: None
+        }),
+        await_ws_msg(DataTransferResponse {
+            status: DataTransferStatus::Accepted
+        }),
+    )
+    .check_data_transfer_responses(vec![Some((
+        DataTransferStatus::Accepted,
+        Some("ok".to_string()),
+    ))]);
+
+    chain.run(15, vec![], None).await;
+}
@@ -22,8 +22,8 @@ pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
     let transaction_id = 1;
-    let id_tag1 = format!("1234");
-    let id_tag2 = format!("2345");
+    let id_tag1 = "1234".to_string();
+    let id_tag2 = "2345".to_string();
 
     let id_tag2_info = IdTagInfo {
         expiry_date: None,
@@ -78,7 +78,7 @@ pub async fn run() {
         }),
         respond(StartTransactionResponse {
             id_tag_info: id_tag2_info,
-            transaction_id: transaction_id
+            transaction_id
         }),
         await_ws_msg(StatusNotificationRequest {
             connector_id: connector_id,
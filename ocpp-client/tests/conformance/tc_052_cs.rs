@@ -16,7 +16,7 @@ use crate::{
 pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
 
     let reservation_expiry_date = Utc::now().checked_add_months(Months::new(1)).unwrap();
     let reservation_id = 1;
@@ -27,7 +27,7 @@ pub async fn run() {
         call(ReserveNowRequest {
             connector_id,
             expiry_date: reservation_expiry_date,
-            id_tag: id_tag,
+            id_tag,
             parent_id_tag: None,
             reservation_id
         }),
@@ -18,12 +18,12 @@ pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
     let transaction_id = 1;
-    let id_tag1 = format!("1234");
-    let id_tag2 = format!("2345");
+    let id_tag1 = "1234".to_string();
+    let id_tag2 = "2345".to_string();
 
     let id_tag2_info = IdTagInfo {
         expiry_date: None,
-        parent_id_tag: Some(format!("parent")),
+        parent_id_tag: Some("parent".to_string()),
         status: AuthorizationStatus::Accepted,
     };
 
@@ -36,7 +36,7 @@ pub async fn run() {
             connector_id,
             expiry_date: reservation_expiry_date,
             id_tag: id_tag1.clone(),
-            parent_id_tag: Some(format!("parent")),
+            parent_id_tag: Some("parent".to_string()),
             reservation_id
         }),
         await_ws_msg(ReserveNowResponse {
@@ -65,7 +65,7 @@ pub async fn run() {
         }),
         respond(StartTransactionResponse {
             id_tag_info: id_tag2_info,
-            transaction_id: transaction_id
+            transaction_id
         }),
         await_ws_msg(StatusNotificationRequest {
             connector_id: connector_id,
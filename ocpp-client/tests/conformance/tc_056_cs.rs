@@ -0,0 +1,91 @@
+use rocpp_core::v16::{
+    messages::set_charging_profile::{SetChargingProfileRequest, SetChargingProfileResponse},
+    types::{ChargingProfilePurposeType, ChargingProfileStatus},
+};
+
+use crate::{
+    harness::charging_profile::ChargingProfileBuilder,
+    state::reusable_states::{BootState, ReusableState},
+    test_chain,
+};
+
+pub async fn run() {
+    let num_connectors = 2;
+    let connector_id = 1;
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        call(SetChargingProfileRequest {
+            connector_id,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                1,
+                ChargingProfilePurposeType::TxDefaultProfile
+            )
+            .duration(300)
+            .periods(vec![(0, 6.0)])
+            .number_phases(3)
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Accepted
+        }),
+        call(SetChargingProfileRequest {
+            connector_id: 0,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                2,
+                ChargingProfilePurposeType::ChargePointMaxProfile
+            )
+            .duration(300)
+            .periods(vec![(0, 6.0)])
+            .number_phases(3)
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Accepted
+        }),
+        call(SetChargingProfileRequest {
+            connector_id,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                3,
+                ChargingProfilePurposeType::ChargePointMaxProfile
+            )
+            .duration(300)
+            .periods(vec![(0, 6.0)])
+            .number_phases(3)
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Rejected
+        }),
+        call(SetChargingProfileRequest {
+            connector_id,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                4,
+                ChargingProfilePurposeType::TxProfile
+            )
+            .duration(300)
+            .periods(vec![(0, 6.0)])
+            .number_phases(3)
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Rejected
+        }),
+        call(SetChargingProfileRequest {
+            connector_id: (num_connectors + 1) as i32,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                5,
+                ChargingProfilePurposeType::TxDefaultProfile
+            )
+            .duration(300)
+            .periods(vec![(0, 6.0)])
+            .number_phases(3)
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Rejected
+        }),
+    );
+
+    chain.run(15, vec![], None).await;
+}
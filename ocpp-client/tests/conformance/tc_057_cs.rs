@@ -0,0 +1,69 @@
+use rocpp_core::v16::{
+    messages::set_charging_profile::{SetChargingProfileRequest, SetChargingProfileResponse},
+    types::{ChargingProfilePurposeType, ChargingProfileStatus, ChargingRateUnitType},
+};
+
+use crate::{
+    harness::charging_profile::ChargingProfileBuilder,
+    state::reusable_states::{ChargingState, ReusableState},
+    test_chain,
+};
+
+pub async fn run() {
+    let num_connectors = 2;
+    let connector_id = 1;
+    let transaction_id = 1;
+    let id_tag = "1234".to_string();
+
+    let chain = test_chain!(
+        ChargingState::default(num_connectors, connector_id, transaction_id, id_tag)
+            .get_test_chain(),
+        call(SetChargingProfileRequest {
+            connector_id: connector_id as i32,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                1,
+                ChargingProfilePurposeType::TxProfile
+            )
+            .transaction_id(transaction_id)
+            .duration(300)
+            .unit(ChargingRateUnitType::W)
+            .periods(vec![(0, 4000.0)])
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Accepted
+        }),
+        call(SetChargingProfileRequest {
+            connector_id: connector_id as i32,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                2,
+                ChargingProfilePurposeType::TxProfile
+            )
+            .transaction_id(transaction_id + 1)
+            .duration(300)
+            .unit(ChargingRateUnitType::W)
+            .periods(vec![(0, 4000.0)])
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Rejected
+        }),
+        call(SetChargingProfileRequest {
+            connector_id: (connector_id + 1) as i32,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                3,
+                ChargingProfilePurposeType::TxProfile
+            )
+            .transaction_id(transaction_id)
+            .duration(300)
+            .unit(ChargingRateUnitType::W)
+            .periods(vec![(0, 4000.0)])
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Rejected
+        }),
+    );
+
+    chain.run(15, vec![], None).await;
+}
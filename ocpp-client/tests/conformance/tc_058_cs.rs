@@ -0,0 +1,109 @@
+use rocpp_client::v16::ChargingLimit;
+use rocpp_core::v16::{
+    messages::{
+        clear_charging_profile::{ClearChargingProfileRequest, ClearChargingProfileResponse},
+        meter_values::{MeterValuesRequest, MeterValuesResponse},
+        set_charging_profile::{SetChargingProfileRequest, SetChargingProfileResponse},
+    },
+    types::{
+        ChargingProfilePurposeType, ChargingProfileStatus, ChargingRateUnitType,
+        ClearChargingProfileStatus,
+    },
+};
+
+use crate::{
+    harness::charging_profile::ChargingProfileBuilder,
+    state::reusable_states::{
+        stop_transaction_chain, AuthorizeState, BootState, ChargingState, ReusableState,
+    },
+    test_chain,
+};
+
+fn amps(limit: f32) -> ChargingLimit {
+    ChargingLimit {
+        limit,
+        unit: ChargingRateUnitType::A,
+        number_phases: 3,
+    }
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+    let connector_id = 1;
+    let id_tag = "abc".to_string();
+    let transaction_id = 1;
+
+    let mut chain = BootState::default(num_connectors)
+        .get_test_chain()
+        .check_charging_limit(1, amps(32.0))
+        .check_charging_limit(2, amps(32.0));
+    chain = test_chain!(
+        chain,
+        call(SetChargingProfileRequest {
+            connector_id: 0,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                1,
+                ChargingProfilePurposeType::TxDefaultProfile
+            )
+            .relative()
+            .periods(vec![(0, 10.0), (2, 16.0)])
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Accepted
+        }),
+    )
+    .check_charging_limit(1, amps(10.0))
+    .check_charging_limit(2, amps(10.0))
+    .merge(AuthorizeState::default(num_connectors, connector_id, id_tag.clone()).get_self_chain())
+    .merge(
+        ChargingState::default(num_connectors, connector_id, transaction_id, id_tag.clone())
+            .get_self_chain(),
+    )
+    .check_charging_limit(1, amps(10.0));
+    // the relative schedule moves to its second period 2s after the transaction started
+    chain = test_chain!(
+        chain,
+        await_ws_msg(MeterValuesRequest {}),
+        respond(MeterValuesResponse {}),
+    )
+    .check_charging_limit(1, amps(16.0))
+    .check_charging_limit(2, amps(10.0));
+    chain = test_chain!(
+        chain,
+        call(SetChargingProfileRequest {
+            connector_id: 0,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                2,
+                ChargingProfilePurposeType::ChargePointMaxProfile
+            )
+            .relative()
+            .periods(vec![(0, 12.0)])
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Accepted
+        }),
+    )
+    .check_charging_limit(1, amps(12.0))
+    .check_charging_limit(2, amps(10.0));
+    chain = test_chain!(
+        chain,
+        call(ClearChargingProfileRequest {
+            id: Some(2),
+            connector_id: None,
+            charging_profile_purpose: None,
+            stack_level: None
+        }),
+        await_ws_msg(ClearChargingProfileResponse {
+            status: ClearChargingProfileStatus::Accepted
+        }),
+    )
+    .check_charging_limit(1, amps(16.0))
+    .merge(stop_transaction_chain(connector_id, id_tag, transaction_id))
+    .check_charging_limit(1, amps(10.0));
+
+    chain
+        .run(15, vec![("MeterValueSampleInterval", "3")], None)
+        .await;
+}
@@ -14,7 +14,7 @@ pub async fn run() {
     let mut chain = test_chain!(
         BootState::default(num_connectors).get_test_chain(),
         call(DataTransferRequest {
-            vendor_id: format!("vendor"),
+            vendor_id: "vendor".to_string(),
             message_id: None,
             data: None
         })
@@ -23,7 +23,7 @@ pub async fn run() {
         .await_ws_msg::<DataTransferResponse>()
         .check(|f| {
             (f.status == DataTransferStatus::Accepted)
-                .then(|| format!("DataTransferStatus::Accepted was not expected"))
+                .then(|| "DataTransferStatus::Accepted was not expected".to_string())
         })
         .done();
     chain.run(15, vec![], None).await;
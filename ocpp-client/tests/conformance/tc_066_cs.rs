@@ -0,0 +1,107 @@
+use rocpp_core::v16::{
+    messages::{
+        get_composite_schedule::{GetCompositeScheduleRequest, GetCompositeScheduleResponse},
+        set_charging_profile::{SetChargingProfileRequest, SetChargingProfileResponse},
+    },
+    types::{
+        ChargingProfilePurposeType, ChargingProfileStatus, ChargingRateUnitType,
+        GetCompositeScheduleStatus,
+    },
+};
+
+use crate::{
+    harness::charging_profile::ChargingProfileBuilder,
+    state::reusable_states::{BootState, ReusableState},
+    test_chain,
+};
+
+fn check_periods(res: &GetCompositeScheduleResponse, expected: &[(i32, f32)]) -> Option<String> {
+    if res.status != GetCompositeScheduleStatus::Accepted {
+        return Some("GetCompositeScheduleStatus::Accepted expected".to_string());
+    }
+    let periods = match &res.charging_schedule {
+        Some(schedule) => &schedule.charging_schedule_period,
+        None => return Some("charging_schedule missing".to_string()),
+    };
+    if periods.len() != expected.len() {
+        return Some(format!(
+            "expected {} periods, found {:?}",
+            expected.len(),
+            periods
+        ));
+    }
+    for (period, (start_period, limit)) in periods.iter().zip(expected) {
+        if (period.start_period - start_period).abs() > 2 || period.limit != *limit {
+            return Some(format!("unexpected period {:?}", period));
+        }
+    }
+    None
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+
+    let mut chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        call(SetChargingProfileRequest {
+            connector_id: 0,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                1,
+                ChargingProfilePurposeType::ChargePointMaxProfile
+            )
+            .periods(vec![(0, 16.0)])
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Accepted
+        }),
+        call(SetChargingProfileRequest {
+            connector_id: 1,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                2,
+                ChargingProfilePurposeType::TxDefaultProfile
+            )
+            .duration(120)
+            .periods(vec![(0, 10.0), (60, 20.0)])
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Accepted
+        }),
+        call(GetCompositeScheduleRequest {
+            connector_id: 1,
+            duration: 300,
+            charging_rate_unit: Some(ChargingRateUnitType::A)
+        }),
+    );
+    chain = chain
+        .await_ws_msg::<GetCompositeScheduleResponse>()
+        .check(|f| check_periods(f, &[(0, 10.0), (60, 16.0)]))
+        .done()
+        .call(GetCompositeScheduleRequest {
+            connector_id: 1,
+            duration: 30,
+            charging_rate_unit: Some(ChargingRateUnitType::W),
+        })
+        .await_ws_msg::<GetCompositeScheduleResponse>()
+        .check(|f| check_periods(f, &[(0, 6900.0)]))
+        .done()
+        .call(GetCompositeScheduleRequest {
+            connector_id: 2,
+            duration: 300,
+            charging_rate_unit: Some(ChargingRateUnitType::A),
+        })
+        .await_ws_msg::<GetCompositeScheduleResponse>()
+        .check(|f| check_periods(f, &[(0, 16.0)]))
+        .done()
+        .call(GetCompositeScheduleRequest {
+            connector_id: (num_connectors + 1) as i32,
+            duration: 300,
+            charging_rate_unit: None,
+        })
+        .await_ws_msg::<GetCompositeScheduleResponse>()
+        .check_eq(&GetCompositeScheduleStatus::Rejected, |f| &f.status)
+        .done();
+
+    chain.run(15, vec![], None).await;
+}
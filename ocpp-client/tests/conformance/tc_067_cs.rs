@@ -0,0 +1,132 @@
+use rocpp_core::v16::{
+    messages::{
+        clear_charging_profile::{ClearChargingProfileRequest, ClearChargingProfileResponse},
+        set_charging_profile::{SetChargingProfileRequest, SetChargingProfileResponse},
+    },
+    types::{
+        ChargingProfilePurposeType, ChargingProfileStatus, ClearChargingProfileStatus,
+        RecurrencyKindType,
+    },
+};
+
+use crate::{
+    harness::charging_profile::ChargingProfileBuilder,
+    state::reusable_states::{BootState, ReusableState},
+    test_chain,
+};
+
+pub async fn run() {
+    let num_connectors = 2;
+
+    let base_dir = std::env::temp_dir();
+    let db_dir = Some(base_dir.join("tc_067"));
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        call(SetChargingProfileRequest {
+            connector_id: 1,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                1,
+                ChargingProfilePurposeType::TxDefaultProfile
+            )
+            .stack_level(0)
+            .recurring(RecurrencyKindType::Daily)
+            .duration(3600)
+            .periods(vec![(0, 10.0)])
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Accepted
+        }),
+        call(SetChargingProfileRequest {
+            connector_id: 2,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                2,
+                ChargingProfilePurposeType::TxDefaultProfile
+            )
+            .stack_level(1)
+            .recurring(RecurrencyKindType::Daily)
+            .duration(3600)
+            .periods(vec![(0, 10.0)])
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Accepted
+        }),
+        call(SetChargingProfileRequest {
+            connector_id: 0,
+            cs_charging_profiles: ChargingProfileBuilder::new(
+                3,
+                ChargingProfilePurposeType::TxDefaultProfile
+            )
+            .stack_level(2)
+            .recurring(RecurrencyKindType::Daily)
+            .duration(3600)
+            .periods(vec![(0, 10.0)])
+            .build()
+        }),
+        await_ws_msg(SetChargingProfileResponse {
+            status: ChargingProfileStatus::Accepted
+        }),
+        cut_power(),
+        await_hard_reset(),
+        spawn_new(15, vec![], db_dir.clone(), false),
+        merge(BootState::default(num_connectors).get_test_chain()),
+        call(ClearChargingProfileRequest {
+            id: Some(1),
+            connector_id: None,
+            charging_profile_purpose: None,
+            stack_level: None
+        }),
+        await_ws_msg(ClearChargingProfileResponse {
+            status: ClearChargingProfileStatus::Accepted
+        }),
+        call(ClearChargingProfileRequest {
+            id: Some(1),
+            connector_id: None,
+            charging_profile_purpose: None,
+            stack_level: None
+        }),
+        await_ws_msg(ClearChargingProfileResponse {
+            status: ClearChargingProfileStatus::Unknown
+        }),
+        call(ClearChargingProfileRequest {
+            id: None,
+            connector_id: Some(2),
+            charging_profile_purpose: Some(ChargingProfilePurposeType::TxDefaultProfile),
+            stack_level: Some(2)
+        }),
+        await_ws_msg(ClearChargingProfileResponse {
+            status: ClearChargingProfileStatus::Unknown
+        }),
+        call(ClearChargingProfileRequest {
+            id: None,
+            connector_id: Some(2),
+            charging_profile_purpose: Some(ChargingProfilePurposeType::TxDefaultProfile),
+            stack_level: Some(1)
+        }),
+        await_ws_msg(ClearChargingProfileResponse {
+            status: ClearChargingProfileStatus::Accepted
+        }),
+        call(ClearChargingProfileRequest {
+            id: None,
+            connector_id: None,
+            charging_profile_purpose: None,
+            stack_level: None
+        }),
+        await_ws_msg(ClearChargingProfileResponse {
+            status: ClearChargingProfileStatus::Accepted
+        }),
+        call(ClearChargingProfileRequest {
+            id: None,
+            connector_id: None,
+            charging_profile_purpose: None,
+            stack_level: None
+        }),
+        await_ws_msg(ClearChargingProfileResponse {
+            status: ClearChargingProfileStatus::Unknown
+        }),
+    );
+
+    chain.run(15, vec![], db_dir).await;
+}
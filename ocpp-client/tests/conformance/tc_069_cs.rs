@@ -17,17 +17,17 @@ pub async fn run() {
     let connector_id = 1;
     let transaction_id = 1;
 
-    let id_tag1 = format!("1234");
+    let id_tag1 = "1234".to_string();
     let id_tag1_info = IdTagInfo {
         expiry_date: None,
-        parent_id_tag: Some(format!("parent")),
+        parent_id_tag: Some("parent".to_string()),
         status: AuthorizationStatus::Accepted,
     };
 
-    let id_tag2 = format!("2345");
+    let id_tag2 = "2345".to_string();
     let id_tag2_info = IdTagInfo {
         expiry_date: None,
-        parent_id_tag: Some(format!("parent")),
+        parent_id_tag: Some("parent".to_string()),
         status: AuthorizationStatus::Accepted,
     };
 
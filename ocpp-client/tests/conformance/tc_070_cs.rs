@@ -23,7 +23,7 @@ pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
     let transaction_id = 1;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
     let meter_value_sample_interval = 4;
     let tol = 20;
     let count = 5;
@@ -46,7 +46,7 @@ pub async fn run() {
                     }
                 };
                 let value = if value.is_empty() {
-                    format!("Energy.Active.Import.Register")
+                    "Energy.Active.Import.Register".to_string()
                 } else {
                     value
                 };
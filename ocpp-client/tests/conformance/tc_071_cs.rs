@@ -24,7 +24,7 @@ pub async fn run() {
     let num_connectors = 2;
     let connector_id = 1;
     let transaction_id = 1;
-    let id_tag = format!("1234");
+    let id_tag = "1234".to_string();
     let clock_aligned_data_interval = 4;
     let tol = 20;
     let count = 5;
@@ -47,7 +47,7 @@ pub async fn run() {
                     }
                 };
                 let value = if value.is_empty() {
-                    format!("Energy.Active.Import.Register")
+                    "Energy.Active.Import.Register".to_string()
                 } else {
                     value
                 };
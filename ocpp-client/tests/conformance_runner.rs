@@ -3,6 +3,7 @@ use std::future::Future;
 use tokio::task::LocalSet;
 
 mod conformance;
+mod extensions;
 mod harness;
 mod state;
 
@@ -52,6 +53,11 @@ async fn tc_069_cs() {
     run_in_local(conformance::tc_069_cs::run()).await;
 }
 
+#[tokio::test]
+async fn tc_005_1_cs() {
+    run_in_local(conformance::tc_005_1_cs::run()).await;
+}
+
 #[tokio::test]
 async fn tc_005_2_cs() {
     run_in_local(conformance::tc_005_2_cs::run()).await;
@@ -112,11 +118,21 @@ async fn tc_016_cs() {
     run_in_local(conformance::tc_016_cs::run()).await;
 }
 
+#[tokio::test]
+async fn tc_017_1_cs() {
+    run_in_local(conformance::tc_017_1_cs::run()).await;
+}
+
 #[tokio::test]
 async fn tc_017_2_cs() {
     run_in_local(conformance::tc_017_2_cs::run()).await;
 }
 
+#[tokio::test]
+async fn tc_018_1_cs() {
+    run_in_local(conformance::tc_018_1_cs::run()).await;
+}
+
 #[tokio::test]
 async fn tc_018_2_cs() {
     run_in_local(conformance::tc_018_2_cs::run()).await;
@@ -312,6 +328,11 @@ async fn tc_048_3_cs() {
     run_in_local(conformance::tc_048_3_cs::run()).await;
 }
 
+#[tokio::test]
+async fn tc_049_cs() {
+    run_in_local(conformance::tc_049_cs::run()).await;
+}
+
 #[tokio::test]
 async fn tc_051_cs() {
     run_in_local(conformance::tc_051_cs::run()).await;
@@ -337,7 +358,171 @@ async fn tc_055_cs() {
     run_in_local(conformance::tc_055_cs::run()).await;
 }
 
+#[tokio::test]
+async fn tc_056_cs() {
+    run_in_local(conformance::tc_056_cs::run()).await;
+}
+
+#[tokio::test]
+async fn tc_057_cs() {
+    run_in_local(conformance::tc_057_cs::run()).await;
+}
+
+#[tokio::test]
+async fn tc_058_cs() {
+    run_in_local(conformance::tc_058_cs::run()).await;
+}
+
 #[tokio::test]
 async fn tc_062_cs() {
     run_in_local(conformance::tc_062_cs::run()).await;
 }
+
+#[tokio::test]
+async fn tc_066_cs() {
+    run_in_local(conformance::tc_066_cs::run()).await;
+}
+
+#[tokio::test]
+async fn tc_067_cs() {
+    run_in_local(conformance::tc_067_cs::run()).await;
+}
+
+#[tokio::test]
+async fn ext_commands() {
+    run_in_local(extensions::commands::run()).await;
+}
+
+#[tokio::test]
+async fn ext_observer() {
+    run_in_local(extensions::observer::run()).await;
+}
+
+#[tokio::test]
+async fn ext_shutdown() {
+    run_in_local(extensions::shutdown::run()).await;
+}
+#[tokio::test]
+async fn ext_shutdown_offline() {
+    run_in_local(extensions::shutdown::run_offline()).await;
+}
+
+#[tokio::test]
+async fn ext_shutdown_remote_start() {
+    run_in_local(extensions::shutdown::run_remote_start()).await;
+}
+
+#[tokio::test]
+async fn ext_reconnect() {
+    run_in_local(extensions::reconnect::run()).await;
+}
+#[tokio::test]
+async fn ext_reconnect_failed_connects() {
+    run_in_local(extensions::reconnect::run_failed_connects()).await;
+}
+
+#[tokio::test]
+async fn ext_security() {
+    run_in_local(extensions::security::run()).await;
+}
+#[tokio::test]
+async fn ext_security_reboot() {
+    run_in_local(extensions::security::run_reboot()).await;
+}
+
+#[tokio::test]
+async fn ext_certificates() {
+    run_in_local(extensions::certificates::run()).await;
+}
+#[tokio::test]
+async fn ext_certificates_invalid() {
+    run_in_local(extensions::certificates::run_invalid()).await;
+}
+#[tokio::test]
+async fn ext_certificates_store_full() {
+    run_in_local(extensions::certificates::run_store_full()).await;
+}
+
+#[tokio::test]
+async fn ext_security_events() {
+    run_in_local(extensions::security_events::run()).await;
+}
+
+#[tokio::test]
+async fn ext_signed_firmware() {
+    run_in_local(extensions::signed_firmware::run()).await;
+}
+
+#[tokio::test]
+async fn ext_firmware_persistence() {
+    run_in_local(extensions::firmware_persistence::run()).await;
+}
+
+#[tokio::test]
+async fn ext_clock() {
+    run_in_local(extensions::clock::run()).await;
+}
+#[tokio::test]
+async fn ext_clock_anchor_writes() {
+    run_in_local(extensions::clock::run_anchor_writes()).await;
+}
+
+#[tokio::test]
+async fn ext_storage() {
+    run_in_local(extensions::storage::run()).await;
+}
+
+#[tokio::test]
+async fn ext_config_migration() {
+    run_in_local(extensions::config_migration::run()).await;
+}
+
+#[tokio::test]
+async fn ext_transaction_queue() {
+    run_in_local(extensions::transaction_queue::run()).await;
+}
+
+#[tokio::test]
+async fn ext_event_order() {
+    run_in_local(extensions::event_order::run()).await;
+}
+
+#[tokio::test]
+async fn ext_meter_batching() {
+    run_in_local(extensions::meter_batching::run()).await;
+}
+
+#[tokio::test]
+async fn ext_call_delivery() {
+    run_in_local(extensions::call_delivery::run()).await;
+}
+
+#[tokio::test]
+async fn ext_call_delivery_reboot() {
+    run_in_local(extensions::call_delivery::run_reboot()).await;
+}
+
+#[tokio::test]
+async fn ext_call_timeouts() {
+    run_in_local(extensions::call_timeouts::run()).await;
+}
+#[tokio::test]
+async fn ext_call_timeouts_slower_link() {
+    run_in_local(extensions::call_timeouts::run_slower_link()).await;
+}
+#[tokio::test]
+async fn ext_unknown_clock() {
+    run_in_local(extensions::unknown_clock::run()).await;
+}
+#[tokio::test]
+async fn ext_unknown_clock_aligned_meter() {
+    run_in_local(extensions::unknown_clock::run_aligned_meter()).await;
+}
+#[tokio::test]
+async fn ext_ws_ping() {
+    run_in_local(extensions::ws_ping::run()).await;
+}
+#[tokio::test]
+async fn ext_ws_ping_clock_sync() {
+    run_in_local(extensions::ws_ping::run_clock_sync()).await;
+}
@@ -0,0 +1,121 @@
+use chrono::Utc;
+use rocpp_core::v16::{
+    messages::{
+        authorize::{AuthorizeRequest, AuthorizeResponse},
+        boot_notification::{BootNotificationRequest, BootNotificationResponse},
+        status_notification::{StatusNotificationRequest, StatusNotificationResponse},
+    },
+    types::{AuthorizationStatus, ChargePointStatus, IdTagInfo, RegistrationStatus},
+};
+
+use crate::{
+    harness::{database::read_table, harness::get_cms_url},
+    state::{
+        reusable_states::{get_all_connector_states, BootState, ConnectionState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+pub async fn run() {
+    let num_connectors = 2;
+    let connector_id = 1;
+    let id_tag = "1234".to_string();
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        present_id_tag(connector_id, id_tag.clone()),
+        await_ws_msg(AuthorizeRequest {
+            id_tag: id_tag.clone()
+        }),
+        // Retry:1 sends it once more after the call timeout
+        await_ws_msg(AuthorizeRequest {
+            id_tag: id_tag.clone()
+        }),
+        with_timing(5000, 1000),
+        respond(AuthorizeResponse {
+            id_tag_info: IdTagInfo {
+                expiry_date: None,
+                parent_id_tag: None,
+                status: AuthorizationStatus::Accepted
+            }
+        }),
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: connector_id,
+            status: ChargePointStatus::Preparing
+        }),
+        // dropped before the answer, UntilDelivered sends it again once online
+        close_connection(),
+        await_disconnection(),
+        restore_connection(),
+        await_connection(get_cms_url(), false),
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: connector_id,
+            status: ChargePointStatus::Preparing
+        }),
+        respond(StatusNotificationResponse {}),
+    );
+
+    chain.run(20, vec![], None).await;
+}
+
+// an UntilDelivered call still unanswered when the power goes is sent after
+// the next boot
+pub async fn run_reboot() {
+    let num_connectors = 2;
+    let connector_id = 1;
+    let id_tag = "1234".to_string();
+    let dir = std::env::temp_dir().join("ext_call_delivery_reboot");
+    let db_dir = Some(dir.clone());
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        present_id_tag(connector_id, id_tag.clone()),
+        await_ws_msg(AuthorizeRequest { id_tag: id_tag }),
+        respond(AuthorizeResponse {
+            id_tag_info: IdTagInfo {
+                expiry_date: None,
+                parent_id_tag: None,
+                status: AuthorizationStatus::Accepted
+            }
+        }),
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: connector_id,
+            status: ChargePointStatus::Preparing
+        }),
+        cut_power(),
+        await_hard_reset(),
+        spawn_new(15, vec![], db_dir.clone(), false),
+        merge(ConnectionState::default().get_test_chain()),
+        merge(
+            TestChain::new()
+                .await_ws_msg::<BootNotificationRequest>()
+                .done()
+                .respond_with_now(BootNotificationResponse {
+                    current_time: Utc::now(),
+                    interval: 1000,
+                    status: RegistrationStatus::Accepted,
+                })
+        ),
+        // held calls go out ahead of the statuses of this boot
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: connector_id,
+            status: ChargePointStatus::Preparing
+        }),
+        respond(StatusNotificationResponse {}),
+        merge(get_all_connector_states(vec![
+            ChargePointStatus::Available;
+            num_connectors
+        ])),
+    )
+    .operation(move |_| {
+        let rows = read_table(&dir, "held_call");
+        assert!(
+            !rows.keys().any(|key| key.starts_with("call:")),
+            "calls left held: {:?}",
+            rows
+        );
+    });
+
+    chain.run(15, vec![], db_dir).await;
+}
@@ -0,0 +1,150 @@
+use rocpp_core::v16::{
+    messages::{
+        authorize::{AuthorizeRequest, AuthorizeResponse},
+        change_configuration::{ChangeConfigurationRequest, ChangeConfigurationResponse},
+        status_notification::{StatusNotificationRequest, StatusNotificationResponse},
+    },
+    protocol_error::ProtocolError,
+    types::{AuthorizationStatus, ChargePointStatus, ConfigurationStatus, IdTagInfo},
+};
+
+use crate::{
+    state::{
+        reusable_states::{BootState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+fn authorize_response(status: AuthorizationStatus) -> AuthorizeResponse {
+    AuthorizeResponse {
+        id_tag_info: IdTagInfo {
+            expiry_date: None,
+            parent_id_tag: None,
+            status,
+        },
+    }
+}
+
+fn preparing(connector_id: usize) -> TestChain {
+    test_chain!(
+        TestChain::new(),
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: connector_id,
+            status: ChargePointStatus::Preparing
+        }),
+        respond(StatusNotificationResponse {}),
+    )
+}
+
+fn change_configuration(key: &str, value: &str) -> TestChain {
+    test_chain!(
+        TestChain::new(),
+        call(ChangeConfigurationRequest {
+            key: key.into(),
+            value: value.into()
+        }),
+        await_ws_msg(ChangeConfigurationResponse {
+            status: ConfigurationStatus::Accepted
+        }),
+    )
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        // the boot round trips bring the 5s call timeout down to the 2s floor
+        present_id_tag(1, "1234".to_string()),
+        await_ws_msg(AuthorizeRequest {
+            id_tag: "1234".to_string()
+        }),
+        await_ws_msg(AuthorizeRequest {
+            id_tag: "1234".to_string()
+        }),
+        with_timing(1980, 1040),
+        // worth another try, after waiting as long as a timeout would, the
+        // timeout dropped the estimate so that is the configured 5s again
+        respond_error(ProtocolError::InternalError),
+        await_ws_msg(AuthorizeRequest {
+            id_tag: "1234".to_string()
+        }),
+        with_timing(4980, 1040),
+        respond(authorize_response(AuthorizationStatus::Accepted)),
+        merge(preparing(1)),
+        // the same answer would come back, so it goes to the response handler
+        present_id_tag(2, "5678".to_string()),
+        await_ws_msg(AuthorizeRequest {
+            id_tag: "5678".to_string()
+        }),
+        respond_error(ProtocolError::NotImplemented),
+        present_id_tag(2, "9999".to_string()),
+        await_ws_msg(AuthorizeRequest {
+            id_tag: "9999".to_string()
+        }),
+        with_timing(0, 500),
+        respond(authorize_response(AuthorizationStatus::Invalid)),
+        merge(change_configuration("AdaptiveCallTimeout", "false")),
+        merge(change_configuration("CallActionTimeouts", "Authorize=1")),
+        present_id_tag(2, "4321".to_string()),
+        await_ws_msg(AuthorizeRequest {
+            id_tag: "4321".to_string()
+        }),
+        await_ws_msg(AuthorizeRequest {
+            id_tag: "4321".to_string()
+        }),
+        with_timing(1000, 800),
+        respond(authorize_response(AuthorizationStatus::Accepted)),
+        merge(preparing(2)),
+    );
+
+    chain
+        .run(
+            20,
+            vec![
+                ("AdaptiveCallTimeout", "true"),
+                ("CallDeliveryPolicy", "Authorize=Retry:2"),
+                ("ConnectionTimeOut", "60"),
+            ],
+            None,
+        )
+        .await;
+}
+
+// the estimate learned on a fast link must not outlive the link getting slower
+pub async fn run_slower_link() {
+    let num_connectors = 2;
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        present_id_tag(1, "1234".to_string()),
+        await_ws_msg(AuthorizeRequest {
+            id_tag: "1234".to_string()
+        }),
+        // left unanswered, timing out after the learned 2s
+        await_ws_msg(AuthorizeRequest {
+            id_tag: "1234".to_string()
+        }),
+        with_timing(1980, 1040),
+        // the timeout dropped the estimate, the retry gets the configured 5s
+        await_ws_msg(AuthorizeRequest {
+            id_tag: "1234".to_string()
+        }),
+        with_timing(4980, 1040),
+        respond(authorize_response(AuthorizationStatus::Accepted)),
+        merge(preparing(1)),
+    );
+
+    chain
+        .run(
+            20,
+            vec![
+                ("AdaptiveCallTimeout", "true"),
+                ("CallDeliveryPolicy", "Authorize=Retry:2"),
+                ("ConnectionTimeOut", "60"),
+            ],
+            None,
+        )
+        .await;
+}
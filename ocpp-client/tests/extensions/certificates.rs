@@ -0,0 +1,295 @@
+use rocpp_client::v16::ClientCertificate;
+use rocpp_core::v16::{
+    messages::{
+        certificate_signed::{CertificateSignedRequest, CertificateSignedResponse},
+        change_configuration::{ChangeConfigurationRequest, ChangeConfigurationResponse},
+        delete_certificate::{DeleteCertificateRequest, DeleteCertificateResponse},
+        extended_trigger_message::{ExtendedTriggerMessageRequest, ExtendedTriggerMessageResponse},
+        get_installed_certificate_ids::{
+            GetInstalledCertificateIdsRequest, GetInstalledCertificateIdsResponse,
+        },
+        install_certificate::{InstallCertificateRequest, InstallCertificateResponse},
+        sign_certificate::{SignCertificateRequest, SignCertificateResponse},
+    },
+    types::{
+        CertificateSignedStatus, CertificateStatus, CertificateUse, ConfigurationStatus,
+        DeleteCertificateStatus, ExtendedMessageTrigger, GenericStatus,
+        GetInstalledCertificateStatus, TriggerMessageStatus,
+    },
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    harness::{
+        crypto::{mock_certificate, mock_csr, mock_hash_data, MOCK_KEY_HANDLE},
+        database::read_table,
+        harness::get_cms_url,
+    },
+    state::{
+        reusable_states::{BootState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+fn install_chain(
+    certificate_type: CertificateUse,
+    certificate: String,
+    status: CertificateStatus,
+) -> TestChain {
+    test_chain!(
+        TestChain::new(),
+        call(InstallCertificateRequest {
+            certificate_type,
+            certificate
+        }),
+        await_ws_msg(InstallCertificateResponse { status: status }),
+    )
+}
+
+fn installed_ids_chain(certificate_type: CertificateUse, serial_numbers: Vec<&str>) -> TestChain {
+    let (status, certificate_hash_data) = if serial_numbers.is_empty() {
+        (GetInstalledCertificateStatus::NotFound, None)
+    } else {
+        (
+            GetInstalledCertificateStatus::Accepted,
+            Some(serial_numbers.into_iter().map(mock_hash_data).collect()),
+        )
+    };
+    test_chain!(
+        TestChain::new(),
+        call(GetInstalledCertificateIdsRequest { certificate_type }),
+        await_ws_msg(GetInstalledCertificateIdsResponse {
+            status: status,
+            certificate_hash_data: certificate_hash_data
+        }),
+    )
+}
+
+fn delete_chain(serial_number: &str, status: DeleteCertificateStatus) -> TestChain {
+    test_chain!(
+        TestChain::new(),
+        call(DeleteCertificateRequest {
+            certificate_hash_data: mock_hash_data(serial_number)
+        }),
+        await_ws_msg(DeleteCertificateResponse { status: status }),
+    )
+}
+
+fn certificate_signed_chain(
+    certificate_chain: String,
+    status: CertificateSignedStatus,
+) -> TestChain {
+    test_chain!(
+        TestChain::new(),
+        call(CertificateSignedRequest { certificate_chain }),
+        await_ws_msg(CertificateSignedResponse { status: status }),
+    )
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+    let overrides = vec![("CertificateSignedMaxChainSize", "64")];
+    let db_dir = Some(std::env::temp_dir().join("ext_certificates"));
+    let signed = mock_certificate("cp");
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        merge(installed_ids_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            vec![]
+        )),
+        merge(install_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            "not a certificate".to_string(),
+            CertificateStatus::Rejected
+        )),
+        merge(install_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            mock_certificate("01"),
+            CertificateStatus::Accepted
+        )),
+        merge(install_chain(
+            CertificateUse::ManufacturerRootCertificate,
+            mock_certificate("02"),
+            CertificateStatus::Accepted
+        )),
+        merge(installed_ids_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            vec!["01"]
+        )),
+        merge(delete_chain("03", DeleteCertificateStatus::NotFound)),
+        merge(delete_chain("02", DeleteCertificateStatus::Accepted)),
+        merge(installed_ids_chain(
+            CertificateUse::ManufacturerRootCertificate,
+            vec![]
+        )),
+        // no key has been generated yet
+        merge(certificate_signed_chain(
+            signed.clone(),
+            CertificateSignedStatus::Rejected
+        )),
+        call(ChangeConfigurationRequest {
+            key: "CpoName".to_string(),
+            value: "ACME".to_string()
+        }),
+        await_ws_msg(ChangeConfigurationResponse {
+            status: ConfigurationStatus::Accepted
+        }),
+        call(ExtendedTriggerMessageRequest {
+            requested_message: ExtendedMessageTrigger::SignChargePointCertificate,
+            connector_id: None
+        }),
+        await_ws_msg(ExtendedTriggerMessageResponse {
+            status: TriggerMessageStatus::Accepted
+        }),
+        await_ws_msg(SignCertificateRequest {
+            csr: mock_csr("ACME", &get_cms_url())
+        }),
+        respond(SignCertificateResponse {
+            status: GenericStatus::Accepted
+        }),
+        // exceeds CertificateSignedMaxChainSize
+        merge(certificate_signed_chain(
+            format!("{}\n{}", signed, mock_certificate("ca")),
+            CertificateSignedStatus::Rejected
+        )),
+        merge(certificate_signed_chain(
+            signed.clone(),
+            CertificateSignedStatus::Accepted
+        )),
+        cut_power(),
+        await_hard_reset(),
+        spawn_new(15, overrides.clone(), db_dir.clone(), false),
+        merge(BootState::default(num_connectors).get_test_chain()),
+        merge(installed_ids_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            vec!["01"]
+        )),
+        call(ChangeConfigurationRequest {
+            key: "SecurityProfile".to_string(),
+            value: "3".to_string()
+        }),
+        await_ws_msg(ChangeConfigurationResponse {
+            status: ConfigurationStatus::Accepted
+        }),
+        await_disconnection(),
+        await_connection(get_cms_url(), false),
+    )
+    .check_connect_attempts({
+        let signed = signed.clone();
+        move |attempts| {
+            let last = attempts.last().unwrap();
+            assert_eq!(last.security_profile, 3);
+            assert_eq!(last.ca_certificate, Some(mock_certificate("01")));
+            assert_eq!(
+                last.client_certificate,
+                Some(ClientCertificate {
+                    certificate: signed,
+                    key_handle: MOCK_KEY_HANDLE.to_string(),
+                })
+            );
+        }
+    })
+    // only the chain and the key handle are stored, the key stays with the crypto
+    .operation({
+        let dir = db_dir.clone().unwrap();
+        move |_| {
+            let rows = read_table(&dir, "client_certificate");
+            let stored: Value = serde_json::from_str(rows["current"].as_str().unwrap()).unwrap();
+            assert_eq!(
+                stored,
+                json!({ "certificate": signed, "key_handle": MOCK_KEY_HANDLE })
+            );
+        }
+    });
+
+    // the configured ca certificate takes over as trust anchor
+    let chain = test_chain!(
+        chain,
+        merge(delete_chain("01", DeleteCertificateStatus::Accepted)),
+        merge(installed_ids_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            vec![]
+        )),
+    );
+
+    chain.run(15, overrides, db_dir).await;
+}
+
+// a certificate that parses but does not verify is not stored
+pub async fn run_invalid() {
+    let num_connectors = 2;
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        merge(install_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            mock_certificate("untrusted"),
+            CertificateStatus::Failed
+        )),
+        merge(install_chain(
+            CertificateUse::ManufacturerRootCertificate,
+            mock_certificate("untrusted"),
+            CertificateStatus::Failed
+        )),
+        merge(installed_ids_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            vec![]
+        )),
+        merge(installed_ids_chain(
+            CertificateUse::ManufacturerRootCertificate,
+            vec![]
+        )),
+    );
+
+    chain.run(15, vec![], None).await;
+}
+
+// CertificateStoreMaxLength counts the certificates of all types, replacing
+// an installed one does not need a free slot
+pub async fn run_store_full() {
+    let num_connectors = 2;
+    let overrides = vec![("CertificateStoreMaxLength", "2")];
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        merge(install_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            mock_certificate("01"),
+            CertificateStatus::Accepted
+        )),
+        merge(install_chain(
+            CertificateUse::ManufacturerRootCertificate,
+            mock_certificate("02"),
+            CertificateStatus::Accepted
+        )),
+        merge(install_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            mock_certificate("03"),
+            CertificateStatus::Rejected
+        )),
+        merge(install_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            mock_certificate("01"),
+            CertificateStatus::Accepted
+        )),
+        merge(installed_ids_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            vec!["01"]
+        )),
+        merge(delete_chain("02", DeleteCertificateStatus::Accepted)),
+        merge(install_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            mock_certificate("03"),
+            CertificateStatus::Accepted
+        )),
+        merge(installed_ids_chain(
+            CertificateUse::CentralSystemRootCertificate,
+            vec!["01", "03"]
+        )),
+    );
+
+    chain.run(15, overrides, None).await;
+}
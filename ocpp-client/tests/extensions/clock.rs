@@ -0,0 +1,164 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use rocpp_client::v16::{StateChange, TimeSource};
+use rocpp_core::v16::{
+    messages::{
+        boot_notification::BootNotificationRequest,
+        get_configuration::{GetConfigurationRequest, GetConfigurationResponse},
+        heart_beat::{HeartbeatRequest, HeartbeatResponse},
+        stop_transaction::{StopTransactionRequest, StopTransactionResponse},
+        trigger_message::{TriggerMessageRequest, TriggerMessageResponse},
+    },
+    types::{MessageTrigger, TriggerMessageStatus},
+};
+use serde_json::{Map, Value};
+
+use crate::{
+    harness::database::read_table,
+    state::{
+        reusable_states::{
+            AuthorizeState, BootState, ChargingState, ConnectionState, ReusableState,
+        },
+        step::TestChain,
+    },
+    test_chain,
+};
+
+fn is_recent(timestamp: DateTime<Utc>) -> Option<String> {
+    ((Utc::now() - timestamp).num_seconds().abs() > 5)
+        .then(|| format!("timestamp {} is not close to the current time", timestamp))
+}
+
+fn stop_chain(transaction_id: i32) -> TestChain {
+    TestChain::new()
+        .await_ws_msg::<StopTransactionRequest>()
+        .check_eq(&transaction_id, |t| &t.transaction_id)
+        .check(|t| is_recent(t.timestamp))
+        .done()
+        .respond(StopTransactionResponse { id_tag_info: None })
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+    let connector_id = 1;
+    let id_tag = "1234".to_string();
+    let db_dir = Some(std::env::temp_dir().join("ext_clock"));
+
+    let chain = test_chain!(
+        ChargingState::default(num_connectors, connector_id, 1, id_tag.clone()).get_test_chain(),
+        // the stop is recorded before the clock is synced and aligned on the next boot
+        cut_power(),
+        await_hard_reset(),
+        spawn_new(15, vec![], db_dir.clone(), false),
+        merge(BootState::default(num_connectors).get_test_chain()),
+        pop(),
+        merge(stop_chain(1)),
+        any_order(num_connectors + 1),
+        merge(
+            AuthorizeState::default(num_connectors, connector_id, id_tag.clone()).get_self_chain()
+        ),
+        merge(ChargingState::default(num_connectors, connector_id, 2, id_tag).get_self_chain()),
+        // the boot with the stop never syncs the clock, so it is estimated on the next one
+        cut_power(),
+        await_hard_reset(),
+        spawn_new(15, vec![], db_dir.clone(), false),
+        merge(ConnectionState::default().get_test_chain()),
+        merge(
+            TestChain::new()
+                .await_ws_msg::<BootNotificationRequest>()
+                .done()
+        ),
+        cut_power(),
+        await_hard_reset(),
+        spawn_new(15, vec![], db_dir.clone(), false),
+        merge(BootState::default(num_connectors).get_test_chain()),
+        pop(),
+        merge(stop_chain(2)),
+        any_order(num_connectors + 1),
+    )
+    .operation(|t| {
+        let changes = t.state_changes.lock().unwrap();
+        assert!(changes.iter().any(|(change, _)| matches!(
+            change,
+            StateChange::TimestampEstimated {
+                local_transaction_id: Some(_)
+            }
+        )));
+        assert_eq!(
+            changes.last().unwrap().1.time_source,
+            Some(TimeSource::CentralSystem)
+        );
+    });
+
+    chain.run(15, vec![], db_dir).await;
+}
+
+fn heartbeat_chain(current_time: DateTime<Utc>) -> TestChain {
+    test_chain!(
+        TestChain::new(),
+        call(TriggerMessageRequest {
+            connector_id: None,
+            requested_message: MessageTrigger::Heartbeat
+        }),
+        await_ws_msg(TriggerMessageResponse {
+            status: TriggerMessageStatus::Accepted
+        }),
+        await_ws_msg(HeartbeatRequest {}),
+        respond(HeartbeatResponse { current_time }),
+        // answered only once the heartbeat response is handled
+        call(GetConfigurationRequest {
+            key: Some(vec!["HeartbeatInterval".to_string()])
+        }),
+        merge(
+            TestChain::new()
+                .await_ws_msg::<GetConfigurationResponse>()
+                .done()
+        ),
+    )
+}
+
+fn anchors(dir: &Path) -> Map<String, Value> {
+    read_table(dir, "clock")
+        .into_iter()
+        .filter(|(key, _)| key.starts_with("anchor:"))
+        .collect()
+}
+
+// a heartbeat correcting a small drift keeps the stored anchor, a jump
+// replaces it
+pub async fn run_anchor_writes() {
+    let num_connectors = 2;
+    let db_dir = Some(std::env::temp_dir().join("ext_clock_anchor_writes"));
+    let dir = db_dir.clone().unwrap();
+    let stored = Arc::new(Mutex::new(Map::new()));
+
+    let chain = BootState::default(num_connectors)
+        .get_test_chain()
+        .operation({
+            let (dir, stored) = (dir.clone(), stored.clone());
+            move |_| {
+                let anchors = anchors(&dir);
+                assert_eq!(anchors.len(), 1, "{:?}", anchors);
+                *stored.lock().unwrap() = anchors;
+            }
+        });
+    let chain = test_chain!(chain, merge(heartbeat_chain(Utc::now())),).operation({
+        let (dir, stored) = (dir.clone(), stored.clone());
+        move |_| assert_eq!(anchors(&dir), *stored.lock().unwrap())
+    });
+    let chain = test_chain!(
+        chain,
+        merge(heartbeat_chain(Utc::now() + Duration::hours(1))),
+    )
+    .operation(move |_| {
+        let anchors = anchors(&dir);
+        assert_eq!(anchors.len(), 1, "{:?}", anchors);
+        assert_ne!(anchors, *stored.lock().unwrap());
+    });
+
+    chain.run(15, vec![], db_dir).await;
+}
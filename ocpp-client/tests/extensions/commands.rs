@@ -0,0 +1,108 @@
+use rocpp_client::v16::{ChargePointCommand, CommandResult};
+use rocpp_core::v16::{
+    messages::{
+        status_notification::{StatusNotificationRequest, StatusNotificationResponse},
+        stop_transaction::{StopTransactionRequest, StopTransactionResponse},
+    },
+    types::{AvailabilityType, ChargePointStatus, Reason},
+};
+
+use crate::{
+    state::reusable_states::{
+        AuthorizeState, BootState, ChargingState, ConnectionState, ReusableState,
+    },
+    test_chain,
+};
+
+pub async fn run() {
+    let num_connectors = 2;
+    let connector_id = 1;
+    let transaction_id = 1;
+    let id_tag = "1234".to_string();
+
+    let inoperative =
+        ChargePointCommand::ChangeAvailability(connector_id, AvailabilityType::Inoperative);
+    let operative =
+        ChargePointCommand::ChangeAvailability(connector_id, AvailabilityType::Operative);
+    let trigger = ChargePointCommand::TriggerStatusNotification(2);
+    let stop = ChargePointCommand::StopTransaction(connector_id);
+    let mut results = vec![
+        (inoperative.clone(), CommandResult::Accepted),
+        (operative.clone(), CommandResult::Accepted),
+        (trigger.clone(), CommandResult::Accepted),
+        (stop.clone(), CommandResult::Rejected),
+        (stop.clone(), CommandResult::Accepted),
+    ];
+
+    let mut chain = BootState::default(num_connectors)
+        .get_test_chain()
+        .send_command(inoperative);
+    chain = test_chain!(
+        chain,
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: connector_id,
+            status: ChargePointStatus::Unavailable
+        }),
+        respond(StatusNotificationResponse {}),
+    )
+    .send_command(operative);
+    chain = test_chain!(
+        chain,
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: connector_id,
+            status: ChargePointStatus::Available
+        }),
+        respond(StatusNotificationResponse {}),
+    )
+    .send_command(trigger);
+    chain = test_chain!(
+        chain,
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: 2,
+            status: ChargePointStatus::Available
+        }),
+        respond(StatusNotificationResponse {}),
+    )
+    .send_command(stop.clone())
+    .merge(AuthorizeState::default(num_connectors, connector_id, id_tag.clone()).get_self_chain())
+    .merge(
+        ChargingState::default(num_connectors, connector_id, transaction_id, id_tag)
+            .get_self_chain(),
+    )
+    .send_command(stop);
+    chain = test_chain!(
+        chain,
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: connector_id,
+            status: ChargePointStatus::Finishing
+        }),
+        respond(StatusNotificationResponse {}),
+        await_ws_msg(StopTransactionRequest {
+            transaction_id: transaction_id,
+            id_tag: None,
+            reason: Some(Reason::Local)
+        }),
+        respond(StopTransactionResponse { id_tag_info: None }),
+        any_order(2),
+    )
+    .check_command_results(results.clone())
+    .send_command(ChargePointCommand::SoftReset);
+    chain = test_chain!(
+        chain,
+        merge(
+            ConnectionState::default()
+                .with_disconnection()
+                .get_self_chain()
+        ),
+        merge(BootState::default(num_connectors).get_self_chain()),
+    );
+    results.push((ChargePointCommand::SoftReset, CommandResult::Accepted));
+    chain = chain
+        .check_command_results(results.clone())
+        .send_command(ChargePointCommand::Shutdown);
+    chain = test_chain!(chain, await_disconnection(), await_hard_reset());
+    results.push((ChargePointCommand::Shutdown, CommandResult::Accepted));
+    chain = chain.check_command_results(results);
+
+    chain.run(15, vec![], None).await;
+}
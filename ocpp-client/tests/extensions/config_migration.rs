@@ -0,0 +1,124 @@
+use std::path::Path;
+
+use chrono::{Months, Utc};
+use rocpp_core::v16::{
+    messages::{
+        change_configuration::{ChangeConfigurationRequest, ChangeConfigurationResponse},
+        get_configuration::{GetConfigurationRequest, GetConfigurationResponse},
+        reserve_now::{ReserveNowRequest, ReserveNowResponse},
+        status_notification::{StatusNotificationRequest, StatusNotificationResponse},
+    },
+    types::{ChargePointStatus, ConfigurationStatus, KeyValue, ReservationStatus},
+};
+use serde_json::json;
+
+use crate::{
+    harness::database::{read_table, write_table},
+    state::{
+        reusable_states::{BootState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+// a key the previous firmware knew about but this one does not
+fn add_legacy_key(dir: &Path) {
+    for table in ["previous_configs", "config"] {
+        let mut rows = read_table(dir, table);
+        rows.insert("LegacyKey".to_string(), json!("1"));
+        write_table(dir, table, &rows);
+    }
+}
+
+fn check_migrated(dir: &Path) {
+    assert!(!read_table(dir, "config").contains_key("LegacyKey"));
+    assert!(!read_table(dir, "previous_configs").contains_key("LegacyKey"));
+    assert_eq!(
+        read_table(dir, "previous_configs").get("MeterValueSampleInterval"),
+        Some(&json!("60"))
+    );
+    assert_eq!(read_table(dir, "schema").get("version"), Some(&json!("3")));
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+    let connector_id = 1;
+    let dir = std::env::temp_dir().join("ext_config_migration");
+    let db_dir = Some(dir.clone());
+    let new_defaults = vec![
+        ("MeterValueSampleInterval", "60"),
+        ("ConnectionTimeOut", "5"),
+    ];
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        call(ChangeConfigurationRequest {
+            key: "MeterValueSampleInterval".into(),
+            value: "30".into()
+        }),
+        await_ws_msg(ChangeConfigurationResponse {
+            status: ConfigurationStatus::Accepted
+        }),
+        call(ReserveNowRequest {
+            connector_id,
+            expiry_date: Utc::now().checked_add_months(Months::new(1)).unwrap(),
+            id_tag: "1234".to_string(),
+            parent_id_tag: None,
+            reservation_id: 1
+        }),
+        await_ws_msg(ReserveNowResponse {
+            status: ReservationStatus::Accepted
+        }),
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: connector_id,
+            status: ChargePointStatus::Reserved
+        }),
+        respond(StatusNotificationResponse {}),
+        cut_power(),
+        await_hard_reset(),
+    )
+    .operation({
+        let dir = dir.clone();
+        move |_| add_legacy_key(&dir)
+    });
+    // the new firmware ships different defaults, the reservation and the value
+    // the csms changed survive
+    let chain = test_chain!(
+        chain,
+        spawn_new(15, new_defaults, db_dir.clone(), false),
+        merge(
+            BootState::default(num_connectors)
+                .with_state(connector_id, ChargePointStatus::Reserved)
+                .get_test_chain()
+        ),
+        call(GetConfigurationRequest {
+            key: Some(vec![
+                "MeterValueSampleInterval".into(),
+                "ConnectionTimeOut".into()
+            ])
+        }),
+        merge(
+            TestChain::new()
+                .await_ws_msg::<GetConfigurationResponse>()
+                .check_eq(
+                    &Some(vec![
+                        KeyValue {
+                            key: "MeterValueSampleInterval".into(),
+                            value: Some("30".into()),
+                            readonly: false
+                        },
+                        KeyValue {
+                            key: "ConnectionTimeOut".into(),
+                            value: Some("5".into()),
+                            readonly: false
+                        }
+                    ]),
+                    |t| &t.configuration_key
+                )
+                .done()
+        ),
+    )
+    .operation(move |_| check_migrated(&dir));
+
+    chain.run(15, vec![], db_dir).await;
+}
@@ -0,0 +1,87 @@
+use rocpp_client::v16::{StateChange, WsEvent};
+use rocpp_core::{
+    format::{frame::Call, message::EncodeDecode},
+    v16::{
+        messages::{
+            heart_beat::{HeartbeatRequest, HeartbeatResponse},
+            trigger_message::{TriggerMessageRequest, TriggerMessageResponse},
+        },
+        types::{MessageTrigger, TriggerMessageStatus},
+    },
+};
+
+use crate::{
+    harness::harness::{get_cms_url, CpHarness},
+    state::{
+        reusable_states::{BootState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+fn trigger_heartbeat() -> TestChain {
+    test_chain!(
+        TestChain::new(),
+        call(TriggerMessageRequest {
+            connector_id: None,
+            requested_message: MessageTrigger::Heartbeat
+        }),
+        await_ws_msg(TriggerMessageResponse {
+            status: TriggerMessageStatus::Accepted
+        }),
+        await_ws_msg(HeartbeatRequest {}),
+        respond(HeartbeatResponse {
+            current_time: chrono::Utc::now()
+        }),
+    )
+}
+
+// a call that would be answered if it was not dropped
+fn stray_call() -> String {
+    Call {
+        unique_id: "stray".to_string(),
+        action: "TriggerMessage".to_string(),
+        payload: serde_json::to_value(TriggerMessageRequest {
+            connector_id: None,
+            requested_message: MessageTrigger::Heartbeat,
+        })
+        .unwrap(),
+    }
+    .encode()
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        // a duplicate connected and a response nobody asked for are ignored
+        merge(TestChain::new().operation(|t: &mut CpHarness| {
+            t.ws_handle.push_raw_event(WsEvent::Connected);
+            t.ws_handle
+                .push_raw_event(WsEvent::Msg(r#"[3,"unknown",{}]"#.to_string()));
+        })),
+        merge(trigger_heartbeat()),
+        close_connection(),
+        await_disconnection(),
+        // a duplicate disconnected and a message while offline are dropped
+        merge(TestChain::new().operation(|t: &mut CpHarness| {
+            t.ws_handle.push_raw_event(WsEvent::Disconnected);
+            t.ws_handle.push_raw_event(WsEvent::Msg(stray_call()));
+        })),
+        restore_connection(),
+        await_connection(get_cms_url(), false),
+        merge(trigger_heartbeat()),
+    )
+    // only the boot and the reconnect bring the charge point online
+    .operation(|t| {
+        let changes = t.state_changes.lock().unwrap();
+        let online = changes
+            .iter()
+            .filter(|(change, _)| matches!(change, StateChange::Online))
+            .count();
+        assert_eq!(online, 2);
+    });
+
+    chain.run(15, vec![], None).await;
+}
@@ -0,0 +1,86 @@
+use chrono::{Duration, Utc};
+use rocpp_core::v16::{
+    messages::{
+        firmware_status_notification::{
+            FirmwareStatusNotificationRequest, FirmwareStatusNotificationResponse,
+        },
+        update_firmware::{UpdateFirmwareRequest, UpdateFirmwareResponse},
+    },
+    types::{ChargePointStatus, FirmwareStatus},
+};
+
+use crate::{
+    state::{
+        reusable_states::{get_all_connector_states, BootState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+fn status_chain(statuses: Vec<FirmwareStatus>) -> TestChain {
+    let mut res = TestChain::new();
+    for status in statuses {
+        res = test_chain!(
+            res,
+            await_ws_msg(FirmwareStatusNotificationRequest { status: status }),
+            respond(FirmwareStatusNotificationResponse {}),
+        );
+    }
+    res
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+    let overrides = vec![];
+    let db_dir = Some(std::env::temp_dir().join("ext_firmware_persistence"));
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        // a failed download with one retry left survives the power cut
+        call(UpdateFirmwareRequest {
+            location: "download_fail".to_string(),
+            retries: Some(1),
+            retrieve_date: Utc::now(),
+            retry_interval: Some(3)
+        }),
+        await_ws_msg(UpdateFirmwareResponse {}),
+        merge(status_chain(vec![FirmwareStatus::Downloading])),
+        cut_power(),
+        await_hard_reset(),
+        spawn_new(15, overrides.clone(), db_dir.clone(), false),
+        merge(BootState::default(num_connectors).get_test_chain()),
+        merge(status_chain(vec![
+            FirmwareStatus::Downloading,
+            FirmwareStatus::DownloadFailed
+        ])),
+        // a download scheduled in the future survives the power cut
+        call(UpdateFirmwareRequest {
+            location: "download_success:install:success".to_string(),
+            retries: None,
+            retrieve_date: Utc::now() + Duration::seconds(4),
+            retry_interval: None
+        }),
+        await_ws_msg(UpdateFirmwareResponse {}),
+        cut_power(),
+        await_hard_reset(),
+        spawn_new(15, overrides.clone(), db_dir.clone(), false),
+        merge(BootState::default(num_connectors).get_test_chain()),
+        merge(status_chain(vec![
+            FirmwareStatus::Downloading,
+            FirmwareStatus::Downloaded
+        ])),
+        merge(get_all_connector_states(vec![
+            ChargePointStatus::Unavailable;
+            num_connectors
+        ])),
+        optional(1),
+        merge(status_chain(vec![FirmwareStatus::Installing])),
+        await_disconnection(),
+        merge(BootState::default(num_connectors).get_test_chain()),
+        pop(),
+        merge(status_chain(vec![FirmwareStatus::Installed])),
+        any_order(num_connectors + 1)
+    );
+
+    chain.run(15, overrides, db_dir).await;
+}
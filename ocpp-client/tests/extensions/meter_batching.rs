@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use chrono::Utc;
+use rocpp_core::v16::{
+    messages::{
+        meter_values::{MeterValuesRequest, MeterValuesResponse},
+        stop_transaction::{StopTransactionRequest, StopTransactionResponse},
+    },
+    types::Reason,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    harness::database::{read_table, write_table},
+    state::{
+        reusable_states::{BootState, ChargingState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+const QUEUED_SAMPLES: u64 = 30;
+
+fn counter(rows: &Map<String, Value>, key: &str) -> u64 {
+    rows.get(key)
+        .and_then(|t| t.as_str())
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(0)
+}
+
+// stands in for a long offline period, one periodic sample per event
+fn queue_samples(dir: &Path) {
+    let mut rows = read_table(dir, "transaction");
+    let local_transaction_id: u32 = rows
+        .keys()
+        .find_map(|key| key.strip_prefix("transaction_connector_map:"))
+        .and_then(|t| t.parse().ok())
+        .unwrap();
+    let head = counter(&rows, "head");
+    let len = counter(&rows, "len");
+    for i in 0..QUEUED_SAMPLES {
+        let event = json!({
+            "Meter": {
+                "connector_id": 0,
+                "local_transaction_id": local_transaction_id,
+                "meter_value": [{
+                    "timestamp": Utc::now(),
+                    "sampled_value": [{ "value": i.to_string(), "context": "Sample.Periodic" }]
+                }]
+            }
+        });
+        rows.insert(format!("event:{}", head + i), json!(event.to_string()));
+    }
+    rows.insert("head".into(), json!((head + QUEUED_SAMPLES).to_string()));
+    rows.insert("len".into(), json!((len + QUEUED_SAMPLES).to_string()));
+    write_table(dir, "transaction", &rows);
+}
+
+fn meter_values_chain(values: &[u64]) -> TestChain {
+    let values: Vec<String> = values.iter().map(|t| t.to_string()).collect();
+    TestChain::new()
+        .await_ws_msg::<MeterValuesRequest>()
+        .check_eq(&Some(1), |t| &t.transaction_id)
+        .check(move |t| {
+            let sent: Vec<String> = t
+                .meter_value
+                .iter()
+                .flat_map(|t| t.sampled_value.iter().map(|t| t.value.clone()))
+                .collect();
+            (sent != values).then(|| format!("expected values {:?}, found {:?}", values, sent))
+        })
+        .done()
+        .respond(MeterValuesResponse {})
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+    let connector_id = 1;
+    let id_tag = "1234".to_string();
+    let dir = std::env::temp_dir().join("ext_meter_batching");
+    let db_dir = Some(dir.clone());
+
+    let chain = test_chain!(
+        ChargingState::default(num_connectors, connector_id, 1, id_tag).get_test_chain(),
+        cut_power(),
+        await_hard_reset(),
+    )
+    .operation({
+        let dir = dir.clone();
+        move |_| queue_samples(&dir)
+    });
+    // the power loss stop pushes the queue over its limit of 20. every other
+    // sample goes first, then the oldest one that is not about to be sent
+    let chain = test_chain!(
+        chain,
+        spawn_new(
+            15,
+            vec![
+                ("MeterValuesBatchMaxLength", "10"),
+                ("TransactionQueueMaxLength", "20"),
+            ],
+            db_dir.clone(),
+            false
+        ),
+        merge(BootState::default(num_connectors).get_test_chain()),
+        pop(),
+        merge(meter_values_chain(&[0, 5, 7, 9, 11, 13, 15, 17, 19, 21])),
+        merge(meter_values_chain(&[23, 25, 27, 29])),
+        merge(
+            TestChain::new()
+                .await_ws_msg::<StopTransactionRequest>()
+                .check_eq(&Some(Reason::PowerLoss), |t| &t.reason)
+                .done()
+                .respond(StopTransactionResponse { id_tag_info: None })
+        ),
+        any_order(num_connectors + 3),
+    )
+    .operation(move |_| {
+        let rows = read_table(&dir, "transaction");
+        assert_eq!(counter(&rows, "len"), 0);
+        assert!(!rows.keys().any(|key| key.starts_with("event:")));
+    });
+
+    chain.run(15, vec![], db_dir).await;
+}
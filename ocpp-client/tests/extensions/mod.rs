@@ -0,0 +1,19 @@
+pub mod call_delivery;
+pub mod call_timeouts;
+pub mod certificates;
+pub mod clock;
+pub mod commands;
+pub mod config_migration;
+pub mod event_order;
+pub mod firmware_persistence;
+pub mod meter_batching;
+pub mod observer;
+pub mod reconnect;
+pub mod security;
+pub mod security_events;
+pub mod shutdown;
+pub mod signed_firmware;
+pub mod storage;
+pub mod transaction_queue;
+pub mod unknown_clock;
+pub mod ws_ping;
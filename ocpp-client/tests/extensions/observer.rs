@@ -0,0 +1,108 @@
+use rocpp_client::v16::{ChargePointCommand, StateChange};
+use rocpp_core::v16::{
+    messages::status_notification::{StatusNotificationRequest, StatusNotificationResponse},
+    types::{ChargePointStatus, RegistrationStatus},
+};
+
+use crate::{
+    state::reusable_states::{
+        stop_transaction_chain, AuthorizeState, BootState, ChargingState, ConnectionState,
+        ReusableState,
+    },
+    test_chain,
+};
+
+pub async fn run() {
+    let num_connectors = 2;
+    let connector_id = 1;
+    let transaction_id = 1;
+    let id_tag = "1234".to_string();
+
+    let chain = BootState::default(num_connectors)
+        .get_test_chain()
+        .check_state_changes(vec![
+            StateChange::RegistrationStatus(RegistrationStatus::Accepted),
+            StateChange::Online,
+        ])
+        .merge(
+            AuthorizeState::default(num_connectors, connector_id, id_tag.clone()).get_self_chain(),
+        )
+        .merge(
+            ChargingState::default(num_connectors, connector_id, transaction_id, id_tag.clone())
+                .get_self_chain(),
+        )
+        .check_snapshot(move |snapshot| {
+            assert!(snapshot.online);
+            assert_eq!(snapshot.registration_status, RegistrationStatus::Accepted);
+            let connector = &snapshot.connectors[connector_id - 1];
+            assert_eq!(connector.status, ChargePointStatus::Charging);
+            assert_eq!(connector.local_transaction_id, Some(1));
+            assert_eq!(connector.transaction_id, Some(transaction_id));
+            assert_eq!(snapshot.connectors[1].status, ChargePointStatus::Available);
+        })
+        .check_state_changes(vec![
+            StateChange::ConnectorStatus {
+                connector_id,
+                status: ChargePointStatus::Preparing,
+            },
+            StateChange::ConnectorStatus {
+                connector_id,
+                status: ChargePointStatus::Charging,
+            },
+            StateChange::TransactionStarted {
+                connector_id,
+                local_transaction_id: 1,
+            },
+            StateChange::TransactionIdAssigned {
+                connector_id,
+                local_transaction_id: 1,
+                transaction_id,
+            },
+        ])
+        .merge(stop_transaction_chain(connector_id, id_tag, transaction_id))
+        .check_snapshot(move |snapshot| {
+            let connector = &snapshot.connectors[connector_id - 1];
+            assert_eq!(connector.status, ChargePointStatus::Available);
+            assert_eq!(connector.local_transaction_id, None);
+            assert_eq!(
+                snapshot.transaction_queue_head,
+                snapshot.transaction_queue_tail
+            );
+        })
+        .check_state_changes(vec![
+            StateChange::ConnectorStatus {
+                connector_id,
+                status: ChargePointStatus::Finishing,
+            },
+            StateChange::TransactionStopped {
+                connector_id,
+                local_transaction_id: 1,
+                transaction_id: Some(transaction_id),
+            },
+            StateChange::ConnectorStatus {
+                connector_id,
+                status: ChargePointStatus::Available,
+            },
+        ])
+        .close_connection();
+    let chain = test_chain!(chain, await_disconnection())
+        .check_snapshot(|snapshot| {
+            assert!(!snapshot.connected);
+            assert!(!snapshot.online);
+        })
+        .check_state_changes(vec![StateChange::Offline])
+        .restore_connection()
+        .merge(ConnectionState::default().get_test_chain())
+        .send_command(ChargePointCommand::TriggerStatusNotification(2));
+    let chain = test_chain!(
+        chain,
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: 2,
+            status: ChargePointStatus::Available
+        }),
+        respond(StatusNotificationResponse {}),
+    )
+    .check_state_changes(vec![StateChange::Online]);
+
+    chain.run(15, vec![], None).await;
+}
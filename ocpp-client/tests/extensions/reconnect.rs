@@ -0,0 +1,96 @@
+use chrono::Utc;
+use rocpp_core::v16::{
+    messages::{
+        boot_notification::{BootNotificationRequest, BootNotificationResponse},
+        trigger_message::{TriggerMessageRequest, TriggerMessageResponse},
+    },
+    types::{MessageTrigger, RegistrationStatus, TriggerMessageStatus},
+};
+
+use crate::{
+    harness::harness::get_cms_url,
+    state::{
+        reusable_states::{BootState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+fn reconnect_chain(min_ms: u64, tol_ms: u64) -> TestChain {
+    test_chain!(
+        TestChain::new(),
+        close_connection(),
+        await_disconnection(),
+        restore_connection(),
+        await_connection(get_cms_url(), false),
+        with_timing(min_ms, tol_ms),
+    )
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        // 2s backoff, jittered into [1, 2]s
+        merge(reconnect_chain(1000, 1200)),
+        // 4s backoff, jittered into [2, 4]s
+        merge(reconnect_chain(2000, 2200)),
+        call(TriggerMessageRequest {
+            connector_id: None,
+            requested_message: MessageTrigger::BootNotification
+        }),
+        await_ws_msg(TriggerMessageResponse {
+            status: TriggerMessageStatus::Accepted
+        }),
+        await_ws_msg(BootNotificationRequest {}),
+        respond_with_now(BootNotificationResponse {
+            current_time: Utc::now(),
+            interval: 10,
+            status: RegistrationStatus::Accepted
+        }),
+        // an accepted boot notification starts the backoff over
+        merge(reconnect_chain(1000, 1200)),
+    );
+
+    chain
+        .run(
+            20,
+            vec![
+                ("ReconnectBackoffInitialInterval", "2"),
+                ("ReconnectBackoffMaxInterval", "8"),
+            ],
+            None,
+        )
+        .await;
+}
+
+// refused attempts are retried with the same backoff as a dropped connection
+pub async fn run_failed_connects() {
+    let num_connectors = 2;
+
+    let chain = BootState::default(num_connectors)
+        .get_test_chain()
+        .operation(|t| t.ws_handle.refuse_connects(2));
+    // 1s after the drop, then two refused attempts backing off [1, 2]s and [2, 4]s
+    let chain = test_chain!(
+        chain,
+        close_connection(),
+        await_disconnection(),
+        restore_connection(),
+        await_connection(get_cms_url(), false),
+        with_timing(3980, 3040),
+    )
+    .check_connect_attempts(|attempts| assert_eq!(attempts.len(), 4));
+
+    chain
+        .run(
+            20,
+            vec![
+                ("ReconnectBackoffInitialInterval", "1"),
+                ("ReconnectBackoffMaxInterval", "8"),
+            ],
+            None,
+        )
+        .await;
+}
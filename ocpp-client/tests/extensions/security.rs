@@ -0,0 +1,192 @@
+use rocpp_client::v16::BasicAuth;
+use rocpp_core::v16::{
+    messages::{
+        change_configuration::{ChangeConfigurationRequest, ChangeConfigurationResponse},
+        get_configuration::{GetConfigurationRequest, GetConfigurationResponse},
+    },
+    types::{ConfigurationStatus, KeyValue},
+};
+
+use crate::{
+    harness::{
+        database::read_table,
+        harness::{get_ca_certificate, get_cms_url, CpHarness},
+    },
+    state::{
+        reusable_states::{BootState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+const AUTHORIZATION_KEY: &str = "0123456789abcdef0123";
+
+fn change_configuration_chain(key: &str, value: &str, status: ConfigurationStatus) -> TestChain {
+    test_chain!(
+        TestChain::new(),
+        call(ChangeConfigurationRequest {
+            key: key.to_string(),
+            value: value.to_string()
+        }),
+        await_ws_msg(ChangeConfigurationResponse { status: status }),
+    )
+}
+
+fn get_configuration_chain(key: &str, value: Option<&str>) -> TestChain {
+    test_chain!(
+        TestChain::new(),
+        call(GetConfigurationRequest {
+            key: Some(vec![key.to_string()])
+        }),
+        await_ws_msg(GetConfigurationResponse {
+            configuration_key: Some(vec![KeyValue {
+                key: key.to_string(),
+                readonly: false,
+                value: value.map(|t| t.to_string()),
+            }]),
+            unknown_key: None
+        }),
+    )
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        // basic auth needs a password first
+        merge(change_configuration_chain(
+            "SecurityProfile",
+            "1",
+            ConfigurationStatus::Rejected
+        )),
+        merge(change_configuration_chain(
+            "AuthorizationKey",
+            "too short",
+            ConfigurationStatus::Rejected
+        )),
+        merge(change_configuration_chain(
+            "AuthorizationKey",
+            AUTHORIZATION_KEY,
+            ConfigurationStatus::Accepted
+        )),
+        // write only
+        merge(get_configuration_chain("AuthorizationKey", None)),
+        merge(change_configuration_chain(
+            "SecurityProfile",
+            "1",
+            ConfigurationStatus::Accepted
+        )),
+        await_disconnection(),
+        await_connection(get_cms_url(), false),
+        merge(get_configuration_chain("SecurityProfile", Some("1"))),
+    )
+    .check_connect_attempts(|attempts| {
+        let last = attempts.last().unwrap();
+        assert_eq!(last.security_profile, 1);
+        assert_eq!(
+            last.basic_auth,
+            Some(BasicAuth {
+                username: get_cms_url(),
+                password: AUTHORIZATION_KEY.to_string(),
+            })
+        );
+        assert_eq!(last.ca_certificate, None);
+    })
+    .with_max_security_profile(1);
+
+    let chain = test_chain!(
+        chain,
+        merge(change_configuration_chain(
+            "SecurityProfile",
+            "2",
+            ConfigurationStatus::Accepted
+        )),
+        await_disconnection(),
+        // the server refuses profile 2, it is retried until the fallback timer
+        // reverts to profile 1
+        await_connection(get_cms_url(), false),
+        with_timing(2000, 500),
+        merge(get_configuration_chain("SecurityProfile", Some("1"))),
+        // downgrades are not allowed
+        merge(change_configuration_chain(
+            "SecurityProfile",
+            "0",
+            ConfigurationStatus::Rejected
+        )),
+    )
+    .check_connect_attempts(|attempts| {
+        let profiles = attempts
+            .iter()
+            .map(|t| t.security_profile)
+            .collect::<Vec<_>>();
+        let (last, refused) = profiles[2..].split_last().unwrap();
+        assert_eq!(profiles[..2], [0, 1]);
+        assert!(refused.len() >= 2 && refused.iter().all(|t| *t == 2));
+        assert_eq!(*last, 1);
+        assert_eq!(attempts[2].ca_certificate, Some(get_ca_certificate()));
+    });
+
+    chain
+        .run(20, vec![("SecurityProfileFallbackTimeout", "2")], None)
+        .await;
+}
+
+// an upgrade the server refuses still falls back after losing power before
+// the fallback timeout
+pub async fn run_reboot() {
+    let num_connectors = 2;
+    let overrides = vec![("SecurityProfileFallbackTimeout", "3")];
+    let db_dir = Some(std::env::temp_dir().join("ext_security_reboot"));
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        merge(change_configuration_chain(
+            "AuthorizationKey",
+            AUTHORIZATION_KEY,
+            ConfigurationStatus::Accepted
+        )),
+    )
+    .with_max_security_profile(1);
+    let chain = test_chain!(
+        chain,
+        merge(change_configuration_chain(
+            "SecurityProfile",
+            "2",
+            ConfigurationStatus::Accepted
+        )),
+        await_disconnection(),
+        cut_power(),
+        await_hard_reset(),
+    )
+    // the new charge point must not get to connect before the server refuses profile 2
+    .operation({
+        let (overrides, db_dir) = (overrides.clone(), db_dir.clone());
+        move |t| {
+            *t = CpHarness::new(15, overrides, db_dir, false);
+            t.ws_handle.set_max_security_profile(1);
+        }
+    });
+    let chain = test_chain!(
+        chain,
+        merge(BootState::default(num_connectors).get_test_chain()),
+        merge(get_configuration_chain("SecurityProfile", Some("0"))),
+    )
+    .check_connect_attempts(|attempts| {
+        let profiles = attempts
+            .iter()
+            .map(|t| t.security_profile)
+            .collect::<Vec<_>>();
+        assert_eq!(profiles.first(), Some(&2));
+        assert_eq!(profiles.last(), Some(&0));
+    })
+    .operation({
+        let dir = db_dir.clone().unwrap();
+        move |_| {
+            let rows = read_table(&dir, "security_profile");
+            assert!(!rows.contains_key("fallback"), "fallback left: {:?}", rows);
+        }
+    });
+
+    chain.run(20, overrides, db_dir).await;
+}
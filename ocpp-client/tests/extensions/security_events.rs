@@ -0,0 +1,190 @@
+use std::path::Path;
+
+use rocpp_core::v16::{
+    messages::{
+        change_configuration::{ChangeConfigurationRequest, ChangeConfigurationResponse},
+        extended_trigger_message::{ExtendedTriggerMessageRequest, ExtendedTriggerMessageResponse},
+        get_log::{GetLogRequest, GetLogResponse},
+        log_status_notification::{LogStatusNotificationRequest, LogStatusNotificationResponse},
+        reset::{ResetRequest, ResetResponse},
+        security_event_notification::{
+            SecurityEventNotificationRequest, SecurityEventNotificationResponse,
+        },
+    },
+    types::{
+        ConfigurationStatus, ExtendedMessageTrigger, LogParameters, LogStatus, LogType,
+        ResetStatus, ResetType, TriggerMessageStatus, UploadLogStatus,
+    },
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    harness::{
+        database::{read_table, write_table},
+        harness::get_cms_url,
+    },
+    state::{
+        reusable_states::{BootState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+fn security_event_chain(kind: &str) -> TestChain {
+    test_chain!(
+        TestChain::new(),
+        await_ws_msg(SecurityEventNotificationRequest {
+            kind: kind.to_string()
+        }),
+        respond(SecurityEventNotificationResponse {}),
+    )
+}
+
+fn get_log_chain(request_id: i32, location: &str, status: UploadLogStatus) -> TestChain {
+    test_chain!(
+        TestChain::new(),
+        call(GetLogRequest {
+            log: LogParameters {
+                remote_location: location.to_string(),
+                oldest_timestamp: None,
+                latest_timestamp: None,
+            },
+            log_type: LogType::SecurityLog,
+            request_id,
+            retries: None,
+            retry_interval: None,
+        }),
+        await_ws_msg(GetLogResponse {
+            status: LogStatus::Accepted,
+            filename: Some(format!("security_log_{}.log", request_id))
+        }),
+        await_ws_msg(LogStatusNotificationRequest {
+            status: UploadLogStatus::Uploading,
+            request_id: Some(request_id)
+        }),
+        respond(LogStatusNotificationResponse {}),
+        await_ws_msg(LogStatusNotificationRequest {
+            status: status,
+            request_id: Some(request_id)
+        }),
+        respond(LogStatusNotificationResponse {}),
+    )
+}
+
+// rewrites the log into the version 2 layout, unpadded keys and no counters
+fn downgrade(dir: &Path) {
+    let rows: Map<String, Value> = read_table(dir, "security_event")
+        .into_iter()
+        .filter(|(key, _)| key != "start" && key != "head")
+        .map(|(key, value)| match key.strip_prefix("event:") {
+            Some(index) => (format!("event:{}", index.parse::<u64>().unwrap()), value),
+            None => (key, value),
+        })
+        .collect();
+    assert!(rows.contains_key("event:0"));
+    write_table(dir, "security_event", &rows);
+    let mut schema = read_table(dir, "schema");
+    schema.insert("version".to_string(), json!("2"));
+    write_table(dir, "schema", &schema);
+}
+
+fn check_migrated(dir: &Path) {
+    let rows = read_table(dir, "security_event");
+    let indices: Vec<u64> = rows
+        .keys()
+        .filter_map(|key| key.strip_prefix("event:"))
+        .inspect(|index| assert_eq!(index.len(), 20, "unpadded key {}", index))
+        .map(|index| index.parse().unwrap())
+        .collect();
+    let counter = |key: &str| {
+        rows.get(key)
+            .and_then(|t| t.as_str())
+            .map(|t| t.to_string())
+    };
+    assert_eq!(
+        counter("start"),
+        indices.iter().min().map(|t| t.to_string())
+    );
+    assert_eq!(
+        counter("head"),
+        indices.iter().max().map(|t| (t + 1).to_string())
+    );
+    assert_eq!(counter("head"), counter("sent"));
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+    let overrides = vec![("SecurityEventNotificationEnabled", "true")];
+    let dir = std::env::temp_dir().join("ext_security_events");
+    let db_dir = Some(dir.clone());
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        // unanswered before the connection drops, so sent again after reconnecting
+        await_ws_msg(SecurityEventNotificationRequest {
+            kind: "StartupOfTheDevice".to_string()
+        }),
+        close_connection(),
+        await_disconnection(),
+        restore_connection(),
+        await_connection(get_cms_url(), false),
+        merge(security_event_chain("StartupOfTheDevice")),
+        merge(security_event_chain("SettingSystemTime")),
+        // not critical, only logged
+        call(ChangeConfigurationRequest {
+            key: "AuthorizationKey".to_string(),
+            value: "0123456789abcdef".to_string()
+        }),
+        await_ws_msg(ChangeConfigurationResponse {
+            status: ConfigurationStatus::Accepted
+        }),
+        merge(get_log_chain(
+            1,
+            "valid_location",
+            UploadLogStatus::Uploaded
+        )),
+        merge(get_log_chain(
+            2,
+            "invalid_location",
+            UploadLogStatus::UploadFailure
+        )),
+        call(ExtendedTriggerMessageRequest {
+            requested_message: ExtendedMessageTrigger::LogStatusNotification,
+            connector_id: None
+        }),
+        await_ws_msg(ExtendedTriggerMessageResponse {
+            status: TriggerMessageStatus::Accepted
+        }),
+        await_ws_msg(LogStatusNotificationRequest {
+            status: UploadLogStatus::Idle,
+            request_id: None
+        }),
+        respond(LogStatusNotificationResponse {}),
+        call(ResetRequest {
+            kind: ResetType::Hard
+        }),
+        await_ws_msg(ResetResponse {
+            status: ResetStatus::Accepted
+        }),
+        // unanswered before the reset, so it is kept across the restart
+        await_ws_msg(SecurityEventNotificationRequest {
+            kind: "ResetOrReboot".to_string()
+        }),
+        await_hard_reset(),
+    )
+    .operation({
+        let dir = dir.clone();
+        move |_| downgrade(&dir)
+    });
+    let chain = test_chain!(
+        chain,
+        spawn_new(15, overrides.clone(), db_dir.clone(), false),
+        merge(BootState::default(num_connectors).get_test_chain()),
+        merge(security_event_chain("ResetOrReboot")),
+        merge(security_event_chain("StartupOfTheDevice")),
+        merge(security_event_chain("SettingSystemTime")),
+    )
+    .operation(move |_| check_migrated(&dir));
+
+    chain.run(15, overrides, db_dir).await;
+}
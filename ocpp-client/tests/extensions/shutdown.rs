@@ -0,0 +1,141 @@
+use rocpp_client::v16::{ChargePointCommand, CommandResult, StateChange};
+use rocpp_core::v16::{
+    messages::{
+        data_transfer::{DataTransferRequest, DataTransferResponse},
+        remote_start_transaction::{RemoteStartTransactionRequest, RemoteStartTransactionResponse},
+    },
+    protocol_error::ProtocolError,
+    types::DataTransferStatus,
+};
+
+use crate::{
+    state::{
+        reusable_states::{BootState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+pub async fn run() {
+    let num_connectors = 2;
+    let connector_id = 1;
+    let id_tag = "1234".to_string();
+
+    let mut chain = BootState::default(num_connectors)
+        .get_test_chain()
+        .send_data_transfer(DataTransferRequest {
+            vendor_id: "rocpp".to_string(),
+            message_id: Some("status".to_string()),
+            data: None,
+        });
+    chain = test_chain!(
+        chain,
+        await_ws_msg(DataTransferRequest {
+            vendor_id: "rocpp".to_string(),
+            message_id: Some("status".to_string())
+        }),
+        // queued behind the data transfer, must never reach the central system
+        present_id_tag(connector_id, id_tag),
+    )
+    .send_command(ChargePointCommand::Shutdown);
+    chain = test_chain!(
+        chain,
+        // calls from the central system are refused once the shutdown is pending
+        call(DataTransferRequest {
+            vendor_id: "rocpp".to_string(),
+            message_id: Some("echo".to_string()),
+            data: None
+        }),
+        merge(
+            TestChain::new().await_call_error::<DataTransferResponse>(ProtocolError::InternalError)
+        ),
+        respond(DataTransferResponse {
+            status: DataTransferStatus::Accepted,
+            data: Some("ok".to_string())
+        }),
+        await_disconnection(),
+        await_hard_reset(),
+    )
+    .check_data_transfer_responses(vec![Some((
+        DataTransferStatus::Accepted,
+        Some("ok".to_string()),
+    ))])
+    .check_command_results(vec![(
+        ChargePointCommand::Shutdown,
+        CommandResult::Accepted,
+    )]);
+
+    chain.run(15, vec![], None).await;
+}
+
+// with the connection down there is no Disconnected to wait for, neither the
+// soft reset nor the shutdown may hang on it
+pub async fn run_offline() {
+    let num_connectors = 2;
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        close_connection(),
+        await_disconnection(),
+    )
+    .send_command(ChargePointCommand::SoftReset)
+    .send_command(ChargePointCommand::Shutdown);
+    let chain = test_chain!(chain, await_hard_reset(),).check_command_results(vec![
+        (ChargePointCommand::SoftReset, CommandResult::Accepted),
+        (ChargePointCommand::Shutdown, CommandResult::Accepted),
+    ]);
+
+    chain.run(15, vec![], None).await;
+}
+
+// a remote start during the shutdown must not start a transaction, while the
+// response to the call in flight still ends the shutdown
+pub async fn run_remote_start() {
+    let num_connectors = 2;
+
+    let chain = BootState::default(num_connectors)
+        .get_test_chain()
+        .send_data_transfer(DataTransferRequest {
+            vendor_id: "rocpp".to_string(),
+            message_id: Some("status".to_string()),
+            data: None,
+        });
+    let chain = test_chain!(
+        chain,
+        await_ws_msg(DataTransferRequest {
+            vendor_id: "rocpp".to_string(),
+            message_id: Some("status".to_string())
+        }),
+    )
+    .send_command(ChargePointCommand::Shutdown);
+    let chain = test_chain!(
+        chain,
+        call(RemoteStartTransactionRequest {
+            connector_id: Some(1),
+            id_tag: "1234".to_string(),
+            charging_profile: None
+        }),
+        merge(
+            TestChain::new()
+                .await_call_error::<RemoteStartTransactionResponse>(ProtocolError::InternalError)
+        ),
+        respond(DataTransferResponse {
+            status: DataTransferStatus::Accepted,
+            data: None
+        }),
+        await_disconnection(),
+        await_hard_reset(),
+    )
+    .operation(|t| {
+        let changes = t.state_changes.lock().unwrap();
+        assert!(
+            !changes
+                .iter()
+                .any(|(change, _)| matches!(change, StateChange::TransactionStarted { .. })),
+            "a transaction started during the shutdown: {:?}",
+            changes
+        );
+    });
+
+    chain.run(15, vec![], None).await;
+}
@@ -0,0 +1,143 @@
+use chrono::{Duration, Utc};
+use rocpp_core::v16::{
+    messages::{
+        install_certificate::{InstallCertificateRequest, InstallCertificateResponse},
+        signed_firmware_status_notification::{
+            SignedFirmwareStatusNotificationRequest, SignedFirmwareStatusNotificationResponse,
+        },
+        signed_update_firmware::{SignedUpdateFirmwareRequest, SignedUpdateFirmwareResponse},
+    },
+    types::{
+        CertificateStatus, CertificateUse, ChargePointStatus, Firmware, SignedFirmwareStatus,
+        UpdateFirmwareStatus,
+    },
+};
+
+use crate::{
+    harness::crypto::mock_certificate,
+    state::{
+        reusable_states::{get_all_connector_states, BootState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+fn signed_update_chain(
+    request_id: i32,
+    signing_certificate: String,
+    signature: &str,
+    install_in: Option<i64>,
+    status: UpdateFirmwareStatus,
+) -> TestChain {
+    test_chain!(
+        TestChain::new(),
+        call(SignedUpdateFirmwareRequest {
+            retries: None,
+            retry_interval: None,
+            request_id,
+            firmware: Firmware {
+                location: "download_success:install:success".to_string(),
+                retrieve_date_time: Utc::now(),
+                install_date_time: install_in.map(|t| Utc::now() + Duration::seconds(t)),
+                signing_certificate,
+                signature: signature.to_string(),
+            }
+        }),
+        await_ws_msg(SignedUpdateFirmwareResponse { status: status }),
+    )
+}
+
+fn status_chain(request_id: i32, statuses: Vec<SignedFirmwareStatus>) -> TestChain {
+    let mut res = TestChain::new();
+    for status in statuses {
+        res = test_chain!(
+            res,
+            await_ws_msg(SignedFirmwareStatusNotificationRequest {
+                status: status,
+                request_id: Some(request_id)
+            }),
+            respond(SignedFirmwareStatusNotificationResponse {}),
+        );
+    }
+    res
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        // no manufacturer root to check the signing certificate against yet
+        merge(signed_update_chain(
+            1,
+            mock_certificate("signing"),
+            "valid_signature",
+            None,
+            UpdateFirmwareStatus::InvalidCertificate
+        )),
+        call(InstallCertificateRequest {
+            certificate_type: CertificateUse::ManufacturerRootCertificate,
+            certificate: mock_certificate("manufacturer")
+        }),
+        await_ws_msg(InstallCertificateResponse {
+            status: CertificateStatus::Accepted
+        }),
+        merge(signed_update_chain(
+            2,
+            "not a certificate".to_string(),
+            "valid_signature",
+            None,
+            UpdateFirmwareStatus::InvalidCertificate
+        )),
+        merge(signed_update_chain(
+            3,
+            mock_certificate("signing"),
+            "invalid_signature",
+            None,
+            UpdateFirmwareStatus::Accepted
+        )),
+        merge(status_chain(
+            3,
+            vec![
+                SignedFirmwareStatus::Downloading,
+                SignedFirmwareStatus::Downloaded,
+                SignedFirmwareStatus::InvalidSignature,
+            ]
+        )),
+        merge(signed_update_chain(
+            4,
+            mock_certificate("signing"),
+            "valid_signature",
+            Some(3),
+            UpdateFirmwareStatus::Accepted
+        )),
+        merge(status_chain(
+            4,
+            vec![
+                SignedFirmwareStatus::Downloading,
+                SignedFirmwareStatus::Downloaded,
+                SignedFirmwareStatus::SignatureVerified,
+                SignedFirmwareStatus::InstallScheduled,
+            ]
+        )),
+        merge(get_all_connector_states(vec![
+            ChargePointStatus::Unavailable;
+            num_connectors
+        ])),
+        optional(1),
+        merge(status_chain(
+            4,
+            vec![
+                SignedFirmwareStatus::Installing,
+                SignedFirmwareStatus::InstallRebooting,
+            ]
+        )),
+        await_disconnection(),
+        merge(BootState::default(num_connectors).get_test_chain()),
+        pop(),
+        merge(status_chain(4, vec![SignedFirmwareStatus::Installed])),
+        any_order(num_connectors + 1)
+    );
+
+    chain.run(15, vec![], None).await;
+}
@@ -0,0 +1,90 @@
+use std::{fs, path::Path};
+
+use rocpp_client::v16::DataLoss;
+use rocpp_core::v16::types::ChargePointStatus;
+use serde_json::{json, Map};
+
+use crate::{
+    harness::database::{read_table, write_table},
+    harness::harness::CpHarness,
+    state::reusable_states::{BootState, ReusableState},
+    test_chain,
+};
+
+fn corrupt_storage(dir: &Path) {
+    let mut config = read_table(dir, "config");
+    config.insert("MeterValueSampleInterval".to_string(), json!("abc"));
+    write_table(dir, "config", &config);
+    let mut availability = Map::new();
+    availability.insert("0".to_string(), json!("\"Inoperative\""));
+    availability.insert("7".to_string(), json!("\"Inoperative\""));
+    write_table(dir, "availabilitytype", &availability);
+    let mut reservation = Map::new();
+    reservation.insert("5".to_string(), json!("{garbage"));
+    write_table(dir, "reservation", &reservation);
+    fs::write(dir.join("local_list.json"), "not a table").unwrap();
+}
+
+fn check_recovery(dir: &Path, t: &CpHarness) {
+    let losses = t.data_losses.lock().unwrap();
+    let expected = vec![
+        DataLoss::ConfigReset {
+            key: "MeterValueSampleInterval".to_string(),
+        },
+        DataLoss::Quarantined {
+            table: "availabilitytype".to_string(),
+            key: "7".to_string(),
+        },
+        DataLoss::Quarantined {
+            table: "reservation".to_string(),
+            key: "5".to_string(),
+        },
+        DataLoss::Unreadable {
+            table: "local_list".to_string(),
+        },
+    ];
+    assert_eq!(losses.len(), expected.len(), "{:?}", losses);
+    for loss in expected {
+        assert!(losses.contains(&loss), "{:?} not in {:?}", loss, losses);
+    }
+    assert_eq!(
+        read_table(dir, "config").get("MeterValueSampleInterval"),
+        Some(&json!("0"))
+    );
+    assert!(read_table(dir, "reservation").is_empty());
+    let quarantine = read_table(dir, "quarantine");
+    assert_eq!(quarantine.get("reservation:5"), Some(&json!("{garbage")));
+    assert_eq!(
+        quarantine.get("availabilitytype:7"),
+        Some(&json!("\"Inoperative\""))
+    );
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+    let dir = std::env::temp_dir().join("ext_storage");
+    let db_dir = Some(dir.clone());
+
+    let chain = test_chain!(
+        BootState::default(num_connectors).get_test_chain(),
+        cut_power(),
+        await_hard_reset(),
+    )
+    .operation({
+        let dir = dir.clone();
+        move |_| corrupt_storage(&dir)
+    });
+    // bad rows are set aside and the charger boots with what is left
+    let chain = test_chain!(
+        chain,
+        spawn_new(15, vec![], db_dir.clone(), false),
+        merge(
+            BootState::default(num_connectors)
+                .with_state(1, ChargePointStatus::Unavailable)
+                .get_test_chain()
+        ),
+    )
+    .operation(move |t| check_recovery(&dir, t));
+
+    chain.run(15, vec![], db_dir).await;
+}
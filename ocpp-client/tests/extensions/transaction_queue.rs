@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use rocpp_core::v16::{
+    messages::{
+        boot_notification::BootNotificationRequest,
+        stop_transaction::{StopTransactionRequest, StopTransactionResponse},
+    },
+    types::Reason,
+};
+use serde_json::json;
+
+use crate::{
+    harness::database::{read_table, write_table},
+    state::{
+        reusable_states::{BootState, ChargingState, ConnectionState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+// rewrites the queue into the version 1 layout, which only had the event rows.
+// the extra stop meter values span several scan pages
+fn downgrade(dir: &Path) {
+    let mut rows = read_table(dir, "transaction");
+    rows.retain(|key, _| {
+        key != "head"
+            && key != "tail"
+            && !key.starts_with("stopped:")
+            && !key.starts_with("meter_count:")
+    });
+    assert!(rows.keys().any(|key| key.starts_with("event:")));
+    let transactions: Vec<String> = rows
+        .keys()
+        .filter_map(|key| key.strip_prefix("transaction_connector_map:"))
+        .map(|t| t.to_string())
+        .collect();
+    for local_transaction_id in transactions {
+        for index in 0..40 {
+            rows.insert(format!("meter:{local_transaction_id}:{index}"), json!("{}"));
+        }
+    }
+    write_table(dir, "transaction", &rows);
+    let mut schema = read_table(dir, "schema");
+    schema.insert("version".to_string(), json!("1"));
+    write_table(dir, "schema", &schema);
+}
+
+fn check_drained(dir: &Path) {
+    let rows = read_table(dir, "transaction");
+    let left: Vec<&String> = rows
+        .keys()
+        .filter(|key| {
+            key.starts_with("event:")
+                || key.starts_with("meter")
+                || key.starts_with("stopped:")
+                || key.starts_with("transaction_")
+        })
+        .collect();
+    assert!(left.is_empty(), "rows left in the queue: {:?}", left);
+    assert_eq!(rows.get("head"), rows.get("tail"));
+}
+
+pub async fn run() {
+    let num_connectors = 2;
+    let connector_id = 1;
+    let id_tag = "1234".to_string();
+    let dir = std::env::temp_dir().join("ext_transaction_queue");
+    let db_dir = Some(dir.clone());
+
+    // the stop for the interrupted transaction is queued on a boot that never
+    // gets a BootNotification response
+    let chain = test_chain!(
+        ChargingState::default(num_connectors, connector_id, 1, id_tag).get_test_chain(),
+        cut_power(),
+        await_hard_reset(),
+        spawn_new(15, vec![], db_dir.clone(), false),
+        merge(ConnectionState::default().get_test_chain()),
+        merge(
+            TestChain::new()
+                .await_ws_msg::<BootNotificationRequest>()
+                .done()
+        ),
+        cut_power(),
+        await_hard_reset(),
+    )
+    .operation({
+        let dir = dir.clone();
+        move |_| downgrade(&dir)
+    });
+    let chain = test_chain!(
+        chain,
+        spawn_new(15, vec![], db_dir.clone(), false),
+        merge(BootState::default(num_connectors).get_test_chain()),
+        pop(),
+        merge(
+            TestChain::new()
+                .await_ws_msg::<StopTransactionRequest>()
+                .check_eq(&1, |t| &t.transaction_id)
+                .check_eq(&Some(Reason::PowerLoss), |t| &t.reason)
+                .done()
+                .respond(StopTransactionResponse { id_tag_info: None })
+        ),
+        any_order(num_connectors + 1),
+    )
+    .operation(move |_| check_drained(&dir));
+
+    chain.run(15, vec![], db_dir).await;
+}
@@ -0,0 +1,173 @@
+use chrono::{Duration, Timelike, Utc};
+use rocpp_client::v16::StateChange;
+use rocpp_core::v16::{
+    messages::{
+        boot_notification::{BootNotificationRequest, BootNotificationResponse},
+        firmware_status_notification::{
+            FirmwareStatusNotificationRequest, FirmwareStatusNotificationResponse,
+        },
+        meter_values::{MeterValuesRequest, MeterValuesResponse},
+        reserve_now::{ReserveNowRequest, ReserveNowResponse},
+        signed_update_firmware::{SignedUpdateFirmwareRequest, SignedUpdateFirmwareResponse},
+        status_notification::{StatusNotificationRequest, StatusNotificationResponse},
+        update_firmware::{UpdateFirmwareRequest, UpdateFirmwareResponse},
+    },
+    protocol_error::ProtocolError,
+    types::{ChargePointStatus, Firmware, FirmwareStatus, RegistrationStatus, ReservationStatus},
+};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    harness::crypto::mock_certificate,
+    state::{
+        reusable_states::{get_all_connector_states, ConnectionState, ReusableState},
+        step::TestChain,
+    },
+    test_chain,
+};
+
+fn reserve_now() -> ReserveNowRequest {
+    ReserveNowRequest {
+        connector_id: 1,
+        expiry_date: Utc::now() + Duration::seconds(60),
+        id_tag: "1234".to_string(),
+        parent_id_tag: None,
+        reservation_id: 1,
+    }
+}
+
+fn update_firmware() -> UpdateFirmwareRequest {
+    UpdateFirmwareRequest {
+        location: "download_success:install:success".to_string(),
+        retries: None,
+        retrieve_date: Utc::now() + Duration::seconds(60),
+        retry_interval: None,
+    }
+}
+
+fn signed_update_firmware() -> SignedUpdateFirmwareRequest {
+    SignedUpdateFirmwareRequest {
+        retries: None,
+        retry_interval: None,
+        request_id: 1,
+        firmware: Firmware {
+            location: "download_success:install:success".to_string(),
+            retrieve_date_time: Utc::now() + Duration::seconds(60),
+            install_date_time: None,
+            signing_certificate: mock_certificate("signing"),
+            signature: "valid_signature".to_string(),
+        },
+    }
+}
+
+fn refused<T: DeserializeOwned + 'static>() -> TestChain {
+    TestChain::new().await_call_error::<T>(ProtocolError::SecurityError)
+}
+
+// the first BootNotification fails, so the charge point runs without a clock
+// until the retry two seconds later is answered
+fn failed_boot() -> TestChain {
+    test_chain!(
+        ConnectionState::default().get_test_chain(),
+        merge(
+            TestChain::new()
+                .await_ws_msg::<BootNotificationRequest>()
+                .done()
+        ),
+        respond_error(ProtocolError::InternalError),
+    )
+}
+
+fn accepted_boot(num_connectors: usize) -> TestChain {
+    TestChain::new()
+        .await_ws_msg::<BootNotificationRequest>()
+        .done()
+        .respond_with_now(BootNotificationResponse {
+            current_time: Utc::now(),
+            interval: 1000,
+            status: RegistrationStatus::Accepted,
+        })
+        .merge(get_all_connector_states(vec![
+            ChargePointStatus::Available;
+            num_connectors
+        ]))
+}
+
+// calls that need the time are refused before the first BootNotification
+// response and nothing is scheduled from them
+pub async fn run() {
+    let num_connectors = 2;
+
+    let chain = test_chain!(
+        failed_boot(),
+        call(reserve_now()),
+        merge(refused::<ReserveNowResponse>()),
+        call(update_firmware()),
+        merge(refused::<UpdateFirmwareResponse>()),
+        call(signed_update_firmware()),
+        merge(refused::<SignedUpdateFirmwareResponse>()),
+        merge(accepted_boot(num_connectors)),
+    )
+    .operation(|t| {
+        let changes = t.state_changes.lock().unwrap();
+        assert!(
+            !changes
+                .iter()
+                .any(|(change, _)| matches!(change, StateChange::FirmwareStatus(_))),
+            "firmware moved before the time was known: {:?}",
+            changes
+        );
+    });
+    // once the time is known the same requests go through
+    let chain = test_chain!(
+        chain,
+        call(reserve_now()),
+        await_ws_msg(ReserveNowResponse {
+            status: ReservationStatus::Accepted
+        }),
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: 1,
+            status: ChargePointStatus::Reserved
+        }),
+        respond(StatusNotificationResponse {}),
+        call(UpdateFirmwareRequest {
+            retrieve_date: Utc::now(),
+            ..update_firmware()
+        }),
+        await_ws_msg(UpdateFirmwareResponse {}),
+        await_ws_msg(FirmwareStatusNotificationRequest {
+            status: FirmwareStatus::Downloading
+        }),
+        respond(FirmwareStatusNotificationResponse {}),
+    );
+
+    chain.run(15, vec![], None).await;
+}
+
+// clock aligned samples only start with the time from the BootNotification
+// response, none are taken while the clock is unknown
+pub async fn run_aligned_meter() {
+    let num_connectors = 2;
+    let overrides = vec![("ClockAlignedDataInterval", "2")];
+    // the harness answers the boot with the time the chain was built at
+    let synced_at = Utc::now();
+
+    let mut chain = test_chain!(failed_boot(), merge(accepted_boot(num_connectors)),);
+    for connector_id in 1..=num_connectors {
+        chain = chain.merge(
+            TestChain::new()
+                .await_ws_msg::<MeterValuesRequest>()
+                .check_eq(&connector_id, |t| &t.connector_id)
+                .check(move |t| {
+                    let timestamp = t.meter_value[0].timestamp;
+                    (timestamp < synced_at || timestamp.second() % 2 != 0).then(|| {
+                        format!("sample at {} is not aligned after {}", timestamp, synced_at)
+                    })
+                })
+                .done()
+                .respond(MeterValuesResponse {}),
+        );
+    }
+
+    chain.run(15, overrides, None).await;
+}
@@ -0,0 +1,117 @@
+use chrono::Utc;
+use rocpp_core::v16::{
+    messages::{
+        authorize::{AuthorizeRequest, AuthorizeResponse},
+        change_configuration::{ChangeConfigurationRequest, ChangeConfigurationResponse},
+        heart_beat::{HeartbeatRequest, HeartbeatResponse},
+        status_notification::{StatusNotificationRequest, StatusNotificationResponse},
+    },
+    types::{AuthorizationStatus, ChargePointStatus, ConfigurationStatus, IdTagInfo},
+};
+
+use crate::{
+    harness::harness::get_cms_url,
+    state::reusable_states::{BootState, ReusableState},
+    test_chain,
+};
+
+pub async fn run() {
+    let num_connectors = 2;
+    let connector_id = 1;
+    let id_tag = "1234".to_string();
+
+    let chain = test_chain!(
+        BootState::default(num_connectors)
+            .with_interval(2)
+            .get_test_chain(),
+        present_id_tag(connector_id, id_tag.clone()),
+        await_ws_msg(AuthorizeRequest {
+            id_tag: id_tag.clone()
+        }),
+        respond(AuthorizeResponse {
+            id_tag_info: IdTagInfo {
+                expiry_date: None,
+                parent_id_tag: None,
+                status: AuthorizationStatus::Accepted
+            }
+        }),
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: connector_id,
+            status: ChargePointStatus::Preparing
+        }),
+        respond(StatusNotificationResponse {}),
+        // pongs keep coming back, so no heartbeat goes out while waiting for the plug
+        await_ws_msg(StatusNotificationRequest {
+            connector_id: connector_id,
+            status: ChargePointStatus::Available
+        }),
+        with_timing(4500, 1500),
+        respond(StatusNotificationResponse {}),
+    )
+    .operation(|t| t.ws_handle.set_answers_pings(false));
+    // once the pongs are older than the heartbeat interval heartbeats resume, and
+    // the unanswered ping closes the connection after the call timeout
+    let chain = test_chain!(
+        chain,
+        await_ws_msg(HeartbeatRequest {}),
+        with_timing(2000, 2200),
+        respond_with_now(HeartbeatResponse {
+            current_time: Utc::now()
+        }),
+        // keeps a second heartbeat out of the way of the disconnection
+        call(ChangeConfigurationRequest {
+            key: "HeartbeatInterval".into(),
+            value: "60".into()
+        }),
+        await_ws_msg(ChangeConfigurationResponse {
+            status: ConfigurationStatus::Accepted
+        }),
+        await_disconnection(),
+        with_timing(1000, 3200),
+    )
+    .operation(|t| t.ws_handle.set_answers_pings(true));
+    // the charge point closed the connection itself, it reconnects right away
+    let chain = test_chain!(chain, await_connection(get_cms_url(), false),);
+
+    chain
+        .run(
+            20,
+            vec![("WebSocketPingInterval", "1"), ("ConnectionTimeOut", "5")],
+            None,
+        )
+        .await;
+}
+
+// pongs alone never resync the clock, a heartbeat still goes out once the last
+// sync is older than HeartbeatMaxSkipInterval
+pub async fn run_clock_sync() {
+    let num_connectors = 2;
+
+    let chain = test_chain!(
+        BootState::default(num_connectors)
+            .with_interval(2)
+            .get_test_chain(),
+        // skipped 2s after the boot response, sent once 3s have passed
+        await_ws_msg(HeartbeatRequest {}),
+        with_timing(4000, 1000),
+        respond_with_now(HeartbeatResponse {
+            current_time: Utc::now()
+        }),
+        await_ws_msg(HeartbeatRequest {}),
+        with_timing(4000, 1000),
+        respond_with_now(HeartbeatResponse {
+            current_time: Utc::now()
+        }),
+    );
+
+    chain
+        .run(
+            20,
+            vec![
+                ("WebSocketPingInterval", "1"),
+                ("HeartbeatMaxSkipInterval", "3"),
+            ],
+            None,
+        )
+        .await;
+}
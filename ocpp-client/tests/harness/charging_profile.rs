@@ -0,0 +1,88 @@
+use chrono::Utc;
+use rocpp_core::v16::types::{
+    ChargingProfile, ChargingProfileKindType, ChargingProfilePurposeType, ChargingRateUnitType,
+    ChargingSchedule, ChargingSchedulePeriod, RecurrencyKindType,
+};
+
+// an Absolute profile in A starting now, without a duration or periods
+pub struct ChargingProfileBuilder {
+    profile: ChargingProfile,
+}
+
+impl ChargingProfileBuilder {
+    pub fn new(charging_profile_id: i32, purpose: ChargingProfilePurposeType) -> Self {
+        Self {
+            profile: ChargingProfile {
+                charging_profile_id,
+                transaction_id: None,
+                stack_level: 0,
+                charging_profile_purpose: purpose,
+                charging_profile_kind: ChargingProfileKindType::Absolute,
+                recurrency_kind: None,
+                valid_from: None,
+                valid_to: None,
+                charging_schedule: ChargingSchedule {
+                    duration: None,
+                    start_schedule: Some(Utc::now()),
+                    charging_rate_unit: ChargingRateUnitType::A,
+                    charging_schedule_period: Vec::new(),
+                    min_charging_rate: None,
+                },
+            },
+        }
+    }
+    pub fn transaction_id(mut self, transaction_id: i32) -> Self {
+        self.profile.transaction_id = Some(transaction_id);
+        self
+    }
+    pub fn stack_level(mut self, stack_level: u32) -> Self {
+        self.profile.stack_level = stack_level;
+        self
+    }
+    // relative schedules start with the transaction, so they carry no start
+    pub fn relative(mut self) -> Self {
+        self.profile.charging_profile_kind = ChargingProfileKindType::Relative;
+        self.profile.charging_schedule.start_schedule = None;
+        self
+    }
+    pub fn recurring(mut self, recurrency_kind: RecurrencyKindType) -> Self {
+        self.profile.charging_profile_kind = ChargingProfileKindType::Recurring;
+        self.profile.recurrency_kind = Some(recurrency_kind);
+        self
+    }
+    pub fn duration(mut self, duration: i32) -> Self {
+        self.profile.charging_schedule.duration = Some(duration);
+        self
+    }
+    pub fn unit(mut self, charging_rate_unit: ChargingRateUnitType) -> Self {
+        self.profile.charging_schedule.charging_rate_unit = charging_rate_unit;
+        self
+    }
+    // (start_period, limit) pairs
+    pub fn periods(mut self, periods: Vec<(i32, f32)>) -> Self {
+        self.profile.charging_schedule.charging_schedule_period = periods
+            .into_iter()
+            .map(|(start_period, limit)| ChargingSchedulePeriod {
+                start_period,
+                limit,
+                number_phases: None,
+            })
+            .collect();
+        self
+    }
+    // applies to the periods set so far
+    pub fn number_phases(mut self, number_phases: i32) -> Self {
+        for period in self
+            .profile
+            .charging_schedule
+            .charging_schedule_period
+            .iter_mut()
+        {
+            period.number_phases = Some(number_phases);
+        }
+        self
+    }
+    pub fn build(self) -> ChargingProfile {
+        self.profile
+    }
+}
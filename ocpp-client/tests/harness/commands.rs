@@ -0,0 +1,43 @@
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use flume::{r#async::RecvFut, unbounded, Sender};
+use futures::FutureExt;
+use rocpp_client::v16::{ChargePointCommand, CommandResult, Commands};
+
+pub type CommandResults = Arc<Mutex<Vec<(ChargePointCommand, CommandResult)>>>;
+
+pub struct MockCommands {
+    command_rx_fut: RecvFut<'static, ChargePointCommand>,
+    results: CommandResults,
+}
+
+impl MockCommands {
+    pub fn new() -> (Self, Sender<ChargePointCommand>, CommandResults) {
+        let (command_tx, command_rx) = unbounded();
+        let results = CommandResults::default();
+        (
+            Self {
+                command_rx_fut: command_rx.into_recv_async(),
+                results: results.clone(),
+            },
+            command_tx,
+            results,
+        )
+    }
+}
+
+impl Commands for MockCommands {
+    async fn command_result(&mut self, command: ChargePointCommand, result: CommandResult) {
+        log::info!("command: {:?}, result: {:?}", command, result);
+        self.results.lock().unwrap().push((command, result));
+    }
+    fn poll_command(&mut self, cx: &mut Context<'_>) -> Poll<ChargePointCommand> {
+        match self.command_rx_fut.poll_unpin(cx) {
+            Poll::Ready(t) => Poll::Ready(t.unwrap()),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use rocpp_client::v16::{ClientCertificate, Crypto};
+use rocpp_core::v16::types::{CertificateHashData, HashAlgorithm};
+
+const BEGIN: &str = "-----BEGIN CERTIFICATE-----\n";
+const END: &str = "\n-----END CERTIFICATE-----";
+
+// mock certificates carry only their serial number
+pub fn mock_certificate(serial_number: &str) -> String {
+    format!("{}{}{}", BEGIN, serial_number, END)
+}
+
+pub fn mock_hash_data(serial_number: &str) -> CertificateHashData {
+    CertificateHashData {
+        hash_algorithm: HashAlgorithm::Sha256,
+        issuer_name_hash: "mock-issuer-name".to_string(),
+        issuer_key_hash: "mock-issuer-key".to_string(),
+        serial_number: serial_number.to_string(),
+    }
+}
+
+pub fn mock_csr(organization: &str, common_name: &str) -> String {
+    format!("CSR:{}:{}", organization, common_name)
+}
+
+pub const MOCK_KEY_HANDLE: &str = "mock key";
+
+pub struct MockCrypto {
+    has_key: bool,
+}
+
+impl MockCrypto {
+    pub fn new() -> Self {
+        Self { has_key: false }
+    }
+}
+
+impl Crypto for MockCrypto {
+    async fn generate_csr(&mut self, organization: String, common_name: String) -> Option<String> {
+        self.has_key = true;
+        Some(mock_csr(&organization, &common_name))
+    }
+    async fn certificate_signed(&mut self, certificate_chain: String) -> Option<ClientCertificate> {
+        if !self.has_key || !certificate_chain.starts_with(BEGIN) {
+            return None;
+        }
+        Some(ClientCertificate {
+            certificate: certificate_chain,
+            key_handle: MOCK_KEY_HANDLE.to_string(),
+        })
+    }
+    async fn certificate_hash_data(&mut self, certificate: String) -> Option<CertificateHashData> {
+        let serial_number = certificate.strip_prefix(BEGIN)?.strip_suffix(END)?;
+        Some(mock_hash_data(serial_number))
+    }
+    // any mock certificate chains up to any installed root, except the ones
+    // with an untrusted serial number
+    async fn verify_certificate(
+        &mut self,
+        certificate: String,
+        trust_anchors: Vec<String>,
+    ) -> bool {
+        !trust_anchors.is_empty()
+            && certificate.starts_with(BEGIN)
+            && certificate.ends_with(END)
+            && !certificate.contains("untrusted")
+    }
+}
@@ -0,0 +1,65 @@
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use flume::{r#async::RecvFut, unbounded, Sender};
+use futures::FutureExt;
+use rocpp_client::v16::DataTransfer;
+use rocpp_core::v16::{
+    messages::data_transfer::{DataTransferRequest, DataTransferResponse},
+    types::DataTransferStatus,
+};
+
+pub type DataTransferResponses = Arc<Mutex<Vec<Option<DataTransferResponse>>>>;
+
+pub struct MockDataTransfer {
+    req_rx_fut: RecvFut<'static, DataTransferRequest>,
+    responses: DataTransferResponses,
+}
+
+impl MockDataTransfer {
+    pub fn new() -> (Self, Sender<DataTransferRequest>, DataTransferResponses) {
+        let (req_tx, req_rx) = unbounded();
+        let responses = DataTransferResponses::default();
+        (
+            Self {
+                req_rx_fut: req_rx.into_recv_async(),
+                responses: responses.clone(),
+            },
+            req_tx,
+            responses,
+        )
+    }
+}
+
+impl DataTransfer for MockDataTransfer {
+    async fn data_transfer(&mut self, req: DataTransferRequest) -> DataTransferResponse {
+        if req.vendor_id != "rocpp" {
+            return DataTransferResponse {
+                status: DataTransferStatus::UnknownVendorId,
+                data: None,
+            };
+        }
+        match req.message_id.as_deref() {
+            Some("echo") => DataTransferResponse {
+                status: DataTransferStatus::Accepted,
+                data: req.data,
+            },
+            _ => DataTransferResponse {
+                status: DataTransferStatus::UnknownMessageId,
+                data: None,
+            },
+        }
+    }
+    async fn data_transfer_response(&mut self, res: Option<DataTransferResponse>) {
+        log::info!("data transfer response: {:?}", res);
+        self.responses.lock().unwrap().push(res);
+    }
+    fn poll_data_transfer(&mut self, cx: &mut Context<'_>) -> Poll<DataTransferRequest> {
+        match self.req_rx_fut.poll_unpin(cx) {
+            Poll::Ready(t) => Poll::Ready(t.unwrap()),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -1,23 +1,55 @@
-use std::{collections::HashMap, fs, path::PathBuf};
-
-use rocpp_client::v16::KeyValueStore;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use rocpp_client::v16::{DataLoss, DbError, KeyValueStore};
+use serde_json::{Map, Value};
+
+pub type DataLosses = Arc<Mutex<Vec<DataLoss>>>;
+
+fn scan<'a>(
+    tbl: &'a HashMap<String, String>,
+    prefix: &str,
+    start_after: Option<&str>,
+    limit: usize,
+) -> Vec<(&'a str, &'a str)> {
+    let mut rows: Vec<_> = tbl
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .filter(|(k, _)| k.starts_with(prefix) && start_after.is_none_or(|s| *k > s))
+        .collect();
+    rows.sort();
+    rows.truncate(limit);
+    rows
+}
 
 pub struct MockDatabase {
     tables: HashMap<String, HashMap<String, String>>,
+    data_losses: DataLosses,
 }
 
 impl MockDatabase {
-    pub fn new() -> Self {
+    pub fn new(data_losses: DataLosses) -> Self {
         Self {
             tables: HashMap::new(),
+            data_losses,
         }
     }
 }
 
 impl KeyValueStore for MockDatabase {
-    async fn db_init(&mut self) {}
+    async fn db_init(&mut self) -> Result<(), DbError> {
+        Ok(())
+    }
 
-    async fn db_transaction(&mut self, table: &str, ops: Vec<(&str, Option<&str>)>) {
+    async fn db_transaction(
+        &mut self,
+        table: &str,
+        ops: Vec<(&str, Option<&str>)>,
+    ) -> Result<(), DbError> {
         let tbl = self.tables.entry(table.to_string()).or_default();
         for (key, value) in ops {
             if let Some(value) = value {
@@ -26,55 +58,97 @@ impl KeyValueStore for MockDatabase {
                 tbl.remove(key);
             }
         }
+        Ok(())
     }
 
-    async fn db_get(&mut self, table: &str, key: &str) -> Option<&str> {
-        self.tables
+    async fn db_get(&mut self, table: &str, key: &str) -> Result<Option<&str>, DbError> {
+        Ok(self
+            .tables
             .get(table)
-            .and_then(|tbl| tbl.get(key).map(|t| t.as_str()))
+            .and_then(|tbl| tbl.get(key).map(|t| t.as_str())))
     }
 
-    async fn db_get_all(&mut self, table: &str) -> Vec<(&str, &str)> {
-        self.tables
+    async fn db_get_all(&mut self, table: &str) -> Result<Vec<(&str, &str)>, DbError> {
+        Ok(self
+            .tables
             .get(table)
             .map(|tbl| tbl.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect())
-            .unwrap_or_default()
+            .unwrap_or_default())
     }
 
-    async fn db_count_keys(&mut self, table: &str) -> usize {
-        self.tables.get(table).map(|t| t.len()).unwrap_or(0)
+    async fn db_scan(
+        &mut self,
+        table: &str,
+        prefix: &str,
+        start_after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<(&str, &str)>, DbError> {
+        let Some(tbl) = self.tables.get(table) else {
+            return Ok(Vec::new());
+        };
+        Ok(scan(tbl, prefix, start_after, limit))
     }
 
-    async fn db_delete_table(&mut self, table: &str) {
+    async fn db_count_keys(&mut self, table: &str) -> Result<usize, DbError> {
+        Ok(self.tables.get(table).map(|t| t.len()).unwrap_or(0))
+    }
+
+    async fn db_delete_table(&mut self, table: &str) -> Result<(), DbError> {
         self.tables.remove(table);
+        Ok(())
+    }
+
+    async fn db_delete_all(&mut self) -> Result<(), DbError> {
+        Ok(())
     }
 
-    async fn db_delete_all(&mut self) {}
+    async fn db_data_lost(&mut self, loss: DataLoss) {
+        self.data_losses.lock().unwrap().push(loss);
+    }
+}
+
+// direct access to the tables FileDatabase keeps in dir, for tests that inspect
+// or tamper with the stored layout
+pub fn read_table(dir: &Path, table: &str) -> Map<String, Value> {
+    fs::read_to_string(dir.join(format!("{table}.json")))
+        .ok()
+        .and_then(|t| serde_json::from_str(&t).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_table(dir: &Path, table: &str, rows: &Map<String, Value>) {
+    fs::write(
+        dir.join(format!("{table}.json")),
+        serde_json::to_string(rows).unwrap(),
+    )
+    .unwrap();
 }
 
 pub struct FileDatabase {
     dir: PathBuf,
     map: HashMap<String, String>,
+    data_losses: DataLosses,
 }
 
 impl FileDatabase {
-    pub fn new(dir: PathBuf) -> Self {
+    pub fn new(dir: PathBuf, data_losses: DataLosses) -> Self {
         fs::create_dir_all(&dir).unwrap();
         Self {
             dir,
             map: HashMap::new(),
+            data_losses,
         }
     }
 
-    fn load_table(&mut self, table: &str) -> &mut HashMap<String, String> {
+    fn load_table(&mut self, table: &str) -> Result<&mut HashMap<String, String>, DbError> {
         let path = self.dir.join(format!("{table}.json"));
         let fresh_map: HashMap<String, String> = if let Ok(data) = std::fs::read_to_string(&path) {
-            serde_json::from_str(&data).unwrap_or_default()
+            serde_json::from_str(&data).map_err(|_| DbError::Corrupted)?
         } else {
             HashMap::new()
         };
         self.map = fresh_map;
-        &mut self.map
+        Ok(&mut self.map)
     }
 
     fn save_table(&self, table: &str) {
@@ -85,10 +159,16 @@ impl FileDatabase {
 }
 
 impl KeyValueStore for FileDatabase {
-    async fn db_init(&mut self) {}
+    async fn db_init(&mut self) -> Result<(), DbError> {
+        Ok(())
+    }
 
-    async fn db_transaction(&mut self, table: &str, ops: Vec<(&str, Option<&str>)>) {
-        let map = self.load_table(table);
+    async fn db_transaction(
+        &mut self,
+        table: &str,
+        ops: Vec<(&str, Option<&str>)>,
+    ) -> Result<(), DbError> {
+        let map = self.load_table(table)?;
         for (key, value) in ops {
             if let Some(value) = value {
                 map.insert(key.to_string(), value.to_string());
@@ -97,27 +177,40 @@ impl KeyValueStore for FileDatabase {
             }
         }
         self.save_table(table);
+        Ok(())
     }
 
-    async fn db_get(&mut self, table: &str, key: &str) -> Option<&str> {
-        self.load_table(table).get(key).map(|t| t.as_str())
+    async fn db_get(&mut self, table: &str, key: &str) -> Result<Option<&str>, DbError> {
+        Ok(self.load_table(table)?.get(key).map(|t| t.as_str()))
     }
 
-    async fn db_get_all(&mut self, table: &str) -> Vec<(&str, &str)> {
-        self.load_table(table)
+    async fn db_get_all(&mut self, table: &str) -> Result<Vec<(&str, &str)>, DbError> {
+        Ok(self
+            .load_table(table)?
             .iter()
             .map(|(k, v)| (k.as_str(), v.as_str()))
-            .collect()
+            .collect())
+    }
+
+    async fn db_scan(
+        &mut self,
+        table: &str,
+        prefix: &str,
+        start_after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<(&str, &str)>, DbError> {
+        Ok(scan(self.load_table(table)?, prefix, start_after, limit))
     }
 
-    async fn db_count_keys(&mut self, table: &str) -> usize {
-        self.load_table(table).len()
+    async fn db_count_keys(&mut self, table: &str) -> Result<usize, DbError> {
+        Ok(self.load_table(table)?.len())
     }
-    async fn db_delete_table(&mut self, table: &str) {
+    async fn db_delete_table(&mut self, table: &str) -> Result<(), DbError> {
         let path = self.dir.join(format!("{table}.json"));
         let _ = fs::remove_file(path);
+        Ok(())
     }
-    async fn db_delete_all(&mut self) {
+    async fn db_delete_all(&mut self) -> Result<(), DbError> {
         if let Ok(entries) = std::fs::read_dir(&self.dir) {
             for entry in entries.flatten() {
                 let path = entry.path();
@@ -126,5 +219,9 @@ impl KeyValueStore for FileDatabase {
                 }
             }
         }
+        Ok(())
+    }
+    async fn db_data_lost(&mut self, loss: DataLoss) {
+        self.data_losses.lock().unwrap().push(loss);
     }
 }
@@ -37,10 +37,34 @@ impl Diagnostics for MockDiagnostics {
             file_name,
             timeout
         );
-        let res = location == format!("valid_location");
-        let res = res
-            .then(|| DiagnosticsResponse::Success)
-            .unwrap_or(DiagnosticsResponse::Failed);
+        let res = location == "valid_location";
+        let res = if res {
+            DiagnosticsResponse::Success
+        } else {
+            DiagnosticsResponse::Failed
+        };
+        self.res = Some(res)
+    }
+    async fn log_upload(
+        &mut self,
+        location: String,
+        file_name: String,
+        content: String,
+        timeout: u64,
+    ) {
+        log::info!(
+            "uploading log at location: {}, file_name: {}, timeout: {}\n{}",
+            location,
+            file_name,
+            timeout,
+            content
+        );
+        self.file_name = Some(file_name);
+        let res = if location == "valid_location" {
+            DiagnosticsResponse::Success
+        } else {
+            DiagnosticsResponse::Failed
+        };
         self.res = Some(res)
     }
     fn poll_diagnostics_upload(&mut self, _cx: &mut Context<'_>) -> Poll<DiagnosticsResponse> {
@@ -6,6 +6,7 @@ pub struct MockFirmware {
     download_res: Option<bool>,
     is_downloaded: Option<bool>,
     install_res: Option<bool>,
+    verify_res: Option<bool>,
 }
 
 impl MockFirmware {
@@ -14,6 +15,7 @@ impl MockFirmware {
             download_res: None,
             is_downloaded: None,
             install_res: None,
+            verify_res: None,
         }
     }
 }
@@ -21,10 +23,10 @@ impl MockFirmware {
 impl Firmware for MockFirmware {
     async fn firmware_download(&mut self, location: String) {
         log::info!("firmware download location: {}", location);
-        let download = if location == format!("download_success:install:success") {
+        let download = if location == "download_success:install:success" {
             self.is_downloaded = Some(true);
             true
-        } else if location == format!("download_success:install:fail") {
+        } else if location == "download_success:install:fail" {
             self.is_downloaded = Some(false);
             true
         } else {
@@ -37,6 +39,10 @@ impl Firmware for MockFirmware {
         self.install_res = self.is_downloaded.take();
         assert!(self.install_res.is_some());
     }
+    async fn firmware_verify(&mut self, signing_certificate: String, signature: String) {
+        log::info!("firmware verify certificate: {}", signing_certificate);
+        self.verify_res = Some(signature == "valid_signature");
+    }
     fn poll_firmware_download(&mut self, _cx: &mut Context<'_>) -> Poll<bool> {
         if let Some(res) = self.download_res.take() {
             Poll::Ready(res)
@@ -51,4 +57,11 @@ impl Firmware for MockFirmware {
             Poll::Pending
         }
     }
+    fn poll_firmware_verify(&mut self, _cx: &mut Context<'_>) -> Poll<bool> {
+        if let Some(res) = self.verify_res.take() {
+            Poll::Ready(res)
+        } else {
+            Poll::Pending
+        }
+    }
 }
@@ -1,32 +1,59 @@
 use std::{
+    collections::HashMap,
     future::Future,
     pin::Pin,
+    sync::{Arc, Mutex},
     task::{Context, Poll},
     time::Duration,
 };
 
+use chrono::{DateTime, Utc};
 use flume::{r#async::RecvFut, unbounded, Sender};
 use futures::FutureExt;
-use rocpp_client::v16::{Hardware, HardwareEvent, MeterData, MeterDataType};
+use rocpp_client::v16::{ChargingLimit, Hardware, HardwareEvent, MeterData, MeterDataType};
 use rocpp_core::v16::types::ChargePointStatus;
 use tokio_util::sync::CancellationToken;
 
+pub type ChargingLimits = Arc<Mutex<HashMap<usize, ChargingLimit>>>;
+
+#[derive(Debug)]
+pub struct ConnectorLockState {
+    pub cable_lock: bool,
+    pub unlocked: Vec<usize>,
+}
+
+pub type ConnectorLocks = Arc<Mutex<ConnectorLockState>>;
+
 pub struct MockHardware {
     hard_reset_token: CancellationToken,
+    charging_limits: ChargingLimits,
+    connector_locks: ConnectorLocks,
     ev_rx_fut: RecvFut<'static, HardwareEvent>,
     cancel_fut: Option<Pin<Box<dyn Future<Output = ()> + 'static>>>,
 }
 
 impl MockHardware {
-    pub fn new(token: CancellationToken) -> (Self, Sender<HardwareEvent>) {
+    pub fn new(
+        token: CancellationToken,
+    ) -> (Self, Sender<HardwareEvent>, ChargingLimits, ConnectorLocks) {
         let (ev_tx, ev_rx) = unbounded();
+        let charging_limits = ChargingLimits::default();
+        // fixed cables by default
+        let connector_locks = Arc::new(Mutex::new(ConnectorLockState {
+            cable_lock: false,
+            unlocked: Vec::new(),
+        }));
         (
             Self {
                 hard_reset_token: token,
+                charging_limits: charging_limits.clone(),
+                connector_locks: connector_locks.clone(),
                 ev_rx_fut: ev_rx.into_recv_async(),
                 cancel_fut: None,
             },
             ev_tx,
+            charging_limits,
+            connector_locks,
         )
     }
 }
@@ -35,6 +62,9 @@ impl Hardware for MockHardware {
     async fn get_boot_time(&self) -> u64 {
         uptime_lib::get().unwrap().as_micros() as u64
     }
+    async fn get_rtc_time(&self) -> Option<DateTime<Utc>> {
+        None
+    }
     async fn hard_reset(&mut self) {
         let token = self.hard_reset_token.clone();
         tokio::task::spawn_local(async move {
@@ -59,11 +89,34 @@ impl Hardware for MockHardware {
             connector_id,
             kind
         );
-        return Some(MeterData {
+        Some(MeterData {
             value: String::from("10"),
             location: None,
             unit: None,
-        });
+        })
+    }
+    async fn set_charging_limit(&mut self, connector_id: usize, limit: ChargingLimit) {
+        log::info!(
+            "charging limit for connector: {}, limit: {:?}",
+            connector_id,
+            limit
+        );
+        self.charging_limits
+            .lock()
+            .unwrap()
+            .insert(connector_id, limit);
+    }
+    async fn has_cable_lock(&self, _connector_id: usize) -> bool {
+        self.connector_locks.lock().unwrap().cable_lock
+    }
+    async fn unlock_connector(&mut self, connector_id: usize) -> bool {
+        log::info!("unlock connector: {}", connector_id);
+        self.connector_locks
+            .lock()
+            .unwrap()
+            .unlocked
+            .push(connector_id);
+        true
     }
     fn poll_hardware_events(&mut self, cx: &mut Context<'_>) -> Poll<HardwareEvent> {
         match self.ev_rx_fut.poll_unpin(cx) {
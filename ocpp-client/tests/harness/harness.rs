@@ -3,19 +3,26 @@ use std::{path::PathBuf, sync::Once};
 use flume::Sender;
 use log::LevelFilter;
 use rocpp_client::v16::{
-    ChargePoint, ChargePointConfig, ChargePointInterfaceFacade, HardwareEvent, KeyValueStore,
+    ChargePoint, ChargePointCommand, ChargePointConfig, ChargePointInterfaceFacade, HardwareEvent,
+    KeyValueStore, SmartChargingConfig,
+};
+use rocpp_core::v16::messages::{
+    boot_notification::BootNotificationRequest, data_transfer::DataTransferRequest,
 };
-use rocpp_core::v16::messages::boot_notification::BootNotificationRequest;
 use tokio_util::sync::CancellationToken;
 
 use crate::harness::event::{Event, SeccEvents};
 
 use super::{
-    database::{FileDatabase, MockDatabase},
+    commands::{CommandResults, MockCommands},
+    crypto::{mock_certificate, MockCrypto},
+    data_transfer::{DataTransferResponses, MockDataTransfer},
+    database::{DataLosses, FileDatabase, MockDatabase},
     diagnostics::MockDiagnostics,
     event::{event_bus, EventRx},
     firmware::MockFirmware,
-    hardware::MockHardware,
+    hardware::{ChargingLimits, ConnectorLocks, MockHardware},
+    observer::{MockObserver, StateChanges},
     timers::TokioTimerServie,
     ws::{MockWs, MockWsHandle},
 };
@@ -25,6 +32,14 @@ pub struct CpHarness {
     pub ws_handle: MockWsHandle,
     pub bus_rx: EventRx,
     pub hardware_tx: Sender<HardwareEvent>,
+    pub charging_limits: ChargingLimits,
+    pub connector_locks: ConnectorLocks,
+    pub data_transfer_tx: Sender<DataTransferRequest>,
+    pub data_transfer_responses: DataTransferResponses,
+    pub command_tx: Sender<ChargePointCommand>,
+    pub command_results: CommandResults,
+    pub state_changes: StateChanges,
+    pub data_losses: DataLosses,
     pub stop_token: CancellationToken,
 }
 
@@ -61,9 +76,34 @@ fn default_ocpp_configs() -> Vec<(String, String)> {
         ("GetConfigurationMaxKeys", "10"),
         (
             "SupportedFeatureProfiles",
-            "Core,FirmwareManagement,LocalAuthListManagement,Reservation,RemoteTrigger",
+            "Core,FirmwareManagement,LocalAuthListManagement,Reservation,SmartCharging,RemoteTrigger",
         ),
         ("UnlockConnectorOnEVSideDisconnect", "false"),
+        ("ChargeProfileMaxStackLevel", "10"),
+        ("ChargingScheduleAllowedChargingRateUnit", "Current,Power"),
+        ("ChargingScheduleMaxPeriods", "24"),
+        ("MaxChargingProfilesInstalled", "10"),
+        ("ReconnectBackoffInitialInterval", "0"),
+        ("ReconnectBackoffMaxInterval", "0"),
+        ("SecurityProfile", "0"),
+        ("AuthorizationKey", ""),
+        ("SecurityProfileFallbackTimeout", "10"),
+        ("CertificateSignedMaxChainSize", "10000"),
+        ("CertificateStoreMaxLength", "10"),
+        ("CpoName", ""),
+        ("SecurityEventNotificationEnabled", "false"),
+        ("MeterValuesBatchMaxLength", "1"),
+        ("TransactionQueueMaxLength", "0"),
+        ("TransactionQueueDropPolicy", "ThinSampled"),
+        (
+            "CallDeliveryPolicy",
+            "Authorize=Retry:1,StatusNotification=UntilDelivered,DiagnosticsStatusNotification=UntilDelivered,FirmwareStatusNotification=UntilDelivered",
+        ),
+        ("CallActionTimeouts", ""),
+        ("AdaptiveCallTimeout", "false"),
+        ("WebSocketPingInterval", "0"),
+        ("WebSocketPongTimeout", "5"),
+        ("HeartbeatMaxSkipInterval", "86400"),
     ];
     configs
         .into_iter()
@@ -79,12 +119,16 @@ pub fn get_cms_url() -> String {
     String::from("temp")
 }
 
+pub fn get_ca_certificate() -> String {
+    mock_certificate("mock")
+}
+
 fn get_boot_info() -> BootNotificationRequest {
     BootNotificationRequest {
         charge_box_serial_number: None,
-        charge_point_model: format!("CP"),
+        charge_point_model: "CP".to_string(),
         charge_point_serial_number: None,
-        charge_point_vendor: format!("IDK"),
+        charge_point_vendor: "IDK".to_string(),
         firmware_version: None,
         iccid: None,
         imsi: None,
@@ -110,6 +154,7 @@ impl CpHarness {
         test_timeout: u64,
         override_defualt_configs: Vec<(&str, &str)>,
         db: D,
+        data_losses: DataLosses,
         clear_db: bool,
     ) -> Self {
         let stop_token = CancellationToken::new();
@@ -118,7 +163,12 @@ impl CpHarness {
         let diagnostics = MockDiagnostics::new();
         let firmware = MockFirmware::new();
         let timer = TokioTimerServie::new();
-        let (hardware, hardware_tx) = MockHardware::new(stop_token.clone());
+        let (hardware, hardware_tx, charging_limits, connector_locks) =
+            MockHardware::new(stop_token.clone());
+        let (data_transfer, data_transfer_tx, data_transfer_responses) = MockDataTransfer::new();
+        let (commands, command_tx, command_results) = MockCommands::new();
+        let (observer, state_changes) = MockObserver::new();
+        let crypto = MockCrypto::new();
         let mut default_ocpp_configs = default_ocpp_configs();
         for (key, value) in override_defualt_configs {
             if let Some(config) = default_ocpp_configs.iter_mut().find(|x| x.0 == key) {
@@ -134,21 +184,43 @@ impl CpHarness {
             default_ocpp_configs,
             clear_db,
             seed: rand::random(),
+            ca_certificate: Some(get_ca_certificate()),
+            client_certificate: None,
+            smart_charging: SmartChargingConfig::default(),
         };
-        let interface =
-            ChargePointInterfaceFacade::new(db, diagnostics, firmware, timer, hardware, ws);
+        let interface = ChargePointInterfaceFacade::new(
+            db,
+            diagnostics,
+            firmware,
+            timer,
+            hardware,
+            ws,
+            data_transfer,
+            commands,
+            observer,
+            crypto,
+        );
         tokio::task::spawn_local(async move {
             let res = tokio::task::spawn_local(ChargePoint::run(interface, configs)).await;
-            let event = res
-                .is_ok()
-                .then(|| SeccEvents::HardReset)
-                .unwrap_or(SeccEvents::Crashed);
+            let event = if res.is_ok() {
+                SeccEvents::HardReset
+            } else {
+                SeccEvents::Crashed
+            };
             tx.push(Event::Secc(event));
         });
         Self {
             ws_handle,
             bus_rx: rx,
             hardware_tx,
+            charging_limits,
+            connector_locks,
+            data_transfer_tx,
+            data_transfer_responses,
+            command_tx,
+            command_results,
+            state_changes,
+            data_losses,
             stop_token,
         }
     }
@@ -159,18 +231,21 @@ impl CpHarness {
         clear_db: bool,
     ) -> Self {
         init_logger();
+        let data_losses = DataLosses::default();
         if let Some(dir) = db_dir {
             Self::new_helper(
                 timeout,
                 override_defualt_configs,
-                FileDatabase::new(dir),
+                FileDatabase::new(dir, data_losses.clone()),
+                data_losses,
                 clear_db,
             )
         } else {
             Self::new_helper(
                 timeout,
                 override_defualt_configs,
-                MockDatabase::new(),
+                MockDatabase::new(data_losses.clone()),
+                data_losses,
                 clear_db,
             )
         }
@@ -1,8 +1,14 @@
+pub mod charging_profile;
+pub mod commands;
+pub mod crypto;
+pub mod data_transfer;
 pub mod database;
 pub mod diagnostics;
 pub mod event;
 pub mod firmware;
 pub mod hardware;
+#[allow(clippy::module_inception)]
 pub mod harness;
+pub mod observer;
 pub mod timers;
 pub mod ws;
@@ -0,0 +1,27 @@
+use std::sync::{Arc, Mutex};
+
+use rocpp_client::v16::{ChargePointSnapshot, Observer, StateChange};
+
+pub type StateChanges = Arc<Mutex<Vec<(StateChange, ChargePointSnapshot)>>>;
+
+pub struct MockObserver {
+    changes: StateChanges,
+}
+
+impl MockObserver {
+    pub fn new() -> (Self, StateChanges) {
+        let changes = StateChanges::default();
+        (
+            Self {
+                changes: changes.clone(),
+            },
+            changes,
+        )
+    }
+}
+
+impl Observer for MockObserver {
+    async fn state_changed(&mut self, change: StateChange, snapshot: ChargePointSnapshot) {
+        self.changes.lock().unwrap().push((change, snapshot));
+    }
+}
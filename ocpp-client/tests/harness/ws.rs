@@ -1,14 +1,14 @@
 use std::{
     collections::VecDeque,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering},
         Arc, Mutex,
     },
     task::{Context, Poll},
 };
 
 use futures::task::AtomicWaker;
-use rocpp_client::v16::{Websocket, WsEvent};
+use rocpp_client::v16::{Websocket, WsConnectOptions, WsEvent};
 use rocpp_core::{
     format::{
         frame::{Call, CallError, CallResult},
@@ -31,6 +31,12 @@ struct Inner {
     pending_conn: Mutex<Option<String>>,
     connected_res: Mutex<Option<()>>,
     disconnect_res: Mutex<Option<()>>,
+    connect_failed_res: Mutex<Option<()>>,
+    pong_res: Mutex<Option<()>>,
+    answers_pings: AtomicBool,
+    max_security_profile: AtomicU8,
+    refused_connects: AtomicU32,
+    connect_attempts: Mutex<Vec<WsConnectOptions>>,
 }
 
 pub struct MockWs {
@@ -50,6 +56,12 @@ impl MockWs {
             pending_conn: Mutex::new(None),
             connected_res: Mutex::new(None),
             disconnect_res: Mutex::new(None),
+            connect_failed_res: Mutex::new(None),
+            pong_res: Mutex::new(None),
+            answers_pings: AtomicBool::new(true),
+            max_security_profile: AtomicU8::new(3),
+            refused_connects: AtomicU32::new(0),
+            connect_attempts: Default::default(),
         });
         (
             Self {
@@ -61,8 +73,24 @@ impl MockWs {
 }
 
 impl Websocket for MockWs {
-    async fn ws_connect(&mut self, url: String) {
+    async fn ws_connect(&mut self, options: WsConnectOptions) {
         log::info!("connecting.......");
+        self.inner
+            .connect_attempts
+            .lock()
+            .unwrap()
+            .push(options.clone());
+        if !self.inner.accepts(&options) {
+            log::info!(
+                "security profile {} rejected by server",
+                options.security_profile
+            );
+            self.inner.connect_failed_res.lock().unwrap().replace(());
+            self.inner.waker.wake();
+            return;
+        }
+        let url = options.url;
+        // a down server leaves the attempt pending until it is back up
         if !self.inner.is_server_up.load(Ordering::Acquire) {
             self.inner.pending_conn.lock().unwrap().replace(url);
             log::info!("server down, will connected when up");
@@ -115,9 +143,22 @@ impl Websocket for MockWs {
         if self.inner.disconnect_res.lock().unwrap().take().is_some() {
             return Poll::Ready(WsEvent::Disconnected);
         }
+        if self
+            .inner
+            .connect_failed_res
+            .lock()
+            .unwrap()
+            .take()
+            .is_some()
+        {
+            return Poll::Ready(WsEvent::ConnectFailed);
+        }
         if let Some(msg) = self.inner.inbox.lock().unwrap().pop_front() {
             return Poll::Ready(WsEvent::Msg(msg));
         }
+        if self.inner.pong_res.lock().unwrap().take().is_some() {
+            return Poll::Ready(WsEvent::Pong);
+        }
         self.inner.waker.register(cx.waker());
         if self.inner.connected_res.lock().unwrap().take().is_some() {
             return Poll::Ready(WsEvent::Connected);
@@ -125,20 +166,69 @@ impl Websocket for MockWs {
         if self.inner.disconnect_res.lock().unwrap().take().is_some() {
             return Poll::Ready(WsEvent::Disconnected);
         }
+        if self
+            .inner
+            .connect_failed_res
+            .lock()
+            .unwrap()
+            .take()
+            .is_some()
+        {
+            return Poll::Ready(WsEvent::ConnectFailed);
+        }
         if let Some(msg) = self.inner.inbox.lock().unwrap().pop_front() {
             return Poll::Ready(WsEvent::Msg(msg));
         }
+        if self.inner.pong_res.lock().unwrap().take().is_some() {
+            return Poll::Ready(WsEvent::Pong);
+        }
         Poll::Pending
     }
 
     async fn ws_close(&mut self) {
+        self.inner.pending_conn.lock().unwrap().take();
+        self.inner.connect_failed_res.lock().unwrap().take();
+        if !self.inner.is_connected.load(Ordering::Acquire) {
+            return;
+        }
         self.inner.is_connected.store(false, Ordering::Release);
         self.inner.inbox.lock().unwrap().clear();
+        self.inner.pong_res.lock().unwrap().take();
         self.inner
             .event_tx
             .push(Event::Connection(ConnectionEvents::Disconnected));
         self.inner.disconnect_res.lock().unwrap().replace(());
     }
+
+    // pings are not reported on the event bus, only their pongs are observable
+    async fn ws_ping(&mut self) {
+        if self.inner.is_connected.load(Ordering::Acquire)
+            && self.inner.answers_pings.load(Ordering::Acquire)
+        {
+            self.inner.pong_res.lock().unwrap().replace(());
+            self.inner.waker.wake();
+        }
+    }
+}
+
+impl Inner {
+    // stand-in for the TLS handshake and basic auth check of a real CSMS
+    fn accepts(&self, options: &WsConnectOptions) -> bool {
+        let refused = self
+            .refused_connects
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+            .is_ok();
+        let has_credentials = match options.security_profile {
+            0 => true,
+            1 => options.basic_auth.is_some(),
+            2 => options.basic_auth.is_some() && options.ca_certificate.is_some(),
+            3 => options.ca_certificate.is_some() && options.client_certificate.is_some(),
+            _ => false,
+        };
+        !refused
+            && has_credentials
+            && options.security_profile <= self.max_security_profile.load(Ordering::Acquire)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -151,6 +241,7 @@ impl MockWsHandle {
         assert!(self.inner.is_server_up.load(Ordering::Acquire));
         self.inner.is_connected.store(false, Ordering::Release);
         self.inner.inbox.lock().unwrap().clear();
+        self.inner.pong_res.lock().unwrap().take();
         self.inner.is_server_up.store(false, Ordering::Release);
         self.inner
             .event_tx
@@ -170,6 +261,38 @@ impl MockWsHandle {
             self.inner.waker.wake();
         }
     }
+    pub fn set_max_security_profile(&self, security_profile: u8) {
+        self.inner
+            .max_security_profile
+            .store(security_profile, Ordering::Release);
+    }
+    // the next connect attempts fail as if the server refused them
+    pub fn refuse_connects(&self, count: u32) {
+        self.inner.refused_connects.store(count, Ordering::Release);
+    }
+    // a half open connection, pings go out but no pong ever comes back
+    pub fn set_answers_pings(&self, answers_pings: bool) {
+        self.inner
+            .answers_pings
+            .store(answers_pings, Ordering::Release);
+    }
+    pub fn connect_attempts(&self) -> Vec<WsConnectOptions> {
+        self.inner.connect_attempts.lock().unwrap().clone()
+    }
+    // delivers an event as is, whatever the connection state
+    pub fn push_raw_event(&self, event: WsEvent) {
+        match event {
+            WsEvent::Connected => self.inner.connected_res.lock().unwrap().replace(()),
+            WsEvent::Disconnected => self.inner.disconnect_res.lock().unwrap().replace(()),
+            WsEvent::ConnectFailed => self.inner.connect_failed_res.lock().unwrap().replace(()),
+            WsEvent::Msg(msg) => {
+                self.inner.inbox.lock().unwrap().push_back(msg);
+                None
+            }
+            WsEvent::Pong => self.inner.pong_res.lock().unwrap().replace(()),
+        };
+        self.inner.waker.wake();
+    }
     pub fn inject(&self, msg: String) {
         assert!(self.inner.is_connected.load(Ordering::Acquire));
         self.inner.inbox.lock().unwrap().push_back(msg);
@@ -68,29 +68,29 @@ impl State for AnyOrder {
                     self.todo.remove(index);
                     self.index = None;
                     if self.todo.is_empty() {
-                        return self.next.take().map_or(StepResult::Done, StepResult::Next);
+                        self.next.take().map_or(StepResult::Done, StepResult::Next)
                     } else {
-                        return StepResult::Pending;
+                        StepResult::Pending
                     }
                 }
-                StepResult::Pending => return StepResult::Pending,
+                StepResult::Pending => StepResult::Pending,
                 StepResult::Next(nxt) => {
                     self.todo[index] = nxt;
-                    return StepResult::Pending;
+                    StepResult::Pending
                 }
-                StepResult::Fail(e) => return StepResult::Fail(e),
+                StepResult::Fail(e) => StepResult::Fail(e),
             }
         } else {
             match self.get_index(ev, d, h) {
                 Ok(index) => {
                     self.index = index;
                     if self.todo.is_empty() {
-                        return self.next.take().map_or(StepResult::Done, StepResult::Next);
+                        self.next.take().map_or(StepResult::Done, StepResult::Next)
                     } else {
-                        return StepResult::Pending;
+                        StepResult::Pending
                     }
                 }
-                Err(e) => return StepResult::Fail(e),
+                Err(e) => StepResult::Fail(e),
             }
         }
     }
@@ -106,12 +106,11 @@ impl State for AnyOrder {
                     self.todo.remove(index);
                     self.index = None;
                     if self.todo.is_empty() {
-                        return self
-                            .next
+                        self.next
                             .take()
-                            .map_or(StartResult::Done, StartResult::Next);
+                            .map_or(StartResult::Done, StartResult::Next)
                     } else {
-                        return StartResult::Break;
+                        StartResult::Break
                     }
                 }
                 StartResult::Break => StartResult::Break,
@@ -4,9 +4,11 @@ use crate::harness::{event::Event, harness::CpHarness};
 
 use super::step::{StartResult, State, StepResult};
 
+type HarnessOp = Box<dyn FnOnce(&mut CpHarness) + Send>;
+
 pub struct Operation {
     next: Option<Box<dyn State>>,
-    op: Option<Box<dyn FnOnce(&mut CpHarness) + Send>>,
+    op: Option<HarnessOp>,
 }
 
 impl Operation {
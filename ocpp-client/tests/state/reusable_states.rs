@@ -74,13 +74,17 @@ impl BootState {
         Self {
             conn: ConnectionState::default(),
             interval: 1000,
-            expected_connector_state: expected_connector_state,
+            expected_connector_state,
         }
     }
     pub fn with_state(mut self, connector_id: usize, state: ChargePointStatus) -> Self {
         self.expected_connector_state[connector_id - 1] = state;
         self
     }
+    pub fn with_interval(mut self, interval: u64) -> Self {
+        self.interval = interval;
+        self
+    }
 }
 
 pub fn get_all_connector_states(expected_connector_state: Vec<ChargePointStatus>) -> TestChain {
@@ -342,7 +346,7 @@ where
                     keys.len()
                 ));
             }
-            let cfg = keys.into_iter().next().unwrap();
+            let cfg = keys.iter().next().unwrap();
             if !resp
                 .unknown_key
                 .as_ref()
@@ -449,18 +453,16 @@ pub fn validate_meter_values(
             .as_ref()
             .map(|t| *t == Location::Ev)
             .unwrap_or(false)
-        {
-            if value
+            && value
                 .measurand
                 .as_ref()
                 .map(|t| *t != Measurand::SoC)
                 .unwrap_or(true)
-            {
-                return Err(anyhow!(
-                    "location = EV found while measurand != SoC in {}",
-                    step
-                ));
-            }
+        {
+            return Err(anyhow!(
+                "location = EV found while measurand != SoC in {}",
+                step
+            ));
         }
         received_measurands.push(MeterDataType {
             measurand: value
@@ -1,4 +1,11 @@
-use rocpp_client::v16::{HardwareEvent, SeccState};
+use rocpp_client::v16::{
+    ChargePointCommand, ChargePointSnapshot, ChargingLimit, CommandResult, HardwareEvent,
+    SeccState, StateChange, WsConnectOptions,
+};
+use rocpp_core::v16::{
+    messages::data_transfer::DataTransferRequest, protocol_error::ProtocolError,
+    types::DataTransferStatus,
+};
 use serde::Serialize;
 use std::{path::PathBuf, time::Duration};
 
@@ -239,6 +246,12 @@ impl TestChain {
         });
         self.combine(2)
     }
+    pub fn respond_error(mut self, err: ProtocolError) -> Self {
+        self = self.operation(|t| {
+            t.ws_handle.send_response::<()>(Err(err));
+        });
+        self.combine(2)
+    }
     pub fn respond_with_now<T: Serialize + Send + WithNowTimestamp + 'static>(
         mut self,
         payload: T,
@@ -292,6 +305,78 @@ impl TestChain {
                 .unwrap();
         })
     }
+    pub fn check_charging_limit(self, connector_id: usize, limit: ChargingLimit) -> Self {
+        self.operation(move |t| {
+            let applied = t
+                .charging_limits
+                .lock()
+                .unwrap()
+                .get(&(connector_id - 1))
+                .cloned();
+            assert_eq!(applied, Some(limit));
+        })
+    }
+    pub fn with_cable_locks(self) -> Self {
+        self.operation(|t| {
+            t.connector_locks.lock().unwrap().cable_lock = true;
+        })
+    }
+    pub fn check_unlocked(self, connector_ids: Vec<usize>) -> Self {
+        self.operation(move |t| {
+            let unlocked = t.connector_locks.lock().unwrap().unlocked.clone();
+            let expected: Vec<usize> = connector_ids.into_iter().map(|f| f - 1).collect();
+            assert_eq!(unlocked, expected);
+        })
+    }
+    pub fn send_data_transfer(self, req: DataTransferRequest) -> Self {
+        self.operation(move |t| {
+            t.data_transfer_tx.send(req).unwrap();
+        })
+    }
+    pub fn check_data_transfer_responses(
+        self,
+        expected: Vec<Option<(DataTransferStatus, Option<String>)>>,
+    ) -> Self {
+        self.operation(move |t| {
+            let responses: Vec<_> = t
+                .data_transfer_responses
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|f| f.as_ref().map(|f| (f.status.clone(), f.data.clone())))
+                .collect();
+            assert_eq!(responses, expected);
+        })
+    }
+    pub fn send_command(self, command: ChargePointCommand) -> Self {
+        self.operation(move |t| {
+            t.command_tx.send(command).unwrap();
+        })
+    }
+    pub fn check_command_results(self, expected: Vec<(ChargePointCommand, CommandResult)>) -> Self {
+        self.operation(move |t| {
+            let results = t.command_results.lock().unwrap().clone();
+            assert_eq!(results, expected);
+        })
+    }
+    pub fn check_state_changes(self, expected: Vec<StateChange>) -> Self {
+        self.operation(move |t| {
+            let changes: Vec<_> = t
+                .state_changes
+                .lock()
+                .unwrap()
+                .drain(..)
+                .map(|f| f.0)
+                .collect();
+            assert_eq!(changes, expected);
+        })
+    }
+    pub fn check_snapshot(self, cb: impl FnOnce(&ChargePointSnapshot) + Send + 'static) -> Self {
+        self.operation(move |t| {
+            let changes = t.state_changes.lock().unwrap();
+            cb(&changes.last().unwrap().1);
+        })
+    }
     pub fn spawn_new(
         self,
         timeout: u64,
@@ -318,6 +403,19 @@ impl TestChain {
             t.ws_handle.restore_connection();
         })
     }
+    pub fn with_max_security_profile(self, security_profile: u8) -> Self {
+        self.operation(move |t| {
+            t.ws_handle.set_max_security_profile(security_profile);
+        })
+    }
+    pub fn check_connect_attempts(
+        self,
+        cb: impl FnOnce(&[WsConnectOptions]) + Send + 'static,
+    ) -> Self {
+        self.operation(move |t| {
+            cb(&t.ws_handle.connect_attempts());
+        })
+    }
 }
 
 #[macro_export]
@@ -350,6 +448,12 @@ macro_rules! test_chain {
         test_chain!($start.respond($payload) $(, $($rest)*)? )
     };
 
+    // respond_error(err)
+    ($start:expr,
+     respond_error($err:expr) $(, $($rest:tt)*)? ) => {
+        test_chain!($start.respond_error($err) $(, $($rest)*)? )
+    };
+
     // respond_with_now(payload)
     ($start:expr,
      respond_with_now($payload:expr) $(, $($rest:tt)*)? ) => {
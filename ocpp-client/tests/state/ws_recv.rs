@@ -16,8 +16,11 @@ pub enum AfterValidation {
     NextCustom(Box<dyn State>),
 }
 
+type WsMsgValidator<T> = Box<dyn FnOnce(&Result<T, ProtocolError>) -> AfterValidation + Send>;
+type FieldValidator<T> = Box<dyn FnOnce(&T) -> Option<String> + Send>;
+
 pub struct AwaitWsMsg<T> {
-    validator: Option<Box<dyn FnOnce(&Result<T, ProtocolError>) -> AfterValidation + Send>>,
+    validator: Option<WsMsgValidator<T>>,
     next: Option<Box<dyn State>>,
 }
 
@@ -69,10 +72,10 @@ impl<T: serde::de::DeserializeOwned> State for AwaitWsMsg<T> {
                 AfterValidation::NextCustom(next) => StepResult::Next(next),
             }
         } else {
-            return StepResult::Fail(anyhow!(
+            StepResult::Fail(anyhow!(
                 "expected WS msg {}",
                 std::any::type_name::<T>().rsplit("::").next().unwrap()
-            ));
+            ))
         }
     }
 }
@@ -80,7 +83,7 @@ impl<T: serde::de::DeserializeOwned> State for AwaitWsMsg<T> {
 pub struct AwaitWsMsgBuilder<T> {
     current: TestChain,
     res: AwaitWsMsg<T>,
-    default_validations: Vec<Box<dyn FnOnce(&T) -> Option<String> + Send>>,
+    default_validations: Vec<FieldValidator<T>>,
 }
 
 impl<T: serde::de::DeserializeOwned + 'static> AwaitWsMsgBuilder<T> {
@@ -139,4 +142,14 @@ impl TestChain {
             default_validations: Vec::new(),
         }
     }
+    // the call is answered with a CALLERROR carrying the given error code
+    pub fn await_call_error<T: serde::de::DeserializeOwned + 'static>(
+        self,
+        err: ProtocolError,
+    ) -> TestChain {
+        self.await_ws_msg::<T>().done_custom(move |t| match t {
+            Err(e) if *e == err => AfterValidation::NextDefault,
+            _ => AfterValidation::Failed(anyhow!("expected a {:?} CALLERROR", err)),
+        })
+    }
 }
@@ -6,6 +6,16 @@ pub enum GenericError {
     ParsingError,
 }
 
+impl GenericError {
+    // lost on the way, the same message may still get through
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            GenericError::TimeOut | GenericError::Offline | GenericError::General
+        )
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub enum OcppError<T> {
     Protocol(T),
@@ -0,0 +1,308 @@
+use alloc::{vec, vec::Vec};
+use chrono::{DateTime, TimeDelta, Utc};
+
+use super::types::{
+    ChargingProfile, ChargingProfileKindType, ChargingProfilePurposeType, ChargingRateUnitType,
+    ChargingSchedule, ChargingSchedulePeriod, RecurrencyKindType,
+};
+
+/// What a composite schedule is asked for, the fields of GetCompositeSchedule plus
+/// what the charge point knows about the connector.
+#[derive(Clone, Debug)]
+pub struct CompositeScheduleParams {
+    /// 0 composes the charge point as a whole, i.e. only ChargePointMaxProfiles.
+    pub connector_id: usize,
+    pub start: DateTime<Utc>,
+    /// Length of the schedule in seconds from start.
+    pub duration: i32,
+    /// Unit the limits are converted to.
+    pub charging_rate_unit: ChargingRateUnitType,
+    /// Start of the transaction running on the connector, anchors Relative profiles.
+    pub transaction_start: Option<DateTime<Utc>>,
+    /// TxProfiles bound to another transaction are ignored.
+    pub transaction_id: Option<i32>,
+    /// Used together with the number of phases to convert between A and W.
+    pub supply_voltage: f32,
+    /// Assumed for periods that leave number_phases unset.
+    pub default_number_phases: i32,
+}
+
+/// A period of the composed schedule, a new one starts whenever the limit or the
+/// number of phases changes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompositePeriod {
+    /// Seconds from the start of the schedule.
+    pub start_period: i32,
+    /// None when no profile limits the connector during this period.
+    pub limit: Option<f32>,
+    pub number_phases: Option<i32>,
+}
+
+/// Composes the profiles installed on the charge point, paired with their
+/// connector id, into the periods limiting `params.connector_id`.
+///
+/// Within each purpose the highest stack level in effect wins. TxProfile goes
+/// before TxDefaultProfile, a connector specific TxDefaultProfile before one on
+/// connector 0, and ChargePointMaxProfile caps the result. Recurring and
+/// Relative profiles are anchored per period, and `valid_from`/`valid_to` cut
+/// profiles off at any point in the window.
+pub fn compose_periods<'a, I>(profiles: I, params: &CompositeScheduleParams) -> Vec<CompositePeriod>
+where
+    I: IntoIterator<Item = (usize, &'a ChargingProfile)>,
+{
+    let profiles: Vec<_> = profiles.into_iter().collect();
+    let end = params.start + TimeDelta::seconds(params.duration as i64);
+    let mut breakpoints = vec![params.start];
+    for (_, profile) in profiles.iter() {
+        breakpoints.extend(profile.valid_from);
+        breakpoints.extend(profile.valid_to);
+        let schedule = &profile.charging_schedule;
+        for start in schedule_starts(profile, params, end) {
+            breakpoints.push(start);
+            for period in schedule.charging_schedule_period.iter() {
+                breakpoints.push(start + TimeDelta::seconds(period.start_period as i64));
+            }
+            if let Some(duration) = schedule.duration {
+                breakpoints.push(start + TimeDelta::seconds(duration as i64));
+            }
+        }
+    }
+    breakpoints.retain(|t| *t >= params.start && *t < end);
+    breakpoints.sort();
+    breakpoints.dedup();
+
+    let mut res: Vec<CompositePeriod> = Vec::new();
+    for t in breakpoints {
+        let (limit, number_phases) = composite_limit_at(&profiles, params, t)
+            .map(|(limit, number_phases)| (Some(limit), number_phases))
+            .unwrap_or((None, None));
+        if res
+            .last()
+            .is_some_and(|last| last.limit == limit && last.number_phases == number_phases)
+        {
+            continue;
+        }
+        res.push(CompositePeriod {
+            start_period: (t - params.start).num_seconds() as i32,
+            limit,
+            number_phases,
+        });
+    }
+    res
+}
+
+/// The GetCompositeSchedule answer built from [`compose_periods`], periods no
+/// profile limits get `default_limit`.
+pub fn composite_schedule<'a, I>(
+    profiles: I,
+    params: &CompositeScheduleParams,
+    default_limit: f32,
+) -> ChargingSchedule
+where
+    I: IntoIterator<Item = (usize, &'a ChargingProfile)>,
+{
+    let mut periods: Vec<ChargingSchedulePeriod> = Vec::new();
+    for period in compose_periods(profiles, params) {
+        let limit = period.limit.unwrap_or(default_limit);
+        if periods
+            .last()
+            .is_some_and(|last| last.limit == limit && last.number_phases == period.number_phases)
+        {
+            continue;
+        }
+        periods.push(ChargingSchedulePeriod {
+            start_period: period.start_period,
+            limit,
+            number_phases: period.number_phases,
+        });
+    }
+    ChargingSchedule {
+        duration: Some(params.duration),
+        start_schedule: Some(params.start),
+        charging_rate_unit: params.charging_rate_unit.clone(),
+        charging_schedule_period: periods,
+        min_charging_rate: None,
+    }
+}
+
+fn composite_limit_at(
+    profiles: &[(usize, &ChargingProfile)],
+    params: &CompositeScheduleParams,
+    t: DateTime<Utc>,
+) -> Option<(f32, Option<i32>)> {
+    let max = stack_limit_at(
+        profiles,
+        params,
+        t,
+        0,
+        ChargingProfilePurposeType::ChargePointMaxProfile,
+    );
+    let tx = if params.connector_id == 0 {
+        None
+    } else {
+        stack_limit_at(
+            profiles,
+            params,
+            t,
+            params.connector_id,
+            ChargingProfilePurposeType::TxProfile,
+        )
+        .or_else(|| {
+            stack_limit_at(
+                profiles,
+                params,
+                t,
+                params.connector_id,
+                ChargingProfilePurposeType::TxDefaultProfile,
+            )
+        })
+        .or_else(|| {
+            stack_limit_at(
+                profiles,
+                params,
+                t,
+                0,
+                ChargingProfilePurposeType::TxDefaultProfile,
+            )
+        })
+    };
+    match (max, tx) {
+        (Some(max), Some(tx)) => Some(if tx.0 < max.0 { tx } else { max }),
+        (max, tx) => max.or(tx),
+    }
+}
+
+fn stack_limit_at(
+    profiles: &[(usize, &ChargingProfile)],
+    params: &CompositeScheduleParams,
+    t: DateTime<Utc>,
+    connector_id: usize,
+    purpose: ChargingProfilePurposeType,
+) -> Option<(f32, Option<i32>)> {
+    profiles
+        .iter()
+        .filter(|(id, profile)| {
+            *id == connector_id
+                && profile.charging_profile_purpose == purpose
+                && (purpose != ChargingProfilePurposeType::TxProfile
+                    || (params.transaction_start.is_some()
+                        && profile
+                            .transaction_id
+                            .is_none_or(|id| Some(id) == params.transaction_id)))
+        })
+        .filter_map(|(_, profile)| {
+            profile_limit_at(profile, params, t).map(|limit| (profile.stack_level, limit))
+        })
+        .max_by_key(|(stack_level, _)| *stack_level)
+        .map(|(_, limit)| limit)
+}
+
+fn profile_limit_at(
+    profile: &ChargingProfile,
+    params: &CompositeScheduleParams,
+    t: DateTime<Utc>,
+) -> Option<(f32, Option<i32>)> {
+    if profile.valid_from.is_some_and(|valid_from| t < valid_from)
+        || profile.valid_to.is_some_and(|valid_to| t >= valid_to)
+    {
+        return None;
+    }
+    let schedule = &profile.charging_schedule;
+    let start = schedule_start_at(profile, params, t)?;
+    let offset = (t - start).num_seconds();
+    if offset < 0
+        || schedule
+            .duration
+            .is_some_and(|duration| offset >= duration as i64)
+    {
+        return None;
+    }
+    let period = schedule
+        .charging_schedule_period
+        .iter()
+        .rev()
+        .find(|period| period.start_period as i64 <= offset)?;
+    let limit = convert_limit(
+        period.limit,
+        &schedule.charging_rate_unit,
+        &params.charging_rate_unit,
+        period.number_phases.unwrap_or(params.default_number_phases),
+        params.supply_voltage,
+    );
+    Some((limit, period.number_phases))
+}
+
+fn schedule_start_at(
+    profile: &ChargingProfile,
+    params: &CompositeScheduleParams,
+    t: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    match profile.charging_profile_kind {
+        ChargingProfileKindType::Absolute => Some(
+            profile
+                .charging_schedule
+                .start_schedule
+                .unwrap_or(params.start),
+        ),
+        ChargingProfileKindType::Relative => Some(params.transaction_start.unwrap_or(params.start)),
+        ChargingProfileKindType::Recurring => {
+            let base = profile.charging_schedule.start_schedule?;
+            let length = recurrency_length(profile.recurrency_kind.as_ref()?);
+            if t < base {
+                return None;
+            }
+            let cycles = (t - base).num_seconds() / length.num_seconds();
+            Some(base + length * cycles as i32)
+        }
+    }
+}
+
+fn schedule_starts(
+    profile: &ChargingProfile,
+    params: &CompositeScheduleParams,
+    end: DateTime<Utc>,
+) -> Vec<DateTime<Utc>> {
+    match profile.charging_profile_kind {
+        ChargingProfileKindType::Recurring => {
+            let (Some(base), Some(kind)) = (
+                profile.charging_schedule.start_schedule,
+                profile.recurrency_kind.as_ref(),
+            ) else {
+                return Vec::new();
+            };
+            let length = recurrency_length(kind);
+            let mut res = Vec::new();
+            let mut start = schedule_start_at(profile, params, params.start).unwrap_or(base);
+            while start < end {
+                res.push(start);
+                start += length;
+            }
+            res
+        }
+        _ => schedule_start_at(profile, params, params.start)
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn recurrency_length(kind: &RecurrencyKindType) -> TimeDelta {
+    match kind {
+        RecurrencyKindType::Daily => TimeDelta::days(1),
+        RecurrencyKindType::Weekly => TimeDelta::weeks(1),
+    }
+}
+
+fn convert_limit(
+    limit: f32,
+    from: &ChargingRateUnitType,
+    to: &ChargingRateUnitType,
+    number_phases: i32,
+    supply_voltage: f32,
+) -> f32 {
+    let factor = supply_voltage * number_phases as f32;
+    match (from, to) {
+        (ChargingRateUnitType::A, ChargingRateUnitType::W) => limit * factor,
+        (ChargingRateUnitType::W, ChargingRateUnitType::A) if factor > 0.0 => limit / factor,
+        _ => limit,
+    }
+}
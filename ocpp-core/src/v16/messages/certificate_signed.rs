@@ -0,0 +1,15 @@
+use alloc::string::String;
+
+use super::super::types::CertificateSignedStatus;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateSignedRequest {
+    pub certificate_chain: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateSignedResponse {
+    pub status: CertificateSignedStatus,
+}
@@ -0,0 +1,13 @@
+use super::super::types::{CertificateHashData, DeleteCertificateStatus};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteCertificateRequest {
+    pub certificate_hash_data: CertificateHashData,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteCertificateResponse {
+    pub status: DeleteCertificateStatus,
+}
@@ -0,0 +1,15 @@
+use super::super::types::{ExtendedMessageTrigger, TriggerMessageStatus};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendedTriggerMessageRequest {
+    pub requested_message: ExtendedMessageTrigger,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connector_id: Option<usize>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendedTriggerMessageResponse {
+    pub status: TriggerMessageStatus,
+}
@@ -0,0 +1,17 @@
+use alloc::vec::Vec;
+
+use super::super::types::{CertificateHashData, CertificateUse, GetInstalledCertificateStatus};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetInstalledCertificateIdsRequest {
+    pub certificate_type: CertificateUse,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetInstalledCertificateIdsResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate_hash_data: Option<Vec<CertificateHashData>>,
+    pub status: GetInstalledCertificateStatus,
+}
@@ -0,0 +1,23 @@
+use alloc::string::String;
+
+use super::super::types::{LogParameters, LogStatus, LogType};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetLogRequest {
+    pub log: LogParameters,
+    pub log_type: LogType,
+    pub request_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_interval: Option<u64>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetLogResponse {
+    pub status: LogStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+}
@@ -0,0 +1,16 @@
+use alloc::string::String;
+
+use super::super::types::{CertificateStatus, CertificateUse};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallCertificateRequest {
+    pub certificate_type: CertificateUse,
+    pub certificate: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallCertificateResponse {
+    pub status: CertificateStatus,
+}
@@ -0,0 +1,13 @@
+use super::super::types::UploadLogStatus;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LogStatusNotificationRequest {
+    pub status: UploadLogStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<i32>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LogStatusNotificationResponse {}
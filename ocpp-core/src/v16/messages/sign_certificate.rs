@@ -0,0 +1,15 @@
+use alloc::string::String;
+
+use super::super::types::GenericStatus;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SignCertificateRequest {
+    pub csr: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SignCertificateResponse {
+    pub status: GenericStatus,
+}
@@ -0,0 +1,13 @@
+use super::super::types::SignedFirmwareStatus;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedFirmwareStatusNotificationRequest {
+    pub status: SignedFirmwareStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<i32>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedFirmwareStatusNotificationResponse {}
@@ -0,0 +1,18 @@
+use super::super::types::{Firmware, UpdateFirmwareStatus};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedUpdateFirmwareRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_interval: Option<u64>,
+    pub request_id: i32,
+    pub firmware: Firmware,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedUpdateFirmwareResponse {
+    pub status: UpdateFirmwareStatus,
+}
@@ -1,3 +1,4 @@
+pub mod composite_schedule;
 pub mod messages;
 pub mod protocol_error;
 pub mod types;
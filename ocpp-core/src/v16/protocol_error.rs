@@ -1,3 +1,5 @@
+use crate::format::error::OcppError;
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
 pub enum ProtocolError {
     InternalError,
@@ -29,3 +31,20 @@ impl core::fmt::Display for ProtocolError {
         write!(f, "{s}")
     }
 }
+
+impl ProtocolError {
+    // the receiver failed while handling the message, the others are answers
+    // to the message itself and come back the same on every try
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ProtocolError::InternalError)
+    }
+}
+
+impl OcppError<ProtocolError> {
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            OcppError::Protocol(e) => e.is_retryable(),
+            OcppError::Other(e) => e.is_retryable(),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use alloc::string::String;
+
+use super::HashAlgorithm;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateHashData {
+    pub hash_algorithm: HashAlgorithm,
+    pub issuer_name_hash: String,
+    pub issuer_key_hash: String,
+    pub serial_number: String,
+}
@@ -0,0 +1,6 @@
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub enum CertificateStatus {
+    Accepted,
+    Failed,
+    Rejected,
+}
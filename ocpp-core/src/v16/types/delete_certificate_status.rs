@@ -0,0 +1,6 @@
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub enum DeleteCertificateStatus {
+    Accepted,
+    Failed,
+    NotFound,
+}
@@ -0,0 +1,13 @@
+use alloc::string::String;
+use chrono::{DateTime, Utc};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Firmware {
+    pub location: String,
+    pub retrieve_date_time: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install_date_time: Option<DateTime<Utc>>,
+    pub signing_certificate: String,
+    pub signature: String,
+}
@@ -0,0 +1,12 @@
+use alloc::string::String;
+use chrono::{DateTime, Utc};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LogParameters {
+    pub remote_location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_timestamp: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_timestamp: Option<DateTime<Utc>>,
+}
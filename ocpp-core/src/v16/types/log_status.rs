@@ -0,0 +1,6 @@
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub enum LogStatus {
+    Accepted,
+    Rejected,
+    AcceptedCanceled,
+}
@@ -0,0 +1,5 @@
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub enum LogType {
+    DiagnosticsLog,
+    SecurityLog,
+}
@@ -3,6 +3,10 @@ mod authorization_status;
 mod availability_status;
 mod availability_type;
 mod cancel_reservation_status;
+mod certificate_hash_data;
+mod certificate_signed_status;
+mod certificate_status;
+mod certificate_use;
 mod charge_point_error_code;
 mod charge_point_status;
 mod charging_profile;
@@ -16,12 +20,21 @@ mod clear_cache_status;
 mod clear_charging_profile_status;
 mod configuration_status;
 mod data_transfer_status;
+mod delete_certificate_status;
 mod diagnostics_status;
+mod extended_message_trigger;
+mod firmware;
 mod firmware_status;
+mod generic_status;
 mod get_composite_schedule_status;
+mod get_installed_certificate_status;
+mod hash_algorithm;
 mod id_tag_info;
 mod key_value;
 mod location;
+mod log_parameters;
+mod log_status;
+mod log_type;
 mod measurand;
 mod message_trigger;
 mod meter_value;
@@ -35,11 +48,14 @@ mod reservation_status;
 mod reset_status;
 mod reset_type;
 mod sampled_value;
+mod signed_firmware_status;
 mod trigger_message_status;
 mod unit_of_measure;
 mod unlock_status;
+mod update_firmware_status;
 mod update_status;
 mod update_type;
+mod upload_log_status;
 mod value_format;
 
 pub use self::authorization_data::AuthorizationData;
@@ -47,6 +63,10 @@ pub use self::authorization_status::AuthorizationStatus;
 pub use self::availability_status::AvailabilityStatus;
 pub use self::availability_type::AvailabilityType;
 pub use self::cancel_reservation_status::CancelReservationStatus;
+pub use self::certificate_hash_data::CertificateHashData;
+pub use self::certificate_signed_status::CertificateSignedStatus;
+pub use self::certificate_status::CertificateStatus;
+pub use self::certificate_use::CertificateUse;
 pub use self::charge_point_error_code::ChargePointErrorCode;
 pub use self::charge_point_status::ChargePointStatus;
 pub use self::charging_profile::ChargingProfile;
@@ -60,12 +80,21 @@ pub use self::clear_cache_status::ClearCacheStatus;
 pub use self::clear_charging_profile_status::ClearChargingProfileStatus;
 pub use self::configuration_status::ConfigurationStatus;
 pub use self::data_transfer_status::DataTransferStatus;
+pub use self::delete_certificate_status::DeleteCertificateStatus;
 pub use self::diagnostics_status::DiagnosticsStatus;
+pub use self::extended_message_trigger::ExtendedMessageTrigger;
+pub use self::firmware::Firmware;
 pub use self::firmware_status::FirmwareStatus;
+pub use self::generic_status::GenericStatus;
 pub use self::get_composite_schedule_status::GetCompositeScheduleStatus;
+pub use self::get_installed_certificate_status::GetInstalledCertificateStatus;
+pub use self::hash_algorithm::HashAlgorithm;
 pub use self::id_tag_info::IdTagInfo;
 pub use self::key_value::KeyValue;
 pub use self::location::Location;
+pub use self::log_parameters::LogParameters;
+pub use self::log_status::LogStatus;
+pub use self::log_type::LogType;
 pub use self::measurand::Measurand;
 pub use self::message_trigger::MessageTrigger;
 pub use self::meter_value::MeterValue;
@@ -79,9 +108,12 @@ pub use self::reservation_status::ReservationStatus;
 pub use self::reset_status::ResetStatus;
 pub use self::reset_type::ResetType;
 pub use self::sampled_value::SampledValue;
+pub use self::signed_firmware_status::SignedFirmwareStatus;
 pub use self::trigger_message_status::TriggerMessageStatus;
 pub use self::unit_of_measure::UnitOfMeasure;
 pub use self::unlock_status::UnlockStatus;
+pub use self::update_firmware_status::UpdateFirmwareStatus;
 pub use self::update_status::UpdateStatus;
 pub use self::update_type::UpdateType;
+pub use self::upload_log_status::UploadLogStatus;
 pub use self::value_format::ValueFormat;
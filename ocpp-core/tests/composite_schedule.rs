@@ -0,0 +1,321 @@
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use rocpp_core::v16::{
+    composite_schedule::{
+        compose_periods, composite_schedule, CompositePeriod, CompositeScheduleParams,
+    },
+    types::{
+        ChargingProfile, ChargingProfileKindType, ChargingProfilePurposeType, ChargingRateUnitType,
+        ChargingSchedule, ChargingSchedulePeriod, RecurrencyKindType,
+    },
+};
+
+const HOUR: i32 = 3600;
+const DAY: i32 = 24 * HOUR;
+
+// a monday
+fn base() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+}
+
+fn at(seconds: i32) -> DateTime<Utc> {
+    base() + TimeDelta::seconds(seconds as i64)
+}
+
+fn params(connector_id: usize, start: i32, duration: i32) -> CompositeScheduleParams {
+    CompositeScheduleParams {
+        connector_id,
+        start: at(start),
+        duration,
+        charging_rate_unit: ChargingRateUnitType::A,
+        transaction_start: None,
+        transaction_id: None,
+        supply_voltage: 230.0,
+        default_number_phases: 3,
+    }
+}
+
+fn profile(
+    stack_level: u32,
+    purpose: ChargingProfilePurposeType,
+    kind: ChargingProfileKindType,
+    periods: &[(i32, f32)],
+) -> ChargingProfile {
+    ChargingProfile {
+        charging_profile_id: stack_level as i32,
+        transaction_id: None,
+        stack_level,
+        charging_profile_purpose: purpose,
+        charging_profile_kind: kind.clone(),
+        recurrency_kind: None,
+        valid_from: None,
+        valid_to: None,
+        charging_schedule: ChargingSchedule {
+            duration: None,
+            start_schedule: match kind {
+                ChargingProfileKindType::Relative => None,
+                _ => Some(base()),
+            },
+            charging_rate_unit: ChargingRateUnitType::A,
+            charging_schedule_period: periods
+                .iter()
+                .map(|(start_period, limit)| ChargingSchedulePeriod {
+                    start_period: *start_period,
+                    limit: *limit,
+                    number_phases: None,
+                })
+                .collect(),
+            min_charging_rate: None,
+        },
+    }
+}
+
+fn tx_default(stack_level: u32, limit: f32) -> ChargingProfile {
+    profile(
+        stack_level,
+        ChargingProfilePurposeType::TxDefaultProfile,
+        ChargingProfileKindType::Absolute,
+        &[(0, limit)],
+    )
+}
+
+fn period(start_period: i32, limit: Option<f32>) -> CompositePeriod {
+    CompositePeriod {
+        start_period,
+        limit,
+        number_phases: None,
+    }
+}
+
+fn limits(
+    profiles: &[(usize, &ChargingProfile)],
+    params: &CompositeScheduleParams,
+) -> Vec<CompositePeriod> {
+    compose_periods(profiles.iter().copied(), params)
+}
+
+#[test]
+fn recurring_daily() {
+    let mut daily = profile(
+        0,
+        ChargingProfilePurposeType::TxDefaultProfile,
+        ChargingProfileKindType::Recurring,
+        &[(0, 10.0), (8 * HOUR, 20.0)],
+    );
+    daily.recurrency_kind = Some(RecurrencyKindType::Daily);
+    let profiles = [(1, &daily)];
+
+    assert_eq!(
+        limits(&profiles, &params(1, DAY + 6 * HOUR, 6 * HOUR)),
+        vec![period(0, Some(10.0)), period(2 * HOUR, Some(20.0))]
+    );
+    // from 20:00 over midnight, the schedule starts over at the day boundary
+    assert_eq!(
+        limits(&profiles, &params(1, DAY + 20 * HOUR, 14 * HOUR)),
+        vec![
+            period(0, Some(20.0)),
+            period(4 * HOUR, Some(10.0)),
+            period(12 * HOUR, Some(20.0)),
+        ]
+    );
+    // nothing before the first recurrence
+    assert_eq!(
+        limits(&profiles, &params(1, -2 * HOUR, 4 * HOUR)),
+        vec![period(0, None), period(2 * HOUR, Some(10.0))]
+    );
+}
+
+#[test]
+fn recurring_weekly() {
+    let mut weekly = profile(
+        0,
+        ChargingProfilePurposeType::TxDefaultProfile,
+        ChargingProfileKindType::Recurring,
+        &[(0, 16.0), (DAY, 32.0)],
+    );
+    weekly.recurrency_kind = Some(RecurrencyKindType::Weekly);
+    weekly.charging_schedule.duration = Some(2 * DAY);
+    let profiles = [(1, &weekly)];
+
+    // from sunday over the week boundary
+    assert_eq!(
+        limits(&profiles, &params(1, 6 * DAY, 4 * DAY)),
+        vec![
+            period(0, None),
+            period(DAY, Some(16.0)),
+            period(2 * DAY, Some(32.0)),
+            period(3 * DAY, None),
+        ]
+    );
+    // a later week repeats the first
+    assert_eq!(
+        limits(&profiles, &params(1, 21 * DAY, 2 * DAY)),
+        vec![period(0, Some(16.0)), period(DAY, Some(32.0))]
+    );
+}
+
+#[test]
+fn relative() {
+    let relative = profile(
+        0,
+        ChargingProfilePurposeType::TxDefaultProfile,
+        ChargingProfileKindType::Relative,
+        &[(0, 10.0), (HOUR, 20.0)],
+    );
+    let profiles = [(1, &relative)];
+
+    // without a transaction it runs from the start of the schedule
+    assert_eq!(
+        limits(&profiles, &params(1, 0, 2 * HOUR)),
+        vec![period(0, Some(10.0)), period(HOUR, Some(20.0))]
+    );
+    let mut params = params(1, 0, 2 * HOUR);
+    params.transaction_start = Some(at(-HOUR / 2));
+    assert_eq!(
+        limits(&profiles, &params),
+        vec![period(0, Some(10.0)), period(HOUR / 2, Some(20.0))]
+    );
+}
+
+#[test]
+fn validity() {
+    let mut limited = tx_default(0, 10.0);
+    limited.valid_from = Some(at(600));
+    limited.valid_to = Some(at(1200));
+    let profiles = [(1, &limited)];
+
+    assert_eq!(
+        limits(&profiles, &params(1, 0, 1800)),
+        vec![period(0, None), period(600, Some(10.0)), period(1200, None)]
+    );
+    // a lower stack level takes over where the higher one is not valid
+    let fallback = tx_default(0, 20.0);
+    let mut limited = tx_default(1, 10.0);
+    limited.valid_to = Some(at(600));
+    let profiles = [(1, &fallback), (1, &limited)];
+    assert_eq!(
+        limits(&profiles, &params(1, 0, 1800)),
+        vec![period(0, Some(10.0)), period(600, Some(20.0))]
+    );
+}
+
+#[test]
+fn stack_precedence() {
+    let station_default = tx_default(5, 20.0);
+    let connector_default = tx_default(0, 18.0);
+    let connector_default_high = tx_default(1, 25.0);
+    let mut tx = profile(
+        0,
+        ChargingProfilePurposeType::TxProfile,
+        ChargingProfileKindType::Absolute,
+        &[(0, 12.0)],
+    );
+    tx.transaction_id = Some(7);
+    let max = profile(
+        0,
+        ChargingProfilePurposeType::ChargePointMaxProfile,
+        ChargingProfileKindType::Absolute,
+        &[(0, 22.0)],
+    );
+    let idle = params(1, 0, HOUR);
+    let mut charging = params(1, 0, HOUR);
+    charging.transaction_start = Some(at(0));
+    charging.transaction_id = Some(7);
+    let limit = |profiles: &[(usize, &ChargingProfile)], params: &CompositeScheduleParams| {
+        limits(profiles, params)[0].limit
+    };
+
+    // connector 0 TxDefaultProfile applies when the connector has none
+    assert_eq!(limit(&[(0, &station_default)], &idle), Some(20.0));
+    // a connector specific one wins regardless of stack level
+    assert_eq!(
+        limit(&[(0, &station_default), (1, &connector_default)], &idle),
+        Some(18.0)
+    );
+    // the highest stack level wins within a purpose, not the lowest limit
+    assert_eq!(
+        limit(
+            &[(1, &connector_default), (1, &connector_default_high)],
+            &idle
+        ),
+        Some(25.0)
+    );
+    // TxProfile only with its transaction running
+    let profiles = [(1, &connector_default), (1, &tx)];
+    assert_eq!(limit(&profiles, &idle), Some(18.0));
+    assert_eq!(limit(&profiles, &charging), Some(12.0));
+    let mut other = charging.clone();
+    other.transaction_id = Some(8);
+    assert_eq!(limit(&profiles, &other), Some(18.0));
+    // ChargePointMaxProfile caps whatever applies
+    assert_eq!(
+        limit(&[(1, &connector_default_high), (0, &max)], &idle),
+        Some(22.0)
+    );
+    assert_eq!(limit(&[(1, &tx), (0, &max)], &charging), Some(12.0));
+    // connector 0 composes the charge point, only its maximum counts
+    assert_eq!(
+        limit(&[(0, &station_default), (0, &max)], &params(0, 0, HOUR)),
+        Some(22.0)
+    );
+    assert_eq!(limit(&[(0, &station_default)], &params(0, 0, HOUR)), None);
+}
+
+#[test]
+fn unit_conversion() {
+    let amps = tx_default(0, 16.0);
+    let mut single_phase = tx_default(0, 16.0);
+    single_phase.charging_schedule.charging_schedule_period[0].number_phases = Some(1);
+    let mut watts = tx_default(0, 11040.0);
+    watts.charging_schedule.charging_rate_unit = ChargingRateUnitType::W;
+    let mut in_watts = params(1, 0, HOUR);
+    in_watts.charging_rate_unit = ChargingRateUnitType::W;
+
+    // number_phases None falls back to default_number_phases
+    assert_eq!(
+        limits(&[(1, &amps)], &in_watts),
+        vec![period(0, Some(16.0 * 230.0 * 3.0))]
+    );
+    assert_eq!(
+        limits(&[(1, &single_phase)], &in_watts),
+        vec![CompositePeriod {
+            start_period: 0,
+            limit: Some(16.0 * 230.0),
+            number_phases: Some(1),
+        }]
+    );
+    assert_eq!(
+        limits(&[(1, &watts)], &params(1, 0, HOUR)),
+        vec![period(0, Some(16.0))]
+    );
+    assert_eq!(
+        limits(&[(1, &watts)], &in_watts),
+        vec![period(0, Some(11040.0))]
+    );
+    // mixed units are compared after conversion
+    let mut max = tx_default(0, 10.0);
+    max.charging_profile_purpose = ChargingProfilePurposeType::ChargePointMaxProfile;
+    assert_eq!(
+        limits(&[(1, &watts), (0, &max)], &in_watts),
+        vec![period(0, Some(10.0 * 230.0 * 3.0))]
+    );
+}
+
+#[test]
+fn default_limit() {
+    let mut limited = tx_default(0, 10.0);
+    limited.charging_schedule.duration = Some(600);
+    let mut later = tx_default(0, 32.0);
+    later.valid_from = Some(at(1200));
+
+    let schedule = composite_schedule([(1, &limited), (1, &later)], &params(1, 0, 1800), 32.0);
+    assert_eq!(schedule.duration, Some(1800));
+    assert_eq!(schedule.start_schedule, Some(at(0)));
+    assert_eq!(schedule.charging_rate_unit, ChargingRateUnitType::A);
+    // the gap gets the default, which merges with the profile that follows
+    let periods: Vec<_> = schedule
+        .charging_schedule_period
+        .iter()
+        .map(|t| (t.start_period, t.limit, t.number_phases))
+        .collect();
+    assert_eq!(periods, vec![(0, 10.0, None), (600, 32.0, None)]);
+}
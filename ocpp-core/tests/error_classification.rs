@@ -0,0 +1,25 @@
+use rocpp_core::{
+    format::error::{GenericError, OcppError},
+    v16::protocol_error::ProtocolError,
+};
+
+#[test]
+fn protocol_errors() {
+    assert!(OcppError::Protocol(ProtocolError::InternalError).is_retryable());
+    for e in [
+        ProtocolError::FormationViolation,
+        ProtocolError::NotImplemented,
+        ProtocolError::NotSupported,
+        ProtocolError::SecurityError,
+        ProtocolError::PropertyConstraintViolation,
+    ] {
+        assert!(!OcppError::Protocol(e).is_retryable());
+    }
+}
+
+#[test]
+fn generic_errors() {
+    assert!(OcppError::<ProtocolError>::Other(GenericError::TimeOut).is_retryable());
+    assert!(OcppError::<ProtocolError>::Other(GenericError::Offline).is_retryable());
+    assert!(!OcppError::<ProtocolError>::Other(GenericError::ParsingError).is_retryable());
+}
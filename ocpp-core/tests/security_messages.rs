@@ -0,0 +1,161 @@
+use rocpp_core::v16::{
+    messages::{
+        certificate_signed::{CertificateSignedRequest, CertificateSignedResponse},
+        delete_certificate::{DeleteCertificateRequest, DeleteCertificateResponse},
+        extended_trigger_message::{ExtendedTriggerMessageRequest, ExtendedTriggerMessageResponse},
+        get_installed_certificate_ids::{
+            GetInstalledCertificateIdsRequest, GetInstalledCertificateIdsResponse,
+        },
+        get_log::{GetLogRequest, GetLogResponse},
+        install_certificate::{InstallCertificateRequest, InstallCertificateResponse},
+        log_status_notification::{LogStatusNotificationRequest, LogStatusNotificationResponse},
+        security_event_notification::{
+            SecurityEventNotificationRequest, SecurityEventNotificationResponse,
+        },
+        sign_certificate::{SignCertificateRequest, SignCertificateResponse},
+        signed_firmware_status_notification::{
+            SignedFirmwareStatusNotificationRequest, SignedFirmwareStatusNotificationResponse,
+        },
+        signed_update_firmware::{SignedUpdateFirmwareRequest, SignedUpdateFirmwareResponse},
+    },
+    types::{
+        CertificateUse, ExtendedMessageTrigger, HashAlgorithm, LogType, SignedFirmwareStatus,
+        UploadLogStatus,
+    },
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+fn round_trip<T: Serialize + DeserializeOwned>(raw: &str) -> T {
+    let expected: Value = serde_json::from_str(raw).unwrap();
+    let decoded: T = serde_json::from_value(expected.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&decoded).unwrap(), expected);
+    decoded
+}
+
+#[test]
+fn sign_certificate() {
+    let req: SignCertificateRequest =
+        round_trip(r#"{"csr":"-----BEGIN CERTIFICATE REQUEST-----"}"#);
+    assert_eq!(req.csr, "-----BEGIN CERTIFICATE REQUEST-----");
+    round_trip::<SignCertificateResponse>(r#"{"status":"Rejected"}"#);
+}
+
+#[test]
+fn certificate_signed() {
+    round_trip::<CertificateSignedRequest>(r#"{"certificateChain":"-----BEGIN CERTIFICATE-----"}"#);
+    round_trip::<CertificateSignedResponse>(r#"{"status":"Accepted"}"#);
+}
+
+#[test]
+fn install_certificate() {
+    let req: InstallCertificateRequest = round_trip(
+        r#"{"certificateType":"ManufacturerRootCertificate","certificate":"-----BEGIN CERTIFICATE-----"}"#,
+    );
+    assert_eq!(
+        req.certificate_type,
+        CertificateUse::ManufacturerRootCertificate
+    );
+    round_trip::<InstallCertificateResponse>(r#"{"status":"Failed"}"#);
+}
+
+#[test]
+fn delete_certificate() {
+    let req: DeleteCertificateRequest = round_trip(
+        r#"{"certificateHashData":{"hashAlgorithm":"SHA384","issuerNameHash":"a1","issuerKeyHash":"b2","serialNumber":"01"}}"#,
+    );
+    assert_eq!(
+        req.certificate_hash_data.hash_algorithm,
+        HashAlgorithm::Sha384
+    );
+    round_trip::<DeleteCertificateResponse>(r#"{"status":"NotFound"}"#);
+}
+
+#[test]
+fn get_installed_certificate_ids() {
+    round_trip::<GetInstalledCertificateIdsRequest>(
+        r#"{"certificateType":"CentralSystemRootCertificate"}"#,
+    );
+    let res: GetInstalledCertificateIdsResponse = round_trip(
+        r#"{"status":"Accepted","certificateHashData":[{"hashAlgorithm":"SHA256","issuerNameHash":"a1","issuerKeyHash":"b2","serialNumber":"01"}]}"#,
+    );
+    assert_eq!(res.certificate_hash_data.unwrap().len(), 1);
+    round_trip::<GetInstalledCertificateIdsResponse>(r#"{"status":"NotFound"}"#);
+}
+
+#[test]
+fn security_event_notification() {
+    let req: SecurityEventNotificationRequest = round_trip(
+        r#"{"type":"FirmwareUpdated","timestamp":"2024-01-01T10:00:00Z","techInfo":"1.0.1"}"#,
+    );
+    assert_eq!(req.kind, "FirmwareUpdated");
+    round_trip::<SecurityEventNotificationRequest>(
+        r#"{"type":"StartupOfTheDevice","timestamp":"2024-01-01T10:00:00Z"}"#,
+    );
+    round_trip::<SecurityEventNotificationResponse>(r#"{}"#);
+}
+
+#[test]
+fn signed_update_firmware() {
+    let req: SignedUpdateFirmwareRequest = round_trip(
+        r#"{"retries":3,"retryInterval":60,"requestId":7,"firmware":{"location":"https://example.com/fw.bin","retrieveDateTime":"2024-01-01T10:00:00Z","installDateTime":"2024-01-01T12:00:00Z","signingCertificate":"-----BEGIN CERTIFICATE-----","signature":"c2lnbmF0dXJl"}}"#,
+    );
+    assert_eq!(req.request_id, 7);
+    round_trip::<SignedUpdateFirmwareRequest>(
+        r#"{"requestId":8,"firmware":{"location":"ftp://example.com/fw.bin","retrieveDateTime":"2024-01-01T10:00:00Z","signingCertificate":"cert","signature":"sig"}}"#,
+    );
+    round_trip::<SignedUpdateFirmwareResponse>(r#"{"status":"InvalidCertificate"}"#);
+}
+
+#[test]
+fn signed_firmware_status_notification() {
+    let req: SignedFirmwareStatusNotificationRequest =
+        round_trip(r#"{"status":"InvalidSignature","requestId":7}"#);
+    assert_eq!(req.status, SignedFirmwareStatus::InvalidSignature);
+    round_trip::<SignedFirmwareStatusNotificationRequest>(r#"{"status":"Idle"}"#);
+    round_trip::<SignedFirmwareStatusNotificationResponse>(r#"{}"#);
+}
+
+#[test]
+fn get_log() {
+    let req: GetLogRequest = round_trip(
+        r#"{"log":{"remoteLocation":"ftp://example.com/logs","oldestTimestamp":"2024-01-01T00:00:00Z","latestTimestamp":"2024-01-02T00:00:00Z"},"logType":"SecurityLog","requestId":1,"retries":2,"retryInterval":30}"#,
+    );
+    assert_eq!(req.log_type, LogType::SecurityLog);
+    round_trip::<GetLogRequest>(
+        r#"{"log":{"remoteLocation":"ftp://example.com/logs"},"logType":"DiagnosticsLog","requestId":2}"#,
+    );
+    round_trip::<GetLogResponse>(r#"{"status":"Accepted","filename":"security.log"}"#);
+    round_trip::<GetLogResponse>(r#"{"status":"AcceptedCanceled"}"#);
+}
+
+#[test]
+fn log_status_notification() {
+    let req: LogStatusNotificationRequest =
+        round_trip(r#"{"status":"UploadFailure","requestId":1}"#);
+    assert_eq!(req.status, UploadLogStatus::UploadFailure);
+    round_trip::<LogStatusNotificationResponse>(r#"{}"#);
+}
+
+#[test]
+fn extended_trigger_message() {
+    let req: ExtendedTriggerMessageRequest =
+        round_trip(r#"{"requestedMessage":"SignChargePointCertificate"}"#);
+    assert_eq!(
+        req.requested_message,
+        ExtendedMessageTrigger::SignChargePointCertificate
+    );
+    round_trip::<ExtendedTriggerMessageRequest>(
+        r#"{"requestedMessage":"StatusNotification","connectorId":1}"#,
+    );
+    round_trip::<ExtendedTriggerMessageResponse>(r#"{"status":"NotImplemented"}"#);
+}
+
+#[test]
+fn rejects_unknown_enum_values() {
+    assert!(serde_json::from_str::<SignCertificateResponse>(r#"{"status":"Maybe"}"#).is_err());
+    assert!(serde_json::from_str::<DeleteCertificateRequest>(
+        r#"{"certificateHashData":{"hashAlgorithm":"MD5","issuerNameHash":"a","issuerKeyHash":"b","serialNumber":"c"}}"#
+    )
+    .is_err());
+}